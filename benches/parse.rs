@@ -0,0 +1,83 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use odin::parser::{COMPUTE_UNIT_LOG_DISC, TxLogParser};
+use solana_transaction_status_client_types::{
+    UiTransactionStatusMeta, option_serializer::OptionSerializer,
+};
+
+// Recorded meta of an ATA create + token transfer
+const TOKEN_TRANSFER_META: &str = include_str!("../fixtures/token_transfer_meta.json");
+
+// Number of CPI invocations in the synthetic large transaction
+const LARGE_TX_INVOCATIONS: usize = 2_500;
+
+fn load_meta(json: &str) -> UiTransactionStatusMeta {
+    serde_json::from_str(json).expect("fixture should be a valid transaction meta")
+}
+
+/// Build a pathological transaction with thousands of invocations to stress
+/// the regex and allocation paths
+fn large_meta() -> UiTransactionStatusMeta {
+    let mut meta = load_meta(TOKEN_TRANSFER_META);
+    let token = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    let mut logs = Vec::with_capacity(LARGE_TX_INVOCATIONS * 4);
+
+    for i in 0..LARGE_TX_INVOCATIONS {
+        logs.push(format!("Program {} invoke [2]", token));
+        logs.push(format!("Program log: Instruction: Transfer #{}", i));
+        logs.push(format!("Program {} consumed 4645 of 1400000 compute units", token));
+        logs.push(format!("Program {} success", token));
+    }
+
+    meta.log_messages = OptionSerializer::Some(logs);
+    meta
+}
+
+fn count_logs(meta: &UiTransactionStatusMeta, cu_only: bool) -> u64 {
+    match meta.log_messages {
+        OptionSerializer::Some(ref logs) => logs
+            .iter()
+            .filter(|log| !cu_only || log.contains(COMPUTE_UNIT_LOG_DISC))
+            .count() as u64,
+        _ => 0,
+    }
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let fixtures = [
+        ("token_transfer", load_meta(TOKEN_TRANSFER_META)),
+        ("large_transaction", large_meta()),
+    ];
+
+    // Logs/sec with compute unit parsing disabled
+    let mut group = c.benchmark_group("parse_logs");
+    for (name, meta) in fixtures.iter() {
+        group.throughput(Throughput::Elements(count_logs(meta, false)));
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let mut parser = TxLogParser::new(String::new(), String::new(), None, false);
+                parser.parse_meta(black_box(meta)).unwrap();
+                parser
+            })
+        });
+    }
+    group.finish();
+
+    // CU-lines/sec with compute unit parsing enabled
+    let mut group = c.benchmark_group("parse_cu_lines");
+    for (name, meta) in fixtures.iter() {
+        group.throughput(Throughput::Elements(count_logs(meta, true)));
+        group.bench_function(*name, |b| {
+            b.iter(|| {
+                let mut parser = TxLogParser::new(String::new(), String::new(), None, true);
+                parser.parse_meta(black_box(meta)).unwrap();
+                parser
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse);
+criterion_main!(benches);
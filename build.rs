@@ -1,6 +1,14 @@
 // will act as a build script for cargo
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_prost_build::compile_protos("proto/odin.proto")?;
+    // Serde derives let clients capture and replay streams as NDJSON.
+    // `default` only goes on messages: serde rejects it on the enums prost
+    // generates for oneofs.
+    tonic_prost_build::configure()
+        .type_attribute(".odin", "#[derive(serde::Serialize, serde::Deserialize)]")
+        .message_attribute(".odin", "#[serde(default)]")
+        // A result is far bigger than a progress update
+        .boxed(".odin.BatchStreamMessage.message.result")
+        .compile_protos(&["proto/odin.proto"], &["proto"])?;
     Ok(())
 }
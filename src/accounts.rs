@@ -0,0 +1,165 @@
+use std::str::FromStr;
+
+use serde_json::Value;
+use solana_sdk::{message::MessageHeader, pubkey::Pubkey};
+
+/// The accounts a transaction locks, split by access
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountAccessSummary {
+    /// Write-locked accounts, signers first, in message order
+    pub writable: Vec<Pubkey>,
+    /// Read-locked accounts, in message order
+    pub readonly: Vec<Pubkey>,
+}
+
+impl AccountAccessSummary {
+    /// Split the accounts of the JSON form of a transaction. `jsonParsed`
+    /// account keys carry their own `writable` flag and include addresses
+    /// loaded from lookup tables; plain `json` keys are split with the
+    /// message header, and loaded addresses are added with `add_loaded`.
+    pub fn from_transaction_json(tx: &Value) -> Self {
+        let mut summary = AccountAccessSummary::default();
+
+        let Some(message) = tx.get("message") else {
+            return summary;
+        };
+        let keys = message
+            .get("accountKeys")
+            .and_then(|keys| keys.as_array())
+            .map(|keys| keys.as_slice())
+            .unwrap_or_default();
+
+        let header = message.get("header").map(|header| {
+            let count = |field: &str| {
+                header
+                    .get(field)
+                    .and_then(|count| count.as_u64())
+                    .unwrap_or(0) as u8
+            };
+            MessageHeader {
+                num_required_signatures: count("numRequiredSignatures"),
+                num_readonly_signed_accounts: count("numReadonlySignedAccounts"),
+                num_readonly_unsigned_accounts: count("numReadonlyUnsignedAccounts"),
+            }
+        });
+
+        for (idx, key) in keys.iter().enumerate() {
+            let (pubkey, writable) = match key {
+                Value::String(pubkey) => (
+                    pubkey.as_str(),
+                    header.is_some_and(|header| is_writable_index(&header, idx, keys.len())),
+                ),
+                key => {
+                    let Some(pubkey) = key.get("pubkey").and_then(|pubkey| pubkey.as_str()) else {
+                        continue;
+                    };
+                    let writable = key
+                        .get("writable")
+                        .and_then(|writable| writable.as_bool())
+                        .unwrap_or(false);
+                    (pubkey, writable)
+                }
+            };
+
+            if let Ok(pubkey) = Pubkey::from_str(pubkey) {
+                summary.push(pubkey, writable);
+            }
+        }
+
+        summary
+    }
+
+    /// Split the static account keys of a compiled message with its header
+    pub fn from_static_keys(header: &MessageHeader, keys: &[Pubkey]) -> Self {
+        let mut summary = AccountAccessSummary::default();
+        for (idx, key) in keys.iter().enumerate() {
+            summary.push(*key, is_writable_index(header, idx, keys.len()));
+        }
+        summary
+    }
+
+    /// Add the addresses a v0 transaction loaded from lookup tables (the
+    /// meta's `loadedAddresses`), skipping any already listed
+    pub fn add_loaded(&mut self, writable: &[String], readonly: &[String]) {
+        for (keys, is_writable) in [(writable, true), (readonly, false)] {
+            for key in keys {
+                if let Ok(pubkey) = Pubkey::from_str(key)
+                    && !self.writable.contains(&pubkey)
+                    && !self.readonly.contains(&pubkey)
+                {
+                    self.push(pubkey, is_writable);
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, pubkey: Pubkey, writable: bool) {
+        if writable {
+            self.writable.push(pubkey);
+        } else {
+            self.readonly.push(pubkey);
+        }
+    }
+}
+
+/// Whether the static key at `idx` is writable according to the header:
+/// signers come first, and each group ends with its readonly accounts
+fn is_writable_index(header: &MessageHeader, idx: usize, total: usize) -> bool {
+    let signers = header.num_required_signatures as usize;
+    if idx < signers {
+        idx < signers.saturating_sub(header.num_readonly_signed_accounts as usize)
+    } else {
+        idx < total.saturating_sub(header.num_readonly_unsigned_accounts as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_split_raw_keys_with_header_and_loaded_addresses() {
+        let payer = Pubkey::new_unique();
+        let destination = Pubkey::new_unique();
+        let system = Pubkey::default();
+        let loaded = Pubkey::new_unique();
+
+        let tx = json!({
+            "message": {
+                "header": {
+                    "numRequiredSignatures": 1,
+                    "numReadonlySignedAccounts": 0,
+                    "numReadonlyUnsignedAccounts": 1
+                },
+                "accountKeys": [payer.to_string(), destination.to_string(), system.to_string()]
+            }
+        });
+
+        let mut summary = AccountAccessSummary::from_transaction_json(&tx);
+        summary.add_loaded(&[], &[loaded.to_string(), system.to_string()]);
+
+        assert_eq!(summary.writable, vec![payer, destination]);
+        assert_eq!(summary.readonly, vec![system, loaded]);
+    }
+
+    #[test]
+    fn test_split_parsed_keys() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+
+        let tx = json!({
+            "message": {
+                "accountKeys": [
+                    {"pubkey": payer.to_string(), "writable": true, "signer": true, "source": "transaction"},
+                    {"pubkey": program.to_string(), "writable": false, "signer": false, "source": "lookupTable"}
+                ]
+            }
+        });
+
+        let summary = AccountAccessSummary::from_transaction_json(&tx);
+
+        assert_eq!(summary.writable, vec![payer]);
+        assert_eq!(summary.readonly, vec![program]);
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Additive-increase/multiplicative-decrease concurrency limit for one RPC.
+/// Each response faster than the latency target grows the limit by
+/// `1 / limit`, i.e. by one per limit's worth of responses; slower ones hold
+/// it; a rate-limited response halves it.
+#[derive(Debug, Clone)]
+pub struct Aimd {
+    min: usize,
+    max: usize,
+    latency_target: Duration,
+    limit: f64,
+    last_decrease: Option<Instant>,
+}
+
+impl Aimd {
+    /// Starts at `min`; `min` is at least 1 and `max` at least `min`
+    pub fn new(min: usize, max: usize, latency_target: Duration) -> Self {
+        let min = min.max(1);
+        Aimd {
+            min,
+            max: max.max(min),
+            latency_target,
+            limit: min as f64,
+            last_decrease: None,
+        }
+    }
+
+    /// Requests that may be in flight right now
+    pub fn limit(&self) -> usize {
+        (self.limit as usize).clamp(self.min, self.max)
+    }
+
+    /// A request was answered after `latency`
+    pub fn record_success(&mut self, latency: Duration) {
+        if latency <= self.latency_target {
+            self.limit = (self.limit + 1.0 / self.limit).min(self.max as f64);
+        }
+    }
+
+    /// A request sent at `started` was rate limited. Requests already in
+    /// flight at the last decrease don't decrease again, so one burst of
+    /// 429s halves the limit once.
+    pub fn record_rate_limited(&mut self, started: Instant) {
+        if self
+            .last_decrease
+            .is_some_and(|decreased| started <= decreased)
+        {
+            return;
+        }
+
+        self.limit = (self.limit / 2.0).max(self.min as f64);
+        self.last_decrease = Some(Instant::now());
+    }
+}
+
+/// Per-RPC-URL AIMD concurrency limits, shared by every batch request
+#[derive(Debug)]
+pub struct AdaptiveConcurrency {
+    min: usize,
+    max: usize,
+    latency_target: Duration,
+    limits: Mutex<HashMap<String, Aimd>>,
+}
+
+impl AdaptiveConcurrency {
+    pub fn new(min: usize, max: usize, latency_target: Duration) -> Self {
+        AdaptiveConcurrency {
+            min,
+            max,
+            latency_target,
+            limits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn limit(&self, rpc_url: &str) -> usize {
+        self.with(rpc_url, |aimd| aimd.limit())
+    }
+
+    pub fn record_success(&self, rpc_url: &str, latency: Duration) {
+        self.with(rpc_url, |aimd| aimd.record_success(latency));
+    }
+
+    pub fn record_rate_limited(&self, rpc_url: &str, started: Instant) {
+        let limit = self.with(rpc_url, |aimd| {
+            aimd.record_rate_limited(started);
+            aimd.limit()
+        });
+        println!(
+            "🐢 RPC {} rate limited, batch concurrency now {}",
+            rpc_url, limit
+        );
+    }
+
+    /// Current limit of every RPC URL seen so far
+    pub fn limits(&self) -> Vec<(String, usize)> {
+        let limits = self.limits.lock().unwrap();
+        let mut limits: Vec<_> = limits
+            .iter()
+            .map(|(rpc_url, aimd)| (rpc_url.clone(), aimd.limit()))
+            .collect();
+        limits.sort_by(|a, b| a.0.cmp(&b.0));
+        limits
+    }
+
+    fn with<T>(&self, rpc_url: &str, f: impl FnOnce(&mut Aimd) -> T) -> T {
+        let mut limits = self.limits.lock().unwrap();
+        let aimd = limits
+            .entry(rpc_url.to_string())
+            .or_insert_with(|| Aimd::new(self.min, self.max, self.latency_target));
+        f(aimd)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_additive_increase_multiplicative_decrease() {
+        let mut aimd = Aimd::new(1, 8, Duration::from_millis(100));
+        assert_eq!(aimd.limit(), 1);
+
+        // Slow responses hold the limit, fast ones grow it up to max
+        aimd.record_success(Duration::from_secs(1));
+        assert_eq!(aimd.limit(), 1);
+        aimd.record_success(Duration::from_millis(10));
+        assert_eq!(aimd.limit(), 2);
+        for _ in 0..100 {
+            aimd.record_success(Duration::from_millis(10));
+        }
+        assert_eq!(aimd.limit(), 8);
+
+        // A burst of 429s from requests sent together halves it once
+        let started = Instant::now();
+        aimd.record_rate_limited(started);
+        aimd.record_rate_limited(started);
+        assert_eq!(aimd.limit(), 4);
+
+        // Requests sent after the decrease halve it again, down to min
+        for later in 1..=3 {
+            aimd.record_rate_limited(started + Duration::from_secs(later));
+        }
+        assert_eq!(aimd.limit(), 1);
+    }
+}
@@ -0,0 +1,454 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use serde_json::{Map, Value, json};
+use sha2::{Digest, Sha256};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::decode::Reader;
+use crate::error::ParseError;
+use crate::invoke::LogEvent;
+
+/// An Anchor event decoded from a `Program data:` log line
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnchorEvent {
+    /// Event name as declared in the IDL
+    pub name: String,
+    /// Event fields, keyed by name
+    pub data: Value,
+}
+
+/// Length of Anchor's event discriminator
+pub const ANCHOR_DISCRIMINATOR_LEN: usize = 8;
+
+/// A `Program data:` payload split without an IDL: the leading
+/// discriminator bytes and whatever follows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawEvent {
+    /// Program that emitted the line, i.e. the top of the invoke stack
+    pub program_id: Option<Pubkey>,
+    pub discriminator: Vec<u8>,
+    pub payload: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+struct IdlEvent {
+    name: String,
+    discriminator: [u8; 8],
+    fields: Vec<Value>,
+}
+
+/// The parts of an Anchor IDL needed to decode its events. Both the legacy
+/// format and the 0.30+ format (explicit discriminators, event fields in
+/// `types`) are accepted.
+#[derive(Debug, Clone, Default)]
+pub struct Idl {
+    events: Vec<IdlEvent>,
+    types: HashMap<String, Value>,
+}
+
+impl Idl {
+    pub fn from_json(json: &str) -> Result<Self, ParseError> {
+        let idl: Value = serde_json::from_str(json)
+            .map_err(|e| ParseError::InvalidInput(format!("Invalid IDL: {}", e)))?;
+        Self::from_value(&idl)
+    }
+
+    pub fn from_value(idl: &Value) -> Result<Self, ParseError> {
+        let types: HashMap<String, Value> = idl
+            .get("types")
+            .and_then(|types| types.as_array())
+            .map(|types| {
+                types
+                    .iter()
+                    .filter_map(|ty| {
+                        Some((
+                            ty.get("name")?.as_str()?.to_string(),
+                            ty.get("type")?.clone(),
+                        ))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut events = Vec::new();
+        for event in idl
+            .get("events")
+            .and_then(|events| events.as_array())
+            .map(|events| events.as_slice())
+            .unwrap_or_default()
+        {
+            let name = event
+                .get("name")
+                .and_then(|name| name.as_str())
+                .ok_or_else(|| ParseError::InvalidInput("IDL event without a name".to_string()))?
+                .to_string();
+
+            let discriminator = match event.get("discriminator").and_then(|d| d.as_array()) {
+                Some(bytes) => bytes
+                    .iter()
+                    .map(|byte| byte.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+                    .collect::<Option<Vec<u8>>>()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| {
+                        ParseError::InvalidInput(format!(
+                            "Invalid discriminator for IDL event {}",
+                            name
+                        ))
+                    })?,
+                None => event_discriminator(&name),
+            };
+
+            // Legacy IDLs list the fields on the event, newer ones in `types`
+            let fields = event
+                .get("fields")
+                .or_else(|| types.get(&name)?.get("fields"))
+                .and_then(|fields| fields.as_array())
+                .cloned()
+                .unwrap_or_default();
+
+            events.push(IdlEvent {
+                name,
+                discriminator,
+                fields,
+            });
+        }
+
+        Ok(Idl { events, types })
+    }
+
+    /// Names of the events declared in the IDL
+    pub fn event_names(&self) -> Vec<&str> {
+        self.events
+            .iter()
+            .map(|event| event.name.as_str())
+            .collect()
+    }
+
+    /// Decode event data (discriminator included); `None` when it isn't one
+    /// of the IDL's events or doesn't match its layout
+    pub fn decode_event(&self, data: &[u8]) -> Option<AnchorEvent> {
+        let (discriminator, rest) = data.split_first_chunk::<8>()?;
+        let event = self
+            .events
+            .iter()
+            .find(|event| event.discriminator == *discriminator)?;
+
+        let mut reader = Reader(rest);
+        let data = self.read_fields(&event.fields, &mut reader)?;
+
+        Some(AnchorEvent {
+            name: event.name.clone(),
+            data,
+        })
+    }
+
+    /// Decode the events `program_id` emitted in a transaction's logs, in
+    /// order. `Program data:` lines of other programs, e.g. CPIs into it,
+    /// are skipped.
+    pub fn events_from_logs(&self, program_id: &str, logs: &[String]) -> Vec<AnchorEvent> {
+        let Ok(program_id) = Pubkey::from_str(program_id) else {
+            return Vec::new();
+        };
+
+        let mut stack: Vec<Pubkey> = Vec::new();
+        let mut events = Vec::new();
+
+        for log in logs {
+            match LogEvent::parse(log) {
+                LogEvent::Invoke { program_id, .. } => stack.push(program_id),
+                LogEvent::Success { .. } | LogEvent::Failed { .. } => {
+                    stack.pop();
+                }
+                LogEvent::Data(data) if stack.last() == Some(&program_id) => {
+                    if let Some(event) = BASE64_STANDARD
+                        .decode(data)
+                        .ok()
+                        .and_then(|data| self.decode_event(&data))
+                    {
+                        events.push(event);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        events
+    }
+
+    fn read_fields(&self, fields: &[Value], reader: &mut Reader) -> Option<Value> {
+        // Named fields decode to an object, tuple fields to an array
+        if fields.iter().all(|field| field.get("name").is_some()) {
+            let mut object = Map::new();
+            for field in fields {
+                let name = field.get("name")?.as_str()?.to_string();
+                object.insert(name, self.read_type(field.get("type")?, reader)?);
+            }
+            Some(Value::Object(object))
+        } else {
+            fields
+                .iter()
+                .map(|field| self.read_type(field, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+    }
+
+    fn read_type(&self, ty: &Value, reader: &mut Reader) -> Option<Value> {
+        if let Some(name) = ty.as_str() {
+            return read_primitive(name, reader);
+        }
+
+        if let Some(inner) = ty.get("vec") {
+            let len = reader.u32()?;
+            return (0..len)
+                .map(|_| self.read_type(inner, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(inner) = ty.get("option") {
+            return match reader.u8()? {
+                0 => Some(Value::Null),
+                _ => self.read_type(inner, reader),
+            };
+        }
+        if let Some(inner) = ty.get("coption") {
+            return match reader.u32()? {
+                0 => Some(Value::Null),
+                _ => self.read_type(inner, reader),
+            };
+        }
+        if let Some([inner, len]) = ty
+            .get("array")
+            .and_then(|array| array.as_array())
+            .map(|a| a.as_slice())
+        {
+            return (0..len.as_u64()?)
+                .map(|_| self.read_type(inner, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(defined) = ty.get("defined") {
+            let name = defined.as_str().or_else(|| defined.get("name")?.as_str())?;
+            return self.read_defined(self.types.get(name)?, reader);
+        }
+
+        None
+    }
+
+    fn read_defined(&self, ty: &Value, reader: &mut Reader) -> Option<Value> {
+        match ty.get("kind")?.as_str()? {
+            "struct" => {
+                let fields = ty
+                    .get("fields")
+                    .and_then(|fields| fields.as_array())
+                    .map(|fields| fields.as_slice())
+                    .unwrap_or_default();
+                self.read_fields(fields, reader)
+            }
+            "enum" => {
+                let variants = ty.get("variants")?.as_array()?;
+                let variant = variants.get(reader.u8()? as usize)?;
+                let name = variant.get("name")?.as_str()?.to_string();
+                let value = match variant.get("fields").and_then(|fields| fields.as_array()) {
+                    Some(fields) => self.read_fields(fields, reader)?,
+                    None => json!({}),
+                };
+
+                let mut object = Map::new();
+                object.insert(name, value);
+                Some(Value::Object(object))
+            }
+            "alias" => self.read_type(ty.get("value")?, reader),
+            _ => None,
+        }
+    }
+}
+
+/// Best-effort classification of every `Program data:` line in `logs`,
+/// splitting the decoded bytes after `discriminator_len` bytes. Lines that
+/// aren't base64 or are shorter than the discriminator are skipped.
+pub fn raw_events(logs: &[String], discriminator_len: usize) -> Vec<RawEvent> {
+    let mut stack: Vec<Pubkey> = Vec::new();
+    let mut events = Vec::new();
+
+    for log in logs {
+        match LogEvent::parse(log) {
+            LogEvent::Invoke { program_id, .. } => stack.push(program_id),
+            LogEvent::Success { .. } | LogEvent::Failed { .. } => {
+                stack.pop();
+            }
+            LogEvent::Data(data) => {
+                let Ok(mut data) = BASE64_STANDARD.decode(data) else {
+                    continue;
+                };
+                if data.len() < discriminator_len {
+                    continue;
+                }
+
+                let payload = data.split_off(discriminator_len);
+                events.push(RawEvent {
+                    program_id: stack.last().copied(),
+                    discriminator: data,
+                    payload,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    events
+}
+
+/// Discriminator Anchor derives for an event without an explicit one:
+/// the first 8 bytes of `sha256("event:<name>")`
+pub fn event_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("event:{}", name));
+    hash[..8].try_into().unwrap()
+}
+
+fn read_primitive(name: &str, reader: &mut Reader) -> Option<Value> {
+    let value = match name {
+        "bool" => json!(reader.u8()? != 0),
+        "u8" => json!(reader.u8()?),
+        "i8" => json!(reader.u8()? as i8),
+        "u16" => json!(reader.u16()?),
+        "i16" => json!(reader.u16()? as i16),
+        "u32" => json!(reader.u32()?),
+        "i32" => json!(reader.u32()? as i32),
+        "u64" => json!(reader.u64()?),
+        "i64" => json!(reader.u64()? as i64),
+        "f32" => json!(f32::from_bits(reader.u32()?)),
+        "f64" => json!(f64::from_bits(reader.u64()?)),
+        // Beyond what JSON numbers hold exactly
+        "u128" => json!(reader.u128()?.to_string()),
+        "i128" => json!((reader.u128()? as i128).to_string()),
+        "publicKey" | "pubkey" => json!(reader.pubkey()?),
+        "string" => {
+            let len = reader.u32()? as usize;
+            json!(String::from_utf8(reader.take(len)?.to_vec()).ok()?)
+        }
+        "bytes" => {
+            let len = reader.u32()? as usize;
+            json!(BASE64_STANDARD.encode(reader.take(len)?))
+        }
+        _ => return None,
+    };
+
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
+
+    fn trade_event() -> Vec<u8> {
+        let mut data = vec![189, 219, 127, 211, 78, 230, 97, 238];
+        data.extend_from_slice(&[1u8; 32]);
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.push(1);
+        data.push(1);
+        data.push(1);
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(b"hi");
+        data
+    }
+
+    #[test]
+    fn test_decode_legacy_idl_event() {
+        let idl = Idl::from_json(
+            r#"{
+                "events": [{
+                    "name": "TradeEvent",
+                    "fields": [
+                        {"name": "mint", "type": "publicKey", "index": false},
+                        {"name": "amount", "type": "u64", "index": false},
+                        {"name": "isBuy", "type": "bool", "index": false},
+                        {"name": "side", "type": {"defined": "Side"}, "index": false},
+                        {"name": "memo", "type": {"option": "string"}, "index": false}
+                    ]
+                }],
+                "types": [{
+                    "name": "Side",
+                    "type": {"kind": "enum", "variants": [{"name": "Bid"}, {"name": "Ask"}]}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let event = idl.decode_event(&trade_event()).unwrap();
+
+        assert_eq!(event.name, "TradeEvent");
+        assert_eq!(
+            event.data,
+            json!({
+                "mint": bs58::encode([1u8; 32]).into_string(),
+                "amount": 1000,
+                "isBuy": true,
+                "side": {"Ask": {}},
+                "memo": "hi"
+            })
+        );
+        assert!(idl.decode_event(&[0u8; 16]).is_none());
+    }
+
+    #[test]
+    fn test_raw_events_discriminator_len() {
+        let logs: Vec<String> = [
+            format!("Program {} invoke [1]", PROGRAM),
+            format!(
+                "Program data: {}",
+                BASE64_STANDARD.encode([1, 2, 3, 4, 5, 6])
+            ),
+            format!("Program data: {}", BASE64_STANDARD.encode([9, 9])),
+            "Program data: not base64!".to_string(),
+            format!("Program {} success", PROGRAM),
+        ]
+        .into();
+
+        let events = raw_events(&logs, 4);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(
+            events[0].program_id,
+            Some(Pubkey::from_str(PROGRAM).unwrap())
+        );
+        assert_eq!(events[0].discriminator, [1, 2, 3, 4]);
+        assert_eq!(events[0].payload, [5, 6]);
+        assert_eq!(raw_events(&logs, ANCHOR_DISCRIMINATOR_LEN).len(), 0);
+    }
+
+    #[test]
+    fn test_events_from_logs() {
+        let idl = Idl::from_json(
+            r#"{
+                "events": [{"name": "Ping", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8]}],
+                "types": [{
+                    "name": "Ping",
+                    "type": {"kind": "struct", "fields": [{"name": "count", "type": "u16"}]}
+                }]
+            }"#,
+        )
+        .unwrap();
+        let ping = BASE64_STANDARD.encode([1, 2, 3, 4, 5, 6, 7, 8, 7, 0]);
+
+        let logs: Vec<String> = [
+            format!("Program {} invoke [1]", PROGRAM),
+            format!("Program data: {}", ping),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            format!("Program data: {}", ping),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+            format!("Program {} success", PROGRAM),
+        ]
+        .into();
+
+        let events = idl.events_from_logs(PROGRAM, &logs);
+
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "Ping");
+        assert_eq!(events[0].data, json!({"count": 7}));
+    }
+}
@@ -0,0 +1,144 @@
+use std::{collections::HashSet, fmt::Display, str::FromStr};
+
+const REDACTED: &str = "[redacted]";
+
+/// Request fields that can be redacted from audit lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuditField {
+    Signature,
+    Program,
+    Client,
+    RpcUrl,
+}
+
+impl FromStr for AuditField {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "signature" => Ok(AuditField::Signature),
+            "program" => Ok(AuditField::Program),
+            "client" => Ok(AuditField::Client),
+            "rpc_url" | "rpc-url" => Ok(AuditField::RpcUrl),
+            _ => Err(format!(
+                "Unknown audit field: {} (expected signature, program, client or rpc_url)",
+                s
+            )),
+        }
+    }
+}
+
+/// What a request asked for, as recorded in the audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditRequest {
+    pub method: &'static str,
+    pub client: Option<String>,
+    pub signature: Option<String>,
+    pub program: Option<String>,
+    pub rpc_url: Option<String>,
+}
+
+/// Outcome of a request, as recorded in the audit log
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditResponse {
+    pub method: &'static str,
+    pub logs: usize,
+    pub compute_units: Option<u64>,
+    pub success: bool,
+    /// Set when the request failed before producing a response
+    pub error: Option<String>,
+}
+
+/// Per-request audit trail, one line per request and one per response
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    redacted: HashSet<AuditField>,
+}
+
+impl AuditLog {
+    pub fn new(redacted: impl IntoIterator<Item = AuditField>) -> Self {
+        AuditLog {
+            redacted: redacted.into_iter().collect(),
+        }
+    }
+
+    pub fn log_request(&self, request: &AuditRequest) {
+        println!("{}", self.format_request(request));
+    }
+
+    pub fn log_response(&self, response: &AuditResponse) {
+        println!("{}", self.format_response(response));
+    }
+
+    pub fn format_request(&self, request: &AuditRequest) -> String {
+        format!(
+            "📝 audit request method={} client={} signature={} program={} rpc_url={}",
+            request.method,
+            self.field(AuditField::Client, request.client.as_ref()),
+            self.field(AuditField::Signature, request.signature.as_ref()),
+            self.field(AuditField::Program, request.program.as_ref()),
+            self.field(AuditField::RpcUrl, request.rpc_url.as_ref()),
+        )
+    }
+
+    pub fn format_response(&self, response: &AuditResponse) -> String {
+        match response.error {
+            Some(ref err) => format!(
+                "📝 audit response method={} status=error error={:?}",
+                response.method, err
+            ),
+            None => format!(
+                "📝 audit response method={} status=ok logs={} compute_units={} success={}",
+                response.method,
+                response.logs,
+                response
+                    .compute_units
+                    .map(|cu| cu.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
+                response.success,
+            ),
+        }
+    }
+
+    fn field(&self, field: AuditField, value: Option<&impl Display>) -> String {
+        match value {
+            None => "-".to_string(),
+            Some(_) if self.redacted.contains(&field) => REDACTED.to_string(),
+            Some(value) => value.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_audit_redaction() {
+        let audit = AuditLog::new(["signature".parse::<AuditField>().unwrap(), AuditField::Client]);
+        let request = AuditRequest {
+            method: "GetTxLogs",
+            client: Some("127.0.0.1:51234".to_string()),
+            signature: Some("5h6xBEauJ3PK6SWC".to_string()),
+            program: None,
+            rpc_url: Some("https://api.mainnet-beta.solana.com".to_string()),
+        };
+
+        assert_eq!(
+            audit.format_request(&request),
+            "📝 audit request method=GetTxLogs client=[redacted] signature=[redacted] program=- rpc_url=https://api.mainnet-beta.solana.com"
+        );
+
+        let response = AuditResponse {
+            method: "GetTxLogs",
+            logs: 3,
+            compute_units: Some(29933),
+            success: true,
+            error: None,
+        };
+        assert_eq!(
+            audit.format_response(&response),
+            "📝 audit response method=GetTxLogs status=ok logs=3 compute_units=29933 success=true"
+        );
+    }
+}
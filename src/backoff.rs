@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use rand::Rng;
+
+/// Capped exponential backoff with random jitter, so streams that lose their
+/// WebSocket at the same moment don't all reconnect at the same moment
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backoff {
+    /// Delay before the first retry
+    pub base: Duration,
+    /// Growth factor applied per retry
+    pub multiplier: f64,
+    /// Upper bound on any delay, jitter included
+    pub max: Duration,
+    /// Fraction of the delay randomly added or removed, from 0.0 to 1.0
+    pub jitter: f64,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            base: Duration::from_millis(500),
+            multiplier: 2.0,
+            max: Duration::from_secs(30),
+            jitter: 0.2,
+        }
+    }
+}
+
+impl Backoff {
+    /// Delay before retry number `attempt`, counting from 0
+    pub fn delay(&self, attempt: u32) -> Duration {
+        self.delay_with(attempt, rand::rng().random_range(-1.0..=1.0))
+    }
+
+    /// `spread` in `[-1, 1]` picks where in the jitter window the delay lands
+    fn delay_with(&self, attempt: u32, spread: f64) -> Duration {
+        let max = self.max.as_secs_f64();
+        let exponent = attempt.min(i32::MAX as u32) as i32;
+        let delay = (self.base.as_secs_f64() * self.multiplier.max(1.0).powi(exponent)).min(max);
+        let jitter = self.jitter.clamp(0.0, 1.0) * spread.clamp(-1.0, 1.0);
+
+        Duration::from_secs_f64((delay * (1.0 + jitter)).max(0.0).min(max))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delays_are_capped_and_jittered() {
+        let backoff = Backoff {
+            base: Duration::from_millis(100),
+            multiplier: 2.0,
+            max: Duration::from_secs(1),
+            jitter: 0.5,
+        };
+
+        // No jitter: 100ms, 200ms, 400ms, 800ms, then the cap
+        assert_eq!(backoff.delay_with(0, 0.0), Duration::from_millis(100));
+        assert_eq!(backoff.delay_with(3, 0.0), Duration::from_millis(800));
+        assert_eq!(backoff.delay_with(4, 0.0), Duration::from_secs(1));
+        assert_eq!(backoff.delay_with(u32::MAX, 0.0), Duration::from_secs(1));
+
+        // Jitter stays within ±50% and never pushes past the cap
+        assert_eq!(backoff.delay_with(1, -1.0), Duration::from_millis(100));
+        assert_eq!(backoff.delay_with(1, 1.0), Duration::from_millis(300));
+        assert_eq!(backoff.delay_with(10, 1.0), Duration::from_secs(1));
+
+        for attempt in 0..20 {
+            let expected = backoff.delay_with(attempt, 0.0).as_secs_f64();
+            let delay = backoff.delay(attempt).as_secs_f64();
+            assert!(delay >= expected * 0.5 - 1e-9);
+            assert!(delay <= (expected * 1.5).min(1.0) + 1e-9);
+        }
+    }
+}
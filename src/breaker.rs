@@ -0,0 +1,204 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// State of the circuit for one RPC URL
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakerState {
+    /// Requests flow normally
+    Closed,
+    /// Too many consecutive failures; requests are rejected until the cooldown ends
+    Open,
+    /// Cooldown over; a single probe request is let through
+    HalfOpen,
+}
+
+impl BreakerState {
+    /// Numeric value for metrics: 0 closed, 1 half-open, 2 open
+    pub fn as_gauge(&self) -> u8 {
+        match self {
+            BreakerState::Closed => 0,
+            BreakerState::HalfOpen => 1,
+            BreakerState::Open => 2,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Breaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    probing: bool,
+}
+
+/// Per-RPC-URL circuit breakers. After `threshold` consecutive upstream
+/// failures a URL's circuit opens and requests are rejected for `cooldown`;
+/// then one probe is let through, which closes the circuit on success or
+/// reopens it on failure.
+#[derive(Debug)]
+pub struct CircuitBreakers {
+    threshold: u32,
+    cooldown: Duration,
+    breakers: Mutex<HashMap<String, Breaker>>,
+}
+
+impl CircuitBreakers {
+    /// A `threshold` of 0 disables the breakers
+    pub fn new(threshold: u32, cooldown: Duration) -> Self {
+        CircuitBreakers {
+            threshold,
+            cooldown,
+            breakers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Ask to send a request to `rpc_url`. Returns how long until the
+    /// circuit may be probed again when the request should be rejected.
+    /// An accepted request must be followed by `record_success`,
+    /// `record_failure` or `release`.
+    pub fn try_acquire(&self, rpc_url: &str) -> Result<(), Duration> {
+        if self.threshold == 0 {
+            return Ok(());
+        }
+
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(rpc_url.to_string()).or_default();
+
+        let Some(opened_at) = breaker.opened_at else {
+            return Ok(());
+        };
+
+        let elapsed = opened_at.elapsed();
+        if elapsed < self.cooldown {
+            return Err(self.cooldown - elapsed);
+        }
+        if breaker.probing {
+            return Err(Duration::ZERO);
+        }
+
+        println!("🩹 Probing RPC {} after circuit cooldown", rpc_url);
+        breaker.probing = true;
+        Ok(())
+    }
+
+    /// The RPC answered; close the circuit
+    pub fn record_success(&self, rpc_url: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+
+        let mut breakers = self.breakers.lock().unwrap();
+        if let Some(breaker) = breakers.get_mut(rpc_url) {
+            if breaker.opened_at.is_some() {
+                println!("✅ Circuit closed for RPC {}", rpc_url);
+            }
+            *breaker = Breaker::default();
+        }
+    }
+
+    /// The RPC failed; open the circuit once the threshold is reached, or
+    /// straight away if this was the probe
+    pub fn record_failure(&self, rpc_url: &str) {
+        if self.threshold == 0 {
+            return;
+        }
+
+        let mut breakers = self.breakers.lock().unwrap();
+        let breaker = breakers.entry(rpc_url.to_string()).or_default();
+        breaker.consecutive_failures += 1;
+
+        if breaker.probing || breaker.consecutive_failures >= self.threshold {
+            if !breaker.probing {
+                eprintln!(
+                    "🔌 Circuit opened for RPC {} after {} consecutive failures",
+                    rpc_url, breaker.consecutive_failures
+                );
+            }
+            breaker.opened_at = Some(Instant::now());
+            breaker.probing = false;
+        }
+    }
+
+    /// The request ended without saying anything about the RPC, e.g. it was
+    /// rejected as invalid; let another request probe instead
+    pub fn release(&self, rpc_url: &str) {
+        let mut breakers = self.breakers.lock().unwrap();
+        if let Some(breaker) = breakers.get_mut(rpc_url) {
+            breaker.probing = false;
+        }
+    }
+
+    pub fn state(&self, rpc_url: &str) -> BreakerState {
+        let breakers = self.breakers.lock().unwrap();
+        breakers
+            .get(rpc_url)
+            .map_or(BreakerState::Closed, |breaker| self.state_of(breaker))
+    }
+
+    /// State and consecutive failures of every RPC URL seen so far
+    pub fn states(&self) -> Vec<(String, BreakerState, u32)> {
+        let breakers = self.breakers.lock().unwrap();
+        let mut states: Vec<_> = breakers
+            .iter()
+            .map(|(rpc_url, breaker)| {
+                (
+                    rpc_url.clone(),
+                    self.state_of(breaker),
+                    breaker.consecutive_failures,
+                )
+            })
+            .collect();
+        states.sort_by(|a, b| a.0.cmp(&b.0));
+        states
+    }
+
+    fn state_of(&self, breaker: &Breaker) -> BreakerState {
+        match breaker.opened_at {
+            None => BreakerState::Closed,
+            Some(_) if breaker.probing => BreakerState::HalfOpen,
+            Some(opened_at) if opened_at.elapsed() >= self.cooldown => BreakerState::HalfOpen,
+            Some(_) => BreakerState::Open,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RPC: &str = "https://rpc.example";
+
+    #[test]
+    fn test_opens_after_threshold_and_probes_once() {
+        let breakers = CircuitBreakers::new(2, Duration::ZERO);
+
+        breakers.record_failure(RPC);
+        assert_eq!(breakers.state(RPC), BreakerState::Closed);
+        breakers.record_failure(RPC);
+        assert_ne!(breakers.state(RPC), BreakerState::Closed);
+
+        // Cooldown of zero: the first request probes, the second waits for it
+        assert!(breakers.try_acquire(RPC).is_ok());
+        assert!(breakers.try_acquire(RPC).is_err());
+
+        // A failed probe reopens the circuit, a successful one closes it
+        breakers.record_failure(RPC);
+        assert!(breakers.try_acquire(RPC).is_ok());
+        breakers.record_success(RPC);
+        assert_eq!(breakers.state(RPC), BreakerState::Closed);
+        assert!(breakers.try_acquire(RPC).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_during_cooldown() {
+        let breakers = CircuitBreakers::new(1, Duration::from_secs(60));
+
+        breakers.record_failure(RPC);
+
+        assert_eq!(breakers.state(RPC), BreakerState::Open);
+        assert!(breakers.try_acquire(RPC).is_err());
+        assert!(breakers.try_acquire("https://other.example").is_ok());
+    }
+}
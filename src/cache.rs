@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use solana_commitment_config::CommitmentLevel;
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+
+/// How long fetched transactions stay cached, by the commitment they were
+/// fetched at. Finalized transactions can't change and never expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommitmentTtl {
+    /// A processed transaction may still be rolled back
+    pub processed: Duration,
+    /// A confirmed transaction is very unlikely to change, but may
+    pub confirmed: Duration,
+}
+
+impl Default for CommitmentTtl {
+    fn default() -> Self {
+        CommitmentTtl {
+            processed: Duration::from_millis(500),
+            confirmed: Duration::from_secs(10),
+        }
+    }
+}
+
+impl CommitmentTtl {
+    /// `None` for commitments whose entries never expire
+    fn ttl(&self, commitment: CommitmentLevel) -> Option<Duration> {
+        match commitment {
+            CommitmentLevel::Processed => Some(self.processed),
+            CommitmentLevel::Confirmed => Some(self.confirmed),
+            CommitmentLevel::Finalized => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    tx: Arc<EncodedConfirmedTransactionWithStatusMeta>,
+    commitment: CommitmentLevel,
+    inserted: Instant,
+}
+
+/// Fetched transactions keyed by RPC URL and signature, shared by parsers.
+/// An entry only answers requests at or below the commitment it was
+/// fetched at, so a transaction seen as confirmed is fetched again when it
+/// is asked for as finalized.
+#[derive(Debug)]
+pub struct TxCache {
+    ttl: CommitmentTtl,
+    max_entries: usize,
+    entries: Mutex<HashMap<(String, String), Entry>>,
+}
+
+impl TxCache {
+    /// Keeps at most `max_entries` transactions, evicting the oldest
+    pub fn new(ttl: CommitmentTtl, max_entries: usize) -> Self {
+        TxCache {
+            ttl,
+            max_entries,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The cached transaction, if it was fetched at `commitment` or higher
+    /// and hasn't expired
+    pub fn get(
+        &self,
+        rpc_url: &str,
+        signature: &str,
+        commitment: CommitmentLevel,
+    ) -> Option<Arc<EncodedConfirmedTransactionWithStatusMeta>> {
+        let entries = self.entries.lock().unwrap();
+        let entry = entries.get(&(rpc_url.to_string(), signature.to_string()))?;
+
+        if rank(entry.commitment) < rank(commitment) || self.expired(entry) {
+            return None;
+        }
+        Some(entry.tx.clone())
+    }
+
+    /// Cache a transaction fetched at `commitment`, replacing any entry for
+    /// the same signature
+    pub fn insert(
+        &self,
+        rpc_url: &str,
+        signature: &str,
+        commitment: CommitmentLevel,
+        tx: Arc<EncodedConfirmedTransactionWithStatusMeta>,
+    ) {
+        if self.max_entries == 0 {
+            return;
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        let key = (rpc_url.to_string(), signature.to_string());
+
+        if !entries.contains_key(&key) && entries.len() >= self.max_entries {
+            entries.retain(|_, entry| !self.expired(entry));
+            if entries.len() >= self.max_entries {
+                let oldest = entries
+                    .iter()
+                    .min_by_key(|(_, entry)| entry.inserted)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    entries.remove(&oldest);
+                }
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                tx,
+                commitment,
+                inserted: Instant::now(),
+            },
+        );
+    }
+
+    /// Number of cached transactions, expired ones included
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn expired(&self, entry: &Entry) -> bool {
+        self.ttl
+            .ttl(entry.commitment)
+            .is_some_and(|ttl| entry.inserted.elapsed() > ttl)
+    }
+}
+
+fn rank(commitment: CommitmentLevel) -> u8 {
+    match commitment {
+        CommitmentLevel::Processed => 0,
+        CommitmentLevel::Confirmed => 1,
+        CommitmentLevel::Finalized => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(slot: u64) -> Arc<EncodedConfirmedTransactionWithStatusMeta> {
+        Arc::new(
+            serde_json::from_value(serde_json::json!({
+                "slot": slot,
+                "transaction": ["", "base64"],
+                "meta": null,
+                "blockTime": null
+            }))
+            .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_entries_answer_up_to_their_commitment() {
+        let ttl = CommitmentTtl {
+            processed: Duration::ZERO,
+            confirmed: Duration::from_secs(60),
+        };
+        let cache = TxCache::new(ttl, 2);
+
+        cache.insert("rpc", "confirmed", CommitmentLevel::Confirmed, tx(1));
+        assert!(
+            cache
+                .get("rpc", "confirmed", CommitmentLevel::Processed)
+                .is_some()
+        );
+        assert!(
+            cache
+                .get("rpc", "confirmed", CommitmentLevel::Finalized)
+                .is_none()
+        );
+        assert!(
+            cache
+                .get("other-rpc", "confirmed", CommitmentLevel::Confirmed)
+                .is_none()
+        );
+
+        // Processed entries expire right away here, finalized ones never
+        cache.insert("rpc", "processed", CommitmentLevel::Processed, tx(2));
+        std::thread::sleep(Duration::from_millis(1));
+        assert!(
+            cache
+                .get("rpc", "processed", CommitmentLevel::Processed)
+                .is_none()
+        );
+
+        // Full: the expired entry makes room first
+        cache.insert("rpc", "finalized", CommitmentLevel::Finalized, tx(3));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache
+                .get("rpc", "finalized", CommitmentLevel::Finalized)
+                .map(|tx| tx.slot),
+            Some(3)
+        );
+    }
+}
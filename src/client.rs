@@ -1,12 +1,80 @@
+use base64::{Engine, prelude::BASE64_STANDARD};
 use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
 
-// Include the generated protobuf code
-pub mod proto {
-    tonic::include_proto!("odin");
+// Generated protobuf types, shared with the server through the library
+use odin::proto;
+use proto::solana_tx_log_client::SolanaTxLogClient;
+use proto::{
+    ComputeUnitLog, ContainsLogRequest, GetRelatedRequest, GetTxBatchRequest, GetTxRequest, GetTxResponse, LogsBySeverity,
+    SimulateRequest, StreamProgramRequest, StreamTransactionResponse, batch_stream_message,
+};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use odin::cu_stats::ComputeUnitAggregator;
+use odin::error::ParseError;
+use odin::expect::Expectations;
+use odin::grpc_client::{anchor_event_to_proto, parsed_from_response, return_data_to_proto};
+use odin::labels::ProgramLabels;
+use odin::output::{FieldNaming, ParsedTransaction};
+use odin::parser::{LogOrder, LogSource, TxLogParser, encoding_label, parse_commitment};
+use odin::severity::Severity;
+use odin::subscribe::{SubscribeOptions, subscribe_logs, ws_url_from_rpc};
+use odin::token_balance::{TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+// Signatures per GetTxLogsBatch request, the server's maximum
+const CU_STATS_BATCH_SIZE: usize = 100;
+
+// Refetches of a recorded transaction the RPC doesn't have yet
+const RECORD_FETCH_RETRIES: u32 = 5;
+const RECORD_FETCH_RETRY_DELAY: Duration = Duration::from_millis(400);
+
+// How often the stream checkpoint file is rewritten
+const CHECKPOINT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Programs listed in each --summary-interval summary
+const SUMMARY_TOP_PROGRAMS: usize = 5;
+
+/// `service` of the events printed with --format observability
+const OBSERVABILITY_SERVICE: &str = "odin";
+
+// Set from --plain before anything is printed
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+// Set with --format json or observability, so stdout holds only the JSON
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// `println!`, without the leading emoji and separator lines under --plain
+macro_rules! out {
+    () => {
+        if !QUIET.load(Ordering::Relaxed) {
+            println!();
+        }
+    };
+    ($($arg:tt)*) => {
+        if !QUIET.load(Ordering::Relaxed) {
+            if let Some(text) = plain_text(&format!($($arg)*)) {
+                println!("{}", text);
+            }
+        }
+    };
 }
 
-use proto::solana_tx_log_client::SolanaTxLogClient;
-use proto::{GetTxRequest, StreamProgramRequest};
+/// `eprintln!`, likewise
+macro_rules! err_out {
+    ($($arg:tt)*) => {
+        if let Some(text) = plain_text(&format!($($arg)*)) {
+            eprintln!("{}", text);
+        }
+    };
+}
 
 /// Odin gRPC Client - Test the transaction log parser
 #[derive(Parser, Debug)]
@@ -33,6 +101,10 @@ struct Args {
     #[arg(short = 'c', long, default_value = "false")]
     include_cu_logs: bool,
 
+    /// Only fetch compute units, skipping the logs (unary mode)
+    #[arg(long, default_value = "false")]
+    cu_only: bool,
+
     /// Hide raw transaction logs (default: show them)
     #[arg(long = "no-raw-logs", default_value = "false")]
     no_raw_logs: bool,
@@ -48,17 +120,368 @@ struct Args {
     /// Program address to stream logs for (required in stream mode)
     #[arg(long, default_value = "")]
     program: String,
+
+    /// Write the transaction's program call graph as Graphviz DOT to this path
+    #[arg(long, default_value = "")]
+    dot: String,
+
+    /// Only stream failed transactions (or ones matching the filter)
+    #[arg(long, default_value = "false")]
+    errors_only: bool,
+
+    /// Checkpoint file for resuming a stream (read on start, written periodically)
+    #[arg(long, default_value = "")]
+    checkpoint: String,
+
+    /// Receive a follow-up status (confirmed/finalized/dropped) for each streamed transaction
+    #[arg(long, default_value = "false")]
+    track_finalization: bool,
+
+    /// Parse a saved `getTransaction` JSON file locally instead of calling the server
+    #[arg(long, default_value = "")]
+    from_file: String,
+
+    /// Show logs the way the Solana Explorer renders them instead of the raw list
+    #[arg(long, default_value = "false")]
+    explorer: bool,
+
+    /// Ask the server to label well-known programs
+    #[arg(long, default_value = "false")]
+    labels: bool,
+
+    /// Follow this account from the transaction and show the related transactions
+    #[arg(long, default_value = "")]
+    related: String,
+
+    /// Maximum related transactions to fetch after the starting one (server default if 0)
+    #[arg(long, default_value = "0")]
+    related_limit: u32,
+
+    /// Only follow the account up to (not including) this signature
+    #[arg(long, default_value = "")]
+    related_before: String,
+
+    /// Continue the related transactions after this signature (a previous run's next cursor)
+    #[arg(long, default_value = "")]
+    related_until: String,
+
+    /// Print the transaction as JSON instead of formatted sections
+    #[arg(long, default_value = "false")]
+    json: bool,
+
+    /// Key style for --json output: snake or camel
+    #[arg(long, default_value = "snake")]
+    json_naming: FieldNaming,
+
+    /// Comma-separated top-level fields to keep in --json output, e.g. logs,compute_units
+    #[arg(long, value_delimiter = ',')]
+    fields: Vec<String>,
+
+    /// Output format: text; json for the full response as one JSON object
+    /// (newline-delimited when streaming); or observability for one flat
+    /// JSON event per transaction (service, timestamp, duration_ms, tx.*).
+    /// Both JSON formats print nothing else on stdout.
+    #[arg(long, visible_alias = "output", default_value = "text")]
+    format: OutputFormat,
+
+    /// Fail unless some raw log contains this substring (repeatable)
+    #[arg(long)]
+    expect_log: Vec<String>,
+
+    /// Fail unless the transaction succeeded
+    #[arg(long, default_value = "false")]
+    expect_success: bool,
+
+    /// Fail unless the transaction consumed fewer compute units than this
+    #[arg(long)]
+    expect_cu_under: Option<u64>,
+
+    /// Fail unless this instruction (`Program log: Instruction: <name>`) ran (repeatable)
+    #[arg(long)]
+    expect_instruction: Vec<String>,
+
+    /// Append every streamed message to this file as NDJSON
+    #[arg(long, default_value = "")]
+    capture: String,
+
+    /// Replay an NDJSON capture through the stream display instead of connecting
+    #[arg(long, default_value = "")]
+    replay: String,
+
+    /// Reproduce the recorded gaps between messages during --replay, scaled
+    /// by this factor (2 = twice as fast). Without it, replay runs flat out.
+    #[arg(long)]
+    replay_speed: Option<f64>,
+
+    /// Simulate this base64-encoded transaction and show the logs it would produce
+    #[arg(long, default_value = "")]
+    encoded_tx: String,
+
+    /// Stream one message per log line instead of one per transaction
+    #[arg(long, default_value = "false")]
+    flatten_logs: bool,
+
+    /// WebSocket URL for streaming, when it isn't the RPC URL with a ws(s)
+    /// scheme (e.g. a local validator on port 8900)
+    #[arg(long, default_value = "")]
+    ws_url: String,
+
+    /// Regular expression for program logs to drop, e.g. `^Instruction: Transfer$`
+    /// (repeatable)
+    #[arg(long = "exclude-pattern")]
+    exclude_patterns: Vec<String>,
+
+    /// Order of program logs: chronological (as emitted) or grouped (all of
+    /// a program's logs together)
+    #[arg(long, default_value = "chronological")]
+    log_order: String,
+
+    /// How --filter matches: substring (case-insensitive) or regex
+    #[arg(long, default_value = "substring")]
+    filter_mode: String,
+
+    /// Only show raw logs containing this text (case-insensitive)
+    #[arg(long, default_value = "")]
+    raw_filter: String,
+
+    /// Commitment level: processed, confirmed or finalized
+    #[arg(long, default_value = "confirmed")]
+    commitment: String,
+
+    /// Print only the last N program instruction logs
+    #[arg(long)]
+    tail: Option<usize>,
+
+    /// Apply --tail to the raw logs too
+    #[arg(long, default_value = "false")]
+    tail_raw: bool,
+
+    /// Print at most N program and N raw logs; display only, --json still
+    /// has them all
+    #[arg(long)]
+    max_display: Option<usize>,
+
+    /// Fail if the transaction landed before this slot
+    #[arg(long)]
+    min_slot: Option<u64>,
+
+    /// Fail if the transaction landed after this slot
+    #[arg(long)]
+    max_slot: Option<u64>,
+
+    /// Plain text output: no emoji and no separator lines, for files and CI logs
+    #[arg(long, default_value = "false")]
+    plain: bool,
+
+    /// File of signatures, one per line: fetch them all and print compute
+    /// unit statistics per program
+    #[arg(long, default_value = "")]
+    cu_stats: String,
+
+    /// Include vote transactions in --cu-stats (skipped by default)
+    #[arg(long, default_value = "false")]
+    include_vote_cu: bool,
+
+    /// Streaming: times to refetch a transaction the RPC doesn't have yet
+    /// (server default when unset)
+    #[arg(long)]
+    fetch_retries: Option<u32>,
+
+    /// Streaming: milliseconds between those refetches (server default when unset)
+    #[arg(long)]
+    fetch_retry_ms: Option<u64>,
+
+    /// Streaming: every N seconds print a rolling summary of the interval
+    /// (transactions/sec, average CU, success rate, top programs by CU;
+    /// the per-program figures need --include-cu-logs)
+    #[arg(long)]
+    summary_interval: Option<u64>,
+
+    /// Record fixtures: subscribe to --program and save the raw
+    /// `getTransaction` JSON of the next --record-count transactions into
+    /// this directory, one `<signature>.json` each (readable by --from-file)
+    #[arg(long, default_value = "")]
+    record: String,
+
+    /// Transactions to save with --record
+    #[arg(long, default_value = "10")]
+    record_count: usize,
+
+    /// Only check whether a raw log line contains this text (case-insensitive)
+    /// and print the first match
+    #[arg(long, default_value = "")]
+    contains: String,
+}
+
+/// How a fetched transaction is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    /// The server's response as JSON, one object per line
+    Json,
+    /// Flat JSON events for log-based observability tools
+    Observability,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "observability" => Ok(OutputFormat::Observability),
+            _ => Err(format!("Unknown format: {} (expected text, json or observability)", s)),
+        }
+    }
+}
+
+/// Last processed position of a stream, persisted so a restarted client can
+/// resume from it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Checkpoint {
+    slot: u64,
+    signature: String,
+}
+
+impl Checkpoint {
+    fn load(path: &str) -> Result<Option<Self>, Box<dyn std::error::Error>> {
+        if !Path::new(path).exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&contents)?))
+    }
+
+    /// Write to a temporary file and rename it so a crash never leaves a
+    /// half-written checkpoint behind
+    fn save(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let tmp_path = format!("{}.tmp", path);
+        std::fs::write(&tmp_path, serde_json::to_string(self)?)?;
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args = Args::parse();
+    PLAIN.store(args.plain, Ordering::Relaxed);
+
+    if !args.fields.is_empty() {
+        if !args.json {
+            return Err("--fields only applies to --json output".into());
+        }
+        ParsedTransaction::check_fields(&args.fields)?;
+    }
+
+    if args.format == OutputFormat::Observability {
+        if args.json {
+            return Err("--format observability and --json are mutually exclusive".into());
+        }
+        let other_mode = args.stream
+            || !args.replay.is_empty()
+            || !args.record.is_empty()
+            || !args.related.is_empty()
+            || !args.cu_stats.is_empty()
+            || !args.contains.is_empty();
+        if other_mode {
+            return Err("--format observability only applies to a single transaction (--tx-sig, --from-file or --encoded-tx)".into());
+        }
+    }
+    if args.format == OutputFormat::Json {
+        if args.json {
+            return Err("--format json and --json are mutually exclusive".into());
+        }
+        let other_mode = !args.record.is_empty()
+            || !args.related.is_empty()
+            || !args.cu_stats.is_empty()
+            || !args.contains.is_empty();
+        if other_mode {
+            return Err("--format json only applies to single transactions, streams and replays".into());
+        }
+    }
+    if args.format != OutputFormat::Text {
+        QUIET.store(true, Ordering::Relaxed);
+    }
+    let started = Instant::now();
+
+    // Offline mode: parse a saved transaction without the server or RPC
+    if !args.from_file.is_empty() {
+        out!("📂 Parsing transaction from file: {}\n", args.from_file);
+
+        let filter = if args.filter.is_empty() {
+            None
+        } else {
+            Some(args.filter.as_str())
+        };
+        let parser = TxLogParser::from_file(&args.from_file, filter, args.include_cu_logs)?;
+        parser.check_slot_range(args.min_slot, args.max_slot)?;
+
+        let parsed = ParsedTransaction::from_parser(&parser);
+        if args.format == OutputFormat::Observability {
+            print_observability_event(&parsed, parser.get_slot(), started)?;
+        } else if args.json {
+            print_json(&parsed, &args)?;
+        } else {
+            let tx_response = local_response(&parser, args.labels);
+            if args.format == OutputFormat::Json {
+                print_response_json(&tx_response)?;
+            } else {
+                display_tx_response(&tx_response, !args.no_raw_logs, &args)?;
+            }
+        }
+
+        check_expectations(&args, &parsed);
+        out!("\n✅ Done!");
+        return Ok(());
+    }
+
+    // Fixture recording: straight from the RPC, without the server
+    if !args.record.is_empty() {
+        if args.program.is_empty() {
+            return Err("--record needs --program".into());
+        }
+        Pubkey::from_str(&args.program)
+            .map_err(|e| format!("--program is not a valid program address ({}): {}", e, args.program))?;
+        return record_fixtures(&args.program, &args.record, args.record_count, &args).await;
+    }
+
+    // Replay mode: feed a captured stream through the stream display
+    if !args.replay.is_empty() {
+        if args.replay_speed.is_some_and(|speed| speed <= 0.0) {
+            return Err("--replay-speed must be greater than 0".into());
+        }
+        return replay_stream(&args.replay, args.replay_speed, !args.no_raw_logs, &args).await;
+    }
+
+    // Catch a mangled transaction before bothering the server
+    if !args.encoded_tx.is_empty() {
+        let bytes = BASE64_STANDARD
+            .decode(args.encoded_tx.trim())
+            .map_err(|e| format!("--encoded-tx is not valid base64: {}", e))?;
+        if bytes.is_empty() {
+            return Err("--encoded-tx decodes to an empty transaction".into());
+        }
+    }
+
+    // Same for a mistyped signature or program address
+    if !args.programmatic && !args.tx_sig.is_empty() {
+        Signature::from_str(&args.tx_sig)
+            .map_err(|e| format!("--tx-sig is not a valid transaction signature ({}): {}", e, args.tx_sig))?;
+    }
+    if args.stream && !args.program.is_empty() {
+        Pubkey::from_str(&args.program)
+            .map_err(|e| format!("--program is not a valid program address ({}): {}", e, args.program))?;
+    }
+    args.log_order.parse::<LogOrder>()?;
 
     // ========================================
     // PROGRAMMATIC MODE - Hardcode your values here!
     // ========================================
-    let (tx_sig, rpc_url, filter, include_cu_logs, show_raw_logs) = if args.programmatic || args.tx_sig.is_empty() {
-        println!("🔧 Using PROGRAMMATIC mode (hardcoded values)\n");
+    let (tx_sig, rpc_url, filter, include_cu_logs, show_raw_logs) = if args.programmatic || (args.tx_sig.is_empty() && args.encoded_tx.is_empty() && args.cu_stats.is_empty()) {
+        out!("🔧 Using PROGRAMMATIC mode (hardcoded values)\n");
         
         // 👇 EDIT THESE VALUES TO TEST DIFFERENT TRANSACTIONS
         let tx_signature = "5mEjzNZjbrFmwyAWUMZemyASaheGj4MFWo2rG8DsD98m2ukKtx8JXkERhJ6GCFPc7s4D2zh36d8XrNBEsquagKkY".to_string();
@@ -69,16 +492,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         (tx_signature, rpc, log_filter, cu_logs, raw_logs)
     } else {
-        println!("🔧 Using CLI mode (command-line arguments)\n");
+        out!("🔧 Using CLI mode (command-line arguments)\n");
         (args.tx_sig.clone(), args.rpc_url.clone(), args.filter.clone(), args.include_cu_logs, !args.no_raw_logs)
     };
 
-    println!("🔌 Connecting to Odin server at: {}", args.server);
+    out!("🔌 Connecting to Odin server at: {}", args.server);
 
     // Connect to the gRPC server
     let mut client = SolanaTxLogClient::connect(args.server.clone()).await?;
 
-    println!("✅ Connected successfully!");
+    out!("✅ Connected successfully!");
 
     // Check if streaming mode
     if args.stream {
@@ -90,81 +513,861 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             args.program.clone()
         };
 
-        return test_streaming(client, program, rpc_url, include_cu_logs, filter, show_raw_logs).await;
+        return test_streaming(client, program, rpc_url, include_cu_logs, filter, show_raw_logs, &args).await;
+    }
+
+    // CU statistics over a file of signatures
+    if !args.cu_stats.is_empty() {
+        return cu_stats(&mut client, &args.cu_stats, rpc_url, filter, &args).await;
+    }
+
+    // Simulation mode
+    if !args.encoded_tx.is_empty() {
+        out!("\n🧪 Simulating encoded transaction ({} base64 chars)", args.encoded_tx.trim().len());
+        out!("🌐 Using RPC: {}", rpc_url);
+
+        let request = tonic::Request::new(SimulateRequest {
+            rpc_url,
+            encoded_tx: args.encoded_tx.trim().to_string(),
+            include_cu_logs,
+            filter,
+            include_labels: args.labels,
+        });
+
+        out!("\n⏳ Requesting simulation...\n");
+        let tx_response = match client.simulate_transaction(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                err_out!("❌ Simulation failed: {}", status.message());
+                std::process::exit(1);
+            }
+        };
+
+        let parsed = parsed_from_response("", &tx_response);
+        if args.format == OutputFormat::Observability {
+            print_observability_event(&parsed, None, started)?;
+        } else if args.format == OutputFormat::Json {
+            print_response_json(&tx_response)?;
+        } else if args.json {
+            print_json(&parsed, &args)?;
+        } else {
+            display_tx_response(&tx_response, show_raw_logs, &args)?;
+        }
+
+        check_expectations(&args, &parsed);
+        out!("\n✅ Done!");
+        return Ok(());
+    }
+
+    // First-match log lookup mode
+    if !args.contains.is_empty() {
+        out!("\n🔎 Looking for \"{}\" in transaction: {}", args.contains, tx_sig);
+        out!("🌐 Using RPC: {}", rpc_url);
+
+        let request = tonic::Request::new(ContainsLogRequest {
+            rpc_url,
+            tx_sig,
+            needle: args.contains.clone(),
+        });
+
+        let response = match client.contains_log(request).await {
+            Ok(response) => response.into_inner(),
+            Err(status) => {
+                err_out!("❌ Lookup failed: {}", status.message());
+                std::process::exit(1);
+            }
+        };
+
+        if response.found {
+            out!("\n✅ Found at log {}: {}", response.index.unwrap_or_default(), response.line);
+        } else {
+            out!("\n❌ No log line contains \"{}\"", args.contains);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Related transactions mode
+    if !args.related.is_empty() {
+        out!("\n🔗 Following account {} from transaction: {}", args.related, tx_sig);
+        out!("🌐 Using RPC: {}", rpc_url);
+
+        let request = tonic::Request::new(GetRelatedRequest {
+            rpc_url,
+            tx_sig,
+            account: args.related.clone(),
+            limit: args.related_limit,
+            include_cu_logs,
+            filter,
+            include_labels: args.labels,
+            before: args.related_before.clone(),
+            until: args.related_until.clone(),
+        });
+
+        out!("\n⏳ Requesting related transactions...\n");
+        let related = client.get_related_transactions(request).await?.into_inner();
+
+        for (idx, related_tx) in related.transactions.iter().enumerate() {
+            out!("{}", "=".repeat(80));
+            out!("🔗 Transaction {}/{}: {}", idx + 1, related.transactions.len(), related_tx.signature);
+            out!("🎰 Slot: {}", related_tx.slot);
+            out!("{}\n", "=".repeat(80));
+
+            if let Some(ref tx_response) = related_tx.response {
+                display_tx_response(tx_response, show_raw_logs, &args)?;
+            } else if !related_tx.error.is_empty() {
+                err_out!("⚠️  {}: {}", related_tx.signature, related_tx.error);
+            }
+            out!();
+        }
+
+        if !related.next_cursor.is_empty() {
+            out!("➡️  More transactions follow: --related-until {}", related.next_cursor);
+        }
+        out!("✅ Done!");
+        return Ok(());
     }
 
     // Unary mode (existing functionality)
-    println!("\n📡 Fetching logs for transaction: {}", tx_sig);
-    println!("🌐 Using RPC: {}", rpc_url);
+    out!("\n📡 Fetching logs for transaction: {}", tx_sig);
+    out!("🌐 Using RPC: {}", rpc_url);
     
     if !filter.is_empty() {
-        println!("🔍 Filter: {}", filter);
+        out!("🔍 Filter: {}", filter);
     }
     
-    if include_cu_logs {
-        println!("⚡ Including compute unit logs");
+    if args.cu_only {
+        out!("⚡ Fetching compute units only");
+    } else if include_cu_logs {
+        out!("⚡ Including compute unit logs");
     }
 
     // Create the request
     let request = tonic::Request::new(GetTxRequest {
         rpc_url,
-        tx_sig,
+        tx_sig: tx_sig.clone(),
         include_cu_logs,
         filter,
+        include_labels: args.labels,
+        cu_only: args.cu_only,
+        exclude_pattern: args.exclude_patterns.clone(),
+        log_order: args.log_order.clone(),
+        min_slot: args.min_slot,
+        max_slot: args.max_slot,
+        filter_mode: args.filter_mode.clone(),
+        raw_filter: args.raw_filter.clone(),
+        commitment: args.commitment.clone(),
     });
 
     // Make the RPC call
-    println!("\n⏳ Requesting transaction logs...\n");
+    out!("\n⏳ Requesting transaction logs...\n");
     let response = client.get_tx_logs(request).await?;
 
     let tx_response = response.into_inner();
 
+    let parsed = parsed_from_response(&tx_sig, &tx_response);
+    if args.format == OutputFormat::Observability {
+        print_observability_event(&parsed, Some(tx_response.slot).filter(|slot| *slot > 0), started)?;
+    } else if args.format == OutputFormat::Json {
+        print_response_json(&tx_response)?;
+    } else if args.json {
+        print_json(&parsed, &args)?;
+    } else {
+        display_tx_response(&tx_response, show_raw_logs, &args)?;
+    }
+
+    check_expectations(&args, &parsed);
+
+    out!("\n✅ Done!");
+
+    Ok(())
+}
+
+/// Print a unary response, and write its call graph if `--dot` was given
+fn display_tx_response(
+    tx_response: &GetTxResponse,
+    show_raw_logs: bool,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Display where the transaction failed, if it did
+    if let Some(ref error) = tx_response.error {
+        print_tx_error(error);
+    }
+
+    if tx_response.truncated {
+        out!("✂️  Logs truncated: the transaction exceeded the server's log limit\n");
+    }
+    if tx_response.limited {
+        out!("🛑 Results limited: the transaction exceeded the server's work budget\n");
+    }
+
+    // Display the compute budget the transaction requested
+    match tx_response.compute_unit_limit {
+        Some(limit) if tx_response.compute_budget_explicit => {
+            out!("🎚️  Compute Unit Limit: {} (set explicitly)", limit)
+        }
+        _ => out!("🎚️  Compute Unit Limit: default (200k per instruction)"),
+    }
+    if let Some(price) = tx_response.compute_unit_price {
+        out!("💸 Compute Unit Price: {} micro-lamports", price);
+    }
+    out!(
+        "🧮 Instructions: {} top-level, {} inner",
+        tx_response.top_level_instructions, tx_response.inner_instructions
+    );
+    if tx_response.slot > 0 {
+        out!("🎰 Slot: {}", tx_response.slot);
+    }
+    if let Some(block_time) = tx_response.block_time {
+        match chrono::DateTime::from_timestamp(block_time, 0) {
+            Some(time) => out!("🕐 Block Time: {} ({})", time.to_rfc3339(), block_time),
+            None => out!("🕐 Block Time: {}", block_time),
+        }
+    }
+    if !tx_response.version.is_empty() {
+        out!("🏷️  Version: {}", tx_response.version);
+    }
+    if !tx_response.encoding.is_empty() && tx_response.encoding != "jsonParsed" {
+        out!("🧾 Encoding: {} (jsonParsed unavailable)", tx_response.encoding);
+    }
+    if let Some(ref cost) = tx_response.cost_breakdown {
+        print_cost_breakdown(cost);
+    }
+    if let Some(ref access) = tx_response.account_access {
+        out!(
+            "🔐 Accounts: {} writable, {} readonly",
+            access.writable.len(),
+            access.readonly.len()
+        );
+    }
+    out!();
+
+    // Display what the enhanced-API provider made of the transaction
+    if let Some(ref enriched) = tx_response.enriched {
+        print_enriched(enriched);
+    }
+
+    print_decoded_instructions(&tx_response.decoded_instructions);
+    print_token_balances(&tx_response.token_balances);
+    print_rewards(&tx_response.rewards);
+
     // Display compute unit logs if included
     if !tx_response.compute_units.is_empty() {
-        println!("⚡ Compute Unit Logs:");
-        println!("{}", "=".repeat(80));
+        out!("⚡ Compute Unit Logs:");
+        out!("{}", "=".repeat(80));
         for cu_log in tx_response.compute_units.iter() {
-            println!("Program ID: {}", cu_log.program_id);
-            println!("  Consumed: {} compute units", cu_log.consumed);
+            if cu_log.label.is_empty() {
+                out!("Program ID: {}", cu_log.program_id);
+            } else {
+                out!("Program ID: {} ({})", cu_log.program_id, cu_log.label);
+            }
+            out!("  Consumed: {} compute units", cu_log.consumed);
+            if let Some(budget) = cu_log.budget {
+                out!("  Budget: {} compute units", budget);
+            }
+        }
+    }
+
+    print_cu_ranking(&tx_response.cu_ranking);
+
+    // Display the programs invoked by the transaction
+    if !tx_response.programs.is_empty() {
+        out!("\n🧩 Programs Invoked:");
+        out!("{}", "=".repeat(80));
+        for (idx, program) in tx_response.programs.iter().enumerate() {
+            match tx_response.program_labels.get(program) {
+                Some(label) => out!("[{}] {} ({})", idx + 1, program, label),
+                None => out!("[{}] {}", idx + 1, program),
+            }
         }
     }
 
     // Display the program instruction logs
-    println!("\n📋 Program Instruction Logs:");
-    println!("{}", "=".repeat(80));
+    out!("\n📋 Program Instruction Logs:");
+    out!("{}", "=".repeat(80));
     
     if tx_response.logs.is_empty() {
-        println!("No logs found (or all filtered out)");
+        out!("{}", no_logs_message(&tx_response.log_source));
+    } else {
+        print_logs(&tx_response.logs, args.tail, args);
+    }
+
+    print_logs_by_severity(tx_response.logs_by_severity.as_ref());
+
+    // Display logs in Explorer style, or the raw logs (optional - controlled by show_raw_logs flag)
+    if args.explorer {
+        print_explorer_logs(&tx_response.raw_logs);
+    } else if show_raw_logs && !tx_response.raw_logs.is_empty() {
+        out!("\n📜 Raw Transaction Logs:");
+        out!("{}", "=".repeat(80));
+        print_logs(&tx_response.raw_logs, args.tail.filter(|_| args.tail_raw), args);
+    }
+
+    print_anchor_events(&tx_response.anchor_events);
+    print_return_data(&tx_response.return_data);
+
+    // Write the CPI call graph if requested
+    if !args.dot.is_empty() {
+        std::fs::write(&args.dot, odin::invoke::to_dot(&tx_response.raw_logs))?;
+        out!("\n🗺️  Call graph written to {} (render with `dot -Tsvg`)", args.dot);
+    }
+
+    Ok(())
+}
+
+/// Build the response the server would return for a locally parsed transaction
+fn local_response(parser: &TxLogParser, include_labels: bool) -> GetTxResponse {
+    let labels = ProgramLabels::new();
+    let program_labels: HashMap<String, String> = if include_labels {
+        parser
+            .get_programs_invoked()
+            .iter()
+            .filter_map(|program_id| {
+                labels
+                    .get(program_id)
+                    .map(|label| (program_id.to_string(), label.to_string()))
+            })
+            .collect()
     } else {
-        for (idx, log) in tx_response.logs.iter().enumerate() {
-            println!("[{}] {}", idx + 1, log);
+        HashMap::new()
+    };
+
+    let compute_units = parser
+        .get_compute_unit_logs()
+        .sorted()
+        .into_iter()
+        .map(|(program_id, cu_log)| {
+            let program_id = program_id.to_string();
+            ComputeUnitLog {
+                label: program_labels.get(&program_id).cloned().unwrap_or_default(),
+                program_id,
+                consumed: cu_log.consumed,
+                budget: cu_log.budget,
+                invocations: cu_log.invocations,
+            }
+        })
+        .collect();
+
+    let cu_ranking = parser
+        .get_cu_ranking()
+        .into_iter()
+        .map(|rank| {
+            let program_id = rank.program_id.to_string();
+            proto::CuRank {
+                label: program_labels.get(&program_id).cloned().unwrap_or_default(),
+                program_id,
+                consumed: rank.consumed,
+                pct_of_total: rank.pct_of_total,
+                rank: rank.rank,
+            }
+        })
+        .collect();
+
+    let mut severity_buckets = parser.get_logs_by_severity();
+
+    GetTxResponse {
+        logs: parser.get_tx_logs(),
+        compute_units,
+        anchor_events: parser
+            .parse_anchor_events()
+            .into_iter()
+            .map(anchor_event_to_proto)
+            .collect(),
+        raw_logs: parser.get_raw_logs(),
+        programs: parser
+            .get_programs_invoked()
+            .iter()
+            .map(|program_id| program_id.to_string())
+            .collect(),
+        logs_by_severity: Some(LogsBySeverity {
+            errors: severity_buckets.remove(&Severity::Error).unwrap_or_default(),
+            warnings: severity_buckets.remove(&Severity::Warning).unwrap_or_default(),
+            info: severity_buckets.remove(&Severity::Info).unwrap_or_default(),
+        }),
+        error: parser.get_tx_error().map(|err| proto::TxError {
+            error: err.error,
+            instruction_index: err.instruction_index.map(u32::from),
+            program_id: err.program_id.map_or(String::new(), |program_id| program_id.to_string()),
+        }),
+        truncated: parser.is_truncated(),
+        program_labels,
+        compute_units_consumed: parser.get_compute_units_consumed(),
+        compute_budget_explicit: parser.is_compute_budget_explicit(),
+        compute_unit_limit: parser.get_compute_budget().and_then(|budget| budget.unit_limit),
+        compute_unit_price: parser.get_compute_budget().and_then(|budget| budget.unit_price),
+        // Saved transactions are parsed without an enhanced-API provider
+        enriched: None,
+        top_level_instructions: parser.get_instruction_counts().0 as u32,
+        inner_instructions: parser.get_instruction_counts().1 as u32,
+        cu_ranking,
+        decoded_instructions: parser
+            .get_decoded_instructions()
+            .into_iter()
+            .map(|decoded| proto::DecodedInstruction {
+                program: decoded.program,
+                name: decoded.name,
+                fields: decoded
+                    .fields
+                    .into_iter()
+                    .map(|(name, value)| proto::DecodedField { name, value })
+                    .collect(),
+            })
+            .collect(),
+        version: parser.get_version().unwrap_or_default(),
+        cost_breakdown: parser
+            .get_cost_breakdown()
+            .map(|cost| proto::CostBreakdown {
+                base_fee: cost.base_fee,
+                priority_fee: cost.priority_fee,
+                total_fee: cost.total_fee,
+                compute_units: cost.compute_units,
+                lamports_per_cu: cost.lamports_per_cu,
+            }),
+        account_access: parser
+            .get_account_access_summary()
+            .map(|access| proto::AccountAccess {
+                writable: access.writable.iter().map(|key| key.to_string()).collect(),
+                readonly: access.readonly.iter().map(|key| key.to_string()).collect(),
+            }),
+        encoding: parser
+            .get_encoding()
+            .map(|encoding| encoding_label(encoding).to_string())
+            .unwrap_or_default(),
+        token_balances: parser
+            .get_token_balance_changes()
+            .into_iter()
+            .map(|change| proto::TokenBalanceChange {
+                account_index: change.account_index as u32,
+                account: change.account.unwrap_or_default(),
+                mint: change.mint,
+                owner: change.owner.unwrap_or_default(),
+                program_id: change.program_id.unwrap_or_default(),
+                decimals: change.decimals as u32,
+                pre_amount: change.pre_amount,
+                post_amount: change.post_amount,
+            })
+            .collect(),
+        slot: parser.get_slot().unwrap_or_default(),
+        has_logs: matches!(parser.get_log_source(), Some(LogSource::Logs | LogSource::Filtered | LogSource::NoProgramLogs)),
+        log_source: parser
+            .get_log_source()
+            .map(|source| source.as_str().to_string())
+            .unwrap_or_default(),
+        rewards: parser
+            .get_rewards()
+            .into_iter()
+            .map(|reward| proto::Reward {
+                pubkey: reward.pubkey,
+                lamports: reward.lamports,
+                post_balance: reward.post_balance,
+                reward_type: reward.reward_type.unwrap_or_default(),
+                commission: reward.commission.map(u32::from),
+            })
+            .collect(),
+        limited: parser.is_limited(),
+        return_data: return_data_to_proto(parser),
+        success: matches!(parser.get_status(), Some(Ok(()))),
+        block_time: parser.get_block_time(),
+    }
+}
+
+/// Why a transaction shows no program logs, from the response's `log_source`
+fn no_logs_message(log_source: &str) -> &'static str {
+    match log_source {
+        "filtered" => "No logs matched the filter (all filtered out)",
+        "no_program_logs" => "The transaction logged no program logs",
+        "empty" => "The transaction emitted no logs",
+        "unavailable" => "The RPC did not return logs for this transaction",
+        // Older servers don't say
+        _ => "No logs found (or all filtered out)",
+    }
+}
+
+/// Print the fees of a transaction and what each compute unit cost
+fn print_cost_breakdown(cost: &proto::CostBreakdown) {
+    out!(
+        "💰 Fee: {} lamports ({} base + {} priority)",
+        cost.total_fee, cost.base_fee, cost.priority_fee
+    );
+    if let Some(lamports_per_cu) = cost.lamports_per_cu {
+        out!("   {:.6} lamports per compute unit", lamports_per_cu);
+    }
+}
+
+/// Print the decoded System, Token and Address Lookup Table instructions
+fn print_decoded_instructions(decoded_instructions: &[proto::DecodedInstruction]) {
+    if decoded_instructions.is_empty() {
+        return;
+    }
+
+    out!("📜 Decoded Instructions:");
+    for decoded in decoded_instructions.iter() {
+        let fields: Vec<String> = decoded
+            .fields
+            .iter()
+            .map(|field| format!("{}={}", field.name, field.value))
+            .collect();
+        out!("   {}::{} {}", decoded.program, decoded.name, fields.join(" "));
+    }
+    out!();
+}
+
+/// Print error and warning logs so failures stand out
+fn print_logs_by_severity(by_severity: Option<&LogsBySeverity>) {
+    let Some(by_severity) = by_severity else {
+        return;
+    };
+
+    if !by_severity.errors.is_empty() {
+        out!("\n🟥 Error Logs:");
+        out!("{}", "=".repeat(80));
+        for (idx, log) in by_severity.errors.iter().enumerate() {
+            out!("[{}] {}", idx + 1, log);
         }
     }
 
-    // Display raw transaction logs (optional - controlled by show_raw_logs flag)
-    if show_raw_logs && !tx_response.raw_logs.is_empty() {
-        println!("\n📜 Raw Transaction Logs:");
-        println!("{}", "=".repeat(80));
-        for (idx, log) in tx_response.raw_logs.iter().enumerate() {
-            println!("[{}] {}", idx + 1, log);
+    if !by_severity.warnings.is_empty() {
+        out!("\n🟨 Warning Logs:");
+        out!("{}", "=".repeat(80));
+        for (idx, log) in by_severity.warnings.iter().enumerate() {
+            out!("[{}] {}", idx + 1, log);
         }
     }
+}
+
+/// Print decoded Anchor events, or their raw discriminator and payload
+fn print_anchor_events(events: &[proto::AnchorEvent]) {
+    if events.is_empty() {
+        return;
+    }
+
+    out!("\n🎯 Anchor Events:");
+    out!("{}", "=".repeat(80));
+    for event in events.iter() {
+        out!("Event: {}", event.name);
+        out!("  Data: {}", event.data);
+    }
+}
+
+/// Print the data each program returned, base64-encoded
+fn print_return_data(return_data: &[proto::ReturnData]) {
+    if return_data.is_empty() {
+        return;
+    }
+
+    out!("\n↩️  Return Data:");
+    out!("{}", "=".repeat(80));
+    for entry in return_data.iter() {
+        out!("{}: {}", entry.program_id, entry.data);
+    }
+}
+
+/// Print the rewards credited or debited by the transaction
+fn print_rewards(rewards: &[proto::Reward]) {
+    if rewards.is_empty() {
+        return;
+    }
+
+    out!("🏆 Rewards:");
+    for reward in rewards.iter() {
+        let reward_type = if reward.reward_type.is_empty() { "unknown" } else { &reward.reward_type };
+        match reward.commission {
+            Some(commission) => out!(
+                "   {} {}: {:+} lamports (balance {}, commission {}%)",
+                reward.pubkey,
+                reward_type,
+                reward.lamports,
+                reward.post_balance,
+                commission
+            ),
+            None => out!(
+                "   {} {}: {:+} lamports (balance {})",
+                reward.pubkey,
+                reward_type,
+                reward.lamports,
+                reward.post_balance
+            ),
+        }
+    }
+    out!();
+}
+
+/// Print token balance changes with their owner and token program
+fn print_token_balances(token_balances: &[proto::TokenBalanceChange]) {
+    if token_balances.is_empty() {
+        return;
+    }
+
+    out!("🪙 Token Balances:");
+    for change in token_balances.iter() {
+        let program = match change.program_id.as_str() {
+            TOKEN_2022_PROGRAM_ID => "token-2022",
+            TOKEN_PROGRAM_ID => "spl-token",
+            _ => "unknown program",
+        };
+        let account = if change.account.is_empty() {
+            format!("#{}", change.account_index)
+        } else {
+            change.account.clone()
+        };
+        out!(
+            "   {} ({}, {}) owner {}: {} -> {} (decimals {})",
+            account,
+            change.mint,
+            program,
+            if change.owner.is_empty() { "unknown" } else { &change.owner },
+            change.pre_amount,
+            change.post_amount,
+            change.decimals
+        );
+    }
+    out!();
+}
+
+/// Print programs ranked by compute units consumed
+fn print_cu_ranking(cu_ranking: &[proto::CuRank]) {
+    if cu_ranking.is_empty() {
+        return;
+    }
+
+    out!("\n🏆 Compute Unit Ranking:");
+    out!("{}", "=".repeat(80));
+    for entry in cu_ranking.iter() {
+        let program = if entry.label.is_empty() {
+            entry.program_id.clone()
+        } else {
+            format!("{} ({})", entry.program_id, entry.label)
+        };
+        out!("#{} {} - {} CU ({:.1}%)", entry.rank, program, entry.consumed, entry.pct_of_total);
+    }
+}
+
+/// Print the provider-parsed view of a transaction
+fn print_enriched(enriched: &proto::EnrichedTransaction) {
+    out!("🔎 Enhanced: {} via {}", enriched.transaction_type, enriched.source);
+    if !enriched.description.is_empty() {
+        out!("   {}", enriched.description);
+    }
+    for (idx, instruction) in enriched.instructions.iter().enumerate() {
+        out!(
+            "   #{} {} ({} inner)",
+            idx + 1,
+            instruction.program_id,
+            instruction.inner_instructions
+        );
+    }
+    for transfer in enriched.transfers.iter() {
+        let asset = if transfer.mint.is_empty() { "lamports" } else { transfer.mint.as_str() };
+        out!("   💱 {} → {}: {} {}", transfer.from, transfer.to, transfer.amount, asset);
+    }
+    for event in enriched.events.iter() {
+        out!("   🎯 {} event: {}", event.kind, event.json);
+    }
+    out!();
+}
+
+/// Check the `--expect-*` flags and exit non-zero if any fail
+fn check_expectations(args: &Args, parsed: &ParsedTransaction) {
+    let expectations = Expectations {
+        logs: args.expect_log.clone(),
+        success: args.expect_success,
+        cu_under: args.expect_cu_under,
+        instructions: args.expect_instruction.clone(),
+    };
+
+    if expectations.is_empty() {
+        return;
+    }
 
-    // Display anchor events (currently empty)
-    if !tx_response.anchor_events.is_empty() {
-        println!("\n🎯 Anchor Events:");
-        println!("{}", "=".repeat(80));
-        for event in tx_response.anchor_events.iter() {
-            println!("Event: {}", event.name);
-            println!("  Data: {}", event.data);
+    let failures = expectations.check(parsed);
+    if failures.is_empty() {
+        out!("\n✅ All {} expectations passed", expectations.count());
+        return;
+    }
+
+    err_out!("\n❌ {} of {} expectations failed:", failures.len(), expectations.count());
+    err_out!("{}", "=".repeat(80));
+    for failure in failures.iter() {
+        err_out!("- {}", failure);
+    }
+    std::process::exit(1);
+}
+
+fn print_json(parsed: &ParsedTransaction, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+    let value = if args.fields.is_empty() {
+        parsed.to_json(args.json_naming)
+    } else {
+        parsed.to_json_fields(args.json_naming, &args.fields)?
+    };
+    println!("{}", serde_json::to_string_pretty(&value)?);
+    Ok(())
+}
+
+/// The whole response as one compact JSON line, for piping into `jq`
+fn print_response_json<T: Serialize>(response: &T) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{}", serde_json::to_string(response)?);
+    Ok(())
+}
+
+/// One line per event, as log shippers expect
+fn print_observability_event(
+    parsed: &ParsedTransaction,
+    slot: Option<u64>,
+    started: Instant,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let event = parsed.to_observability_event(OBSERVABILITY_SERVICE, slot, started.elapsed());
+    println!("{}", serde_json::to_string(&event)?);
+    Ok(())
+}
+
+/// Fetch every signature in `path` through the batch endpoint and print
+/// per-program compute unit statistics
+async fn cu_stats(
+    client: &mut SolanaTxLogClient<tonic::transport::Channel>,
+    path: &str,
+    rpc_url: String,
+    filter: String,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let signatures: Vec<String> = std::fs::read_to_string(path)?
+        .lines()
+        .map(|line| line.trim().to_string())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .collect();
+    if signatures.is_empty() {
+        return Err(format!("No signatures in {}", path).into());
+    }
+
+    out!("\n📊 Fetching {} transactions from {}", signatures.len(), path);
+
+    let mut aggregator = ComputeUnitAggregator::new().with_vote_program(args.include_vote_cu);
+    let mut failed = 0;
+    let mut done = 0;
+    for chunk in signatures.chunks(CU_STATS_BATCH_SIZE) {
+        let request = tonic::Request::new(GetTxBatchRequest {
+            rpc_url: rpc_url.clone(),
+            tx_sigs: chunk.to_vec(),
+            include_cu_logs: true,
+            filter: filter.clone(),
+            include_labels: false,
+        });
+
+        // Results arrive as they complete, with progress updates in between
+        let mut stream = client.stream_tx_logs_batch(request).await?.into_inner();
+        while let Some(message) = stream.message().await? {
+            match message.message {
+                Some(batch_stream_message::Message::Result(result)) => match result.response {
+                    Some(ref tx_response) => aggregator.add(&parsed_from_response(&result.tx_sig, tx_response)),
+                    None => {
+                        failed += 1;
+                        err_out!("⚠️  {}: {}", result.tx_sig, result.error);
+                    }
+                },
+                Some(batch_stream_message::Message::Progress(progress)) => {
+                    out!("⏳ {}/{} transactions", done + progress.processed as usize, signatures.len());
+                }
+                None => {}
+            }
         }
+        done += chunk.len();
     }
 
-    println!("\n✅ Done!");
+    out!("\n📊 Compute Units per Program ({} transactions, {} failed):", aggregator.transactions(), failed);
+    out!("{}", "=".repeat(80));
+    let labels = ProgramLabels::new();
+    for stats in aggregator.stats() {
+        let label = Pubkey::from_str(&stats.program_id)
+            .ok()
+            .and_then(|program_id| labels.get(&program_id))
+            .filter(|_| args.labels);
+        match label {
+            Some(label) => out!("{} ({})", stats.program_id, label),
+            None => out!("{}", stats.program_id),
+        }
+        out!(
+            "  {} txs | avg {:.0} | min {} | p50 {} | p90 {} | p99 {} | max {} | total {}",
+            stats.transactions, stats.average, stats.min, stats.p50, stats.p90, stats.p99, stats.max, stats.total
+        );
+    }
 
+    out!("\n✅ Done!");
     Ok(())
 }
 
+/// Print `logs` numbered, the last `tail` of them or all, and at most
+/// --max-display lines; notes how many lines were skipped on either side
+fn print_logs(logs: &[String], tail: Option<usize>, args: &Args) {
+    let start = tail.map_or(0, |tail| logs.len().saturating_sub(tail));
+    if start > 0 {
+        out!("... {} earlier logs hidden (--tail)", start);
+    }
+
+    let shown = args.max_display.unwrap_or(usize::MAX);
+    for (idx, log) in logs.iter().enumerate().skip(start).take(shown) {
+        out!("[{}] {}", idx + 1, log);
+    }
+
+    let hidden = (logs.len() - start).saturating_sub(shown);
+    if hidden > 0 {
+        out!("… {} more (use --json to capture all)", hidden);
+    }
+}
+
+/// `text` as it should be printed: unchanged by default; under --plain
+/// without the emoji leading each line, and `None` for separator lines
+fn plain_text(text: &str) -> Option<String> {
+    if !PLAIN.load(Ordering::Relaxed) {
+        return Some(text.to_string());
+    }
+    if !text.is_empty() && text.chars().all(|c| c == '=') {
+        return None;
+    }
+
+    let lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            let body = line.trim_start();
+            let indent = &line[..line.len() - body.len()];
+            let stripped = body.trim_start_matches(is_emoji);
+            if stripped.len() == body.len() {
+                line.to_string()
+            } else {
+                format!("{}{}", indent, stripped.trim_start())
+            }
+        })
+        .collect();
+    Some(lines.join("\n"))
+}
+
+/// Emoji, symbols and the joiners and variation selectors they come with
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x2300..=0x23FF | 0x2600..=0x27BF | 0x1F000..=0x1FAFF | 0x200D | 0xFE0F
+    )
+}
+
+fn print_explorer_logs(raw_logs: &[String]) {
+    out!("\n🔭 Explorer Logs:");
+    out!("{}", "=".repeat(80));
+    // Multi-line and already newline-terminated, so not through `out!`
+    if !QUIET.load(Ordering::Relaxed) {
+        print!("{}", odin::invoke::format_explorer_style(raw_logs));
+    }
+}
+
+fn print_tx_error(error: &proto::TxError) {
+    out!("❌ Transaction Failed: {}", error.error);
+    if let Some(idx) = error.instruction_index {
+        if error.program_id.is_empty() {
+            out!("  At instruction #{}", idx);
+        } else {
+            out!("  At instruction #{} ({})", idx, error.program_id);
+        }
+    }
+    out!();
+}
+
 /// Test streaming mode
 async fn test_streaming(
     mut client: SolanaTxLogClient<tonic::transport::Channel>,
@@ -173,77 +1376,475 @@ async fn test_streaming(
     include_cu_logs: bool,
     filter: String,
     show_raw_logs: bool,
+    args: &Args,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    println!("\n🌊 STREAMING MODE");
-    println!("📡 Program: {}", program_address);
-    println!("🌐 RPC: {}", rpc_url);
+    out!("\n🌊 STREAMING MODE");
+    out!("📡 Program: {}", program_address);
+    out!("🌐 RPC: {}", rpc_url);
     
     if !filter.is_empty() {
-        println!("🔍 Filter: {}", filter);
+        out!("🔍 Filter: {}", filter);
     }
     
     if include_cu_logs {
-        println!("⚡ Including compute unit logs");
+        out!("⚡ Including compute unit logs");
     }
+
+    if args.errors_only {
+        out!("🚨 Errors-only mode: skipping successful transactions");
+    }
+
+    if args.track_finalization {
+        out!("🔒 Tracking finalization of streamed transactions");
+    }
+
+    if args.flatten_logs {
+        out!("🧵 Flattened: one message per log line");
+    }
+
+    // Resume from the checkpoint, re-reading its slot so nothing is missed
+    let checkpoint = if args.checkpoint.is_empty() {
+        None
+    } else {
+        let checkpoint = Checkpoint::load(&args.checkpoint)?.unwrap_or_default();
+        if checkpoint.slot > 0 {
+            out!("📍 Resuming from slot {} (last signature {})", checkpoint.slot, checkpoint.signature);
+        }
+        Some(checkpoint)
+    };
+    let since_slot = checkpoint.as_ref().map_or(0, |c| c.slot);
     
-    println!("\n⏳ Subscribing to real-time logs...\n");
+    out!("\n⏳ Subscribing to real-time logs...\n");
 
     let request = tonic::Request::new(StreamProgramRequest {
         rpc_url,
         program_address: program_address.clone(),
         include_cu_logs,
         filter,
+        errors_only: args.errors_only,
+        since_slot,
+        track_finalization: args.track_finalization,
+        include_labels: args.labels,
+        flatten_logs: args.flatten_logs,
+        exclude_pattern: args.exclude_patterns.clone(),
+        ws_url: args.ws_url.clone(),
+        log_order: args.log_order.clone(),
+        fetch_retries: args.fetch_retries,
+        fetch_retry_ms: args.fetch_retry_ms,
+        raw_filter: args.raw_filter.clone(),
+        commitment: args.commitment.clone(),
     });
 
     let mut stream = client.stream_program_logs(request).await?.into_inner();
 
-    println!("✅ Subscribed! Waiting for transactions...\n");
+    out!("✅ Subscribed! Waiting for transactions...\n");
 
-    let mut count = 0;
-    while let Some(tx_response) = stream.message().await? {
-        count += 1;
-        
-        println!("{}", "=".repeat(80));
-        println!("📨 Transaction #{}: {}", count, tx_response.signature);
+    let mut sink = StreamSink::new(checkpoint, args)?;
+    match args.summary_interval.filter(|secs| *secs > 0) {
+        Some(secs) => {
+            let period = Duration::from_secs(secs);
+            let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+            sink.summary = Some(RollingSummary::new());
+            loop {
+                tokio::select! {
+                    message = stream.message() => match message? {
+                        Some(tx_response) => sink.handle(&tx_response, show_raw_logs, args)?,
+                        None => break,
+                    },
+                    _ = ticker.tick() => {
+                        if let Some(ref mut summary) = sink.summary {
+                            summary.print_and_reset();
+                        }
+                    }
+                }
+            }
+        }
+        None => {
+            while let Some(tx_response) = stream.message().await? {
+                sink.handle(&tx_response, show_raw_logs, args)?;
+            }
+        }
+    }
+    sink.finish(args)?;
+
+    out!("🛑 Stream ended");
+    Ok(())
+}
+
+/// Subscribe to `program` and save the raw `getTransaction` result of the
+/// next `count` transactions to `dir`, for use as test fixtures
+async fn record_fixtures(
+    program: &str,
+    dir: &str,
+    count: usize,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use futures_util::StreamExt;
+
+    let ws_url = if args.ws_url.is_empty() {
+        ws_url_from_rpc(&args.rpc_url)
+    } else {
+        args.ws_url.clone()
+    };
+
+    out!("\n⏺️  RECORD MODE");
+    out!("🎯 Program: {}", program);
+    out!("🌐 Using RPC: {} ({})", args.rpc_url, ws_url);
+    out!("📁 Saving {} transaction(s) to {}\n", count, dir);
+
+    let commitment = parse_commitment(&args.commitment)?;
+    std::fs::create_dir_all(dir)?;
+    let options = SubscribeOptions {
+        commitment: commitment.commitment.to_string(),
+        ..Default::default()
+    };
+    let notifications = subscribe_logs(ws_url, program.to_string(), options);
+    tokio::pin!(notifications);
+
+    let mut recorded = 0;
+    while recorded < count {
+        let Some(notification) = notifications.next().await else {
+            break;
+        };
+        let notification = notification?;
+
+        // The notification can beat the transaction to the RPC's index
+        let parser = TxLogParser::new(args.rpc_url.clone(), notification.signature.clone(), None, false)
+            .with_commitment(commitment);
+        let mut retries = 0;
+        let value = loop {
+            match parser.fetch_json().await {
+                Err(ParseError::NotFound(_) | ParseError::NodeBehind(_)) if retries < RECORD_FETCH_RETRIES => {
+                    retries += 1;
+                    tokio::time::sleep(RECORD_FETCH_RETRY_DELAY).await;
+                }
+                value => break value,
+            }
+        };
+        let value = match value {
+            Ok(value) => value,
+            Err(e) => {
+                err_out!("⏭️  Skipping {}: {}", notification.signature, e);
+                continue;
+            }
+        };
+
+        let path = Path::new(dir).join(format!("{}.json", notification.signature));
+        std::fs::write(&path, serde_json::to_string_pretty(&value)?)?;
+        recorded += 1;
+        out!("💾 [{}/{}] {}", recorded, count, path.display());
+    }
+
+    out!("\n✅ Recorded {} fixture(s)", recorded);
+    Ok(())
+}
+
+/// Replay a stream captured with `--capture`, feeding each message through
+/// the same display as a live stream. With a speed, the gaps between the
+/// recorded timestamps are reproduced, divided by the speed.
+async fn replay_stream(
+    path: &str,
+    speed: Option<f64>,
+    show_raw_logs: bool,
+    args: &Args,
+) -> Result<(), Box<dyn std::error::Error>> {
+    out!("\n⏪ REPLAY MODE");
+    out!("📂 Capture: {}", path);
+    match speed {
+        Some(speed) => out!("⏱️  Speed: {}x\n", speed),
+        None => out!("⏱️  Speed: as fast as possible\n"),
+    }
+
+    let file = std::fs::File::open(path)?;
+    let mut sink = StreamSink::new(None, args)?;
+    let mut previous: Option<chrono::DateTime<chrono::FixedOffset>> = None;
+
+    for (line_no, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let tx_response: StreamTransactionResponse = serde_json::from_str(&line)
+            .map_err(|e| format!("Invalid capture line {}: {}", line_no + 1, e))?;
+
+        if let Some(speed) = speed {
+            let timestamp = chrono::DateTime::parse_from_rfc3339(&tx_response.timestamp).ok();
+            if let (Some(previous), Some(timestamp)) = (previous, timestamp)
+                && let Ok(gap) = (timestamp - previous).to_std()
+            {
+                tokio::time::sleep(gap.div_f64(speed)).await;
+            }
+            if timestamp.is_some() {
+                previous = timestamp;
+            }
+        }
+
+        sink.handle(&tx_response, show_raw_logs, args)?;
+    }
+    sink.finish(args)?;
+
+    out!("🛑 Replay ended");
+    Ok(())
+}
+
+/// Where streamed messages go: the terminal, the checkpoint file and the
+/// `--capture` file. Shared by live streaming and replay.
+struct StreamSink {
+    count: usize,
+    checkpoint: Option<Checkpoint>,
+    last_checkpoint_write: Instant,
+    capture: Option<BufWriter<std::fs::File>>,
+    /// Set with --summary-interval
+    summary: Option<RollingSummary>,
+}
+
+impl StreamSink {
+    fn new(checkpoint: Option<Checkpoint>, args: &Args) -> Result<Self, Box<dyn std::error::Error>> {
+        let capture = if args.capture.is_empty() {
+            None
+        } else {
+            out!("💾 Capturing stream to {}\n", args.capture);
+            Some(BufWriter::new(
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&args.capture)?,
+            ))
+        };
+
+        Ok(StreamSink {
+            count: 0,
+            checkpoint,
+            last_checkpoint_write: Instant::now(),
+            capture,
+            summary: None,
+        })
+    }
+
+    fn handle(
+        &mut self,
+        tx_response: &StreamTransactionResponse,
+        show_raw_logs: bool,
+        args: &Args,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Record every message as one JSON line, status updates included
+        if let Some(ref mut capture) = self.capture {
+            serde_json::to_writer(&mut *capture, tx_response)?;
+            capture.write_all(b"\n")?;
+            capture.flush()?;
+        }
+
+        // Newline-delimited JSON under --format json, status updates included;
+        // the text below is suppressed, checkpoints are still written and
+        // summaries go to stderr
+        if args.format == OutputFormat::Json {
+            print_response_json(tx_response)?;
+        }
+
+        // Follow-up status for a transaction streamed earlier
+        if let Some(ref update) = tx_response.status_update {
+            let icon = match update.status.as_str() {
+                "finalized" => "🔒",
+                "dropped" => "⚠️ ",
+                _ => "⏳",
+            };
+            out!("{} Status update for {}: {}\n", icon, tx_response.signature, update.status);
+            return Ok(());
+        }
+
+        // A single log line from a flattened stream
+        if let Some(log_index) = tx_response.log_index {
+            out!("[{} @ {}] #{} {}", tx_response.signature, tx_response.slot, log_index + 1, tx_response.log_line);
+            return Ok(());
+        }
+
+        self.count += 1;
+        if let Some(ref mut summary) = self.summary {
+            summary.add(tx_response);
+        }
+
+        // Track the stream position and persist it periodically
+        if let Some(ref mut checkpoint) = self.checkpoint {
+            checkpoint.slot = tx_response.slot;
+            checkpoint.signature = tx_response.signature.clone();
+
+            if self.last_checkpoint_write.elapsed() >= CHECKPOINT_INTERVAL {
+                checkpoint.save(&args.checkpoint)?;
+                self.last_checkpoint_write = Instant::now();
+            }
+        }
+
+        out!("{}", "=".repeat(80));
+        out!("📨 Transaction #{}: {}", self.count, tx_response.signature);
+        if tx_response.slot > 0 {
+            out!("🎰 Slot: {}", tx_response.slot);
+        }
         if !tx_response.timestamp.is_empty() {
-            println!("🕐 Timestamp: {}", tx_response.timestamp);
+            out!("🕐 Timestamp: {}", tx_response.timestamp);
+        }
+        out!("{}", "=".repeat(80));
+
+        // Display where the transaction failed, if it did
+        if let Some(ref error) = tx_response.error {
+            print_tx_error(error);
+        }
+
+        if tx_response.truncated {
+            out!("✂️  Logs truncated: the transaction exceeded the server's log limit\n");
+        }
+        if tx_response.limited {
+            out!("🛑 Results limited: the transaction exceeded the server's work budget\n");
         }
-        println!("{}", "=".repeat(80));
 
         // Display compute unit logs if included
         if !tx_response.compute_units.is_empty() {
-            println!("\n⚡ Compute Unit Logs:");
-            println!("{}", "=".repeat(80));
+            out!("\n⚡ Compute Unit Logs:");
+            out!("{}", "=".repeat(80));
             for cu_log in tx_response.compute_units.iter() {
-                println!("Program ID: {}", cu_log.program_id);
-                println!("  Consumed: {} compute units", cu_log.consumed);
+                if cu_log.label.is_empty() {
+                    out!("Program ID: {}", cu_log.program_id);
+                } else {
+                    out!("Program ID: {} ({})", cu_log.program_id, cu_log.label);
+                }
+                out!("  Consumed: {} compute units", cu_log.consumed);
+                if let Some(budget) = cu_log.budget {
+                    out!("  Budget: {} compute units", budget);
+                }
             }
         }
 
+        print_cu_ranking(&tx_response.cu_ranking);
+
+        // Display the programs invoked by the transaction
+        if !tx_response.programs.is_empty() {
+            let programs: Vec<String> = tx_response
+                .programs
+                .iter()
+                .map(|program| match tx_response.program_labels.get(program) {
+                    Some(label) => format!("{} ({})", program, label),
+                    None => program.clone(),
+                })
+                .collect();
+            out!("\n🧩 Programs Invoked: {}", programs.join(", "));
+        }
+
         // Display the program instruction logs
-        println!("\n📋 Program Instruction Logs:");
-        println!("{}", "=".repeat(80));
-        
+        out!("\n📋 Program Instruction Logs:");
+        out!("{}", "=".repeat(80));
+
         if tx_response.logs.is_empty() {
-            println!("No logs found (or all filtered out)");
+            out!("{}", no_logs_message(&tx_response.log_source));
         } else {
-            for (idx, log) in tx_response.logs.iter().enumerate() {
-                println!("[{}] {}", idx + 1, log);
-            }
+            print_logs(&tx_response.logs, args.tail, args);
         }
 
-        // Display raw transaction logs (optional)
-        if show_raw_logs && !tx_response.raw_logs.is_empty() {
-            println!("\n📜 Raw Transaction Logs:");
-            println!("{}", "=".repeat(80));
-            for (idx, log) in tx_response.raw_logs.iter().enumerate() {
-                println!("[{}] {}", idx + 1, log);
-            }
+        print_logs_by_severity(tx_response.logs_by_severity.as_ref());
+
+        // Display logs in Explorer style, or the raw logs (optional)
+        if args.explorer {
+            print_explorer_logs(&tx_response.raw_logs);
+        } else if show_raw_logs && !tx_response.raw_logs.is_empty() {
+            out!("\n📜 Raw Transaction Logs:");
+            out!("{}", "=".repeat(80));
+            print_logs(&tx_response.raw_logs, args.tail.filter(|_| args.tail_raw), args);
         }
 
-        println!("\n");
+        print_anchor_events(&tx_response.anchor_events);
+        print_return_data(&tx_response.return_data);
+
+        out!("\n");
+        Ok(())
     }
 
-    println!("🛑 Stream ended");
-    Ok(())
+    /// Persist the final stream position
+    fn finish(&self, args: &Args) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(ref checkpoint) = self.checkpoint {
+            checkpoint.save(&args.checkpoint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Stream activity since the last --summary-interval summary
+struct RollingSummary {
+    transactions: usize,
+    failed: usize,
+    cu_total: u64,
+    /// Transactions that reported their compute units
+    cu_reported: usize,
+    by_program: HashMap<String, u64>,
+    started: Instant,
+}
+
+impl RollingSummary {
+    fn new() -> Self {
+        RollingSummary {
+            transactions: 0,
+            failed: 0,
+            cu_total: 0,
+            cu_reported: 0,
+            by_program: HashMap::new(),
+            started: Instant::now(),
+        }
+    }
+
+    fn add(&mut self, tx_response: &StreamTransactionResponse) {
+        self.transactions += 1;
+        if tx_response.error.is_some() {
+            self.failed += 1;
+        }
+        if let Some(consumed) = tx_response.compute_units_consumed {
+            self.cu_total += consumed;
+            self.cu_reported += 1;
+        }
+        for rank in tx_response.cu_ranking.iter() {
+            *self.by_program.entry(rank.program_id.clone()).or_default() += rank.consumed;
+        }
+    }
+
+    fn print_and_reset(&mut self) {
+        let elapsed = self.started.elapsed();
+        let rate = if elapsed.is_zero() {
+            0.0
+        } else {
+            self.transactions as f64 / elapsed.as_secs_f64()
+        };
+
+        let mut lines = vec![
+            "-".repeat(80),
+            format!("📊 Last {:.0}s: {} transaction(s), {:.2} tx/s", elapsed.as_secs_f64(), self.transactions, rate),
+        ];
+        if self.transactions > 0 {
+            let succeeded = self.transactions - self.failed;
+            lines.push(format!(
+                "  Success rate: {:.1}% ({} failed)",
+                succeeded as f64 * 100.0 / self.transactions as f64,
+                self.failed
+            ));
+        }
+        if self.cu_reported > 0 {
+            lines.push(format!("  Average CU: {:.0}", self.cu_total as f64 / self.cu_reported as f64));
+        }
+
+        let mut programs: Vec<(&String, &u64)> = self.by_program.iter().collect();
+        programs.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (program_id, consumed) in programs.into_iter().take(SUMMARY_TOP_PROGRAMS) {
+            lines.push(format!("  {:<44} {:>12} CU", program_id, consumed));
+        }
+        lines.push(format!("{}\n", "-".repeat(80)));
+
+        // Under --format json stdout holds only the JSON, so the summary
+        // goes to stderr
+        let to_stderr = QUIET.load(Ordering::Relaxed);
+        for line in lines {
+            if to_stderr {
+                err_out!("{}", line);
+            } else {
+                out!("{}", line);
+            }
+        }
+
+        *self = RollingSummary::new();
+    }
 }
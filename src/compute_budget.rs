@@ -0,0 +1,257 @@
+use serde_json::Value;
+
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Base fee charged per signature, in lamports
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+// Compute unit limit granted per instruction without `SetComputeUnitLimit`,
+// and the cap on any transaction's limit
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Compute budget requested by a transaction's Compute Budget instructions
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// `SetComputeUnitLimit`, absent when the runtime default applies
+    pub unit_limit: Option<u32>,
+    /// `SetComputeUnitPrice`, in micro-lamports per compute unit
+    pub unit_price: Option<u64>,
+    /// `RequestHeapFrame`, in bytes
+    pub heap_frame: Option<u32>,
+    /// `SetLoadedAccountsDataSizeLimit`, in bytes
+    pub loaded_accounts_data_size_limit: Option<u32>,
+}
+
+impl ComputeBudget {
+    /// Whether the transaction set its compute unit limit instead of getting
+    /// the default of 200k per instruction
+    pub fn is_explicit(&self) -> bool {
+        self.unit_limit.is_some()
+    }
+
+    /// Collect the Compute Budget instructions from the JSON form of a
+    /// transaction. Handles both `json` (program id index into the account
+    /// keys) and `jsonParsed` (program id inline) encodings.
+    pub fn from_transaction_json(tx: &Value) -> Self {
+        let mut budget = ComputeBudget::default();
+
+        let Some(message) = tx.get("message") else {
+            return budget;
+        };
+
+        let account_keys: Vec<&str> = message
+            .get("accountKeys")
+            .and_then(|keys| keys.as_array())
+            .map(|keys| {
+                keys.iter()
+                    .filter_map(|key| key.as_str().or_else(|| key.get("pubkey")?.as_str()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let instructions = message
+            .get("instructions")
+            .and_then(|instructions| instructions.as_array())
+            .map(|instructions| instructions.as_slice())
+            .unwrap_or_default();
+
+        for instruction in instructions {
+            let program_id = instruction
+                .get("programId")
+                .and_then(|program_id| program_id.as_str())
+                .or_else(|| {
+                    let idx = instruction.get("programIdIndex")?.as_u64()?;
+                    account_keys.get(idx as usize).copied()
+                });
+
+            if program_id != Some(COMPUTE_BUDGET_PROGRAM_ID) {
+                continue;
+            }
+
+            let data = instruction
+                .get("data")
+                .and_then(|data| data.as_str())
+                .and_then(|data| bs58::decode(data).into_vec().ok());
+
+            if let Some(data) = data {
+                budget.decode_instruction(&data);
+            }
+        }
+
+        budget
+    }
+
+    /// Compute unit limit the runtime applies: the requested one, or 200k
+    /// per instruction (Compute Budget ones excluded), capped at 1.4M
+    pub fn effective_unit_limit(&self, instructions: usize) -> u64 {
+        self.unit_limit
+            .map_or_else(
+                || DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT.saturating_mul(instructions as u64),
+                u64::from,
+            )
+            .min(MAX_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// Priority fee in lamports: the unit price times the effective limit,
+    /// rounded up. 0 when no price was set.
+    pub fn priority_fee(&self, instructions: usize) -> u64 {
+        let Some(unit_price) = self.unit_price else {
+            return 0;
+        };
+
+        let micro_lamports = unit_price as u128 * self.effective_unit_limit(instructions) as u128;
+        micro_lamports
+            .div_ceil(MICRO_LAMPORTS_PER_LAMPORT)
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Apply one borsh-encoded Compute Budget instruction
+    pub fn decode_instruction(&mut self, data: &[u8]) {
+        let Some((&tag, rest)) = data.split_first() else {
+            return;
+        };
+
+        let read_u32 = || rest.get(..4).map(|b| u32::from_le_bytes(b.try_into().unwrap()));
+        let read_u64 = || rest.get(..8).map(|b| u64::from_le_bytes(b.try_into().unwrap()));
+
+        match tag {
+            1 => self.heap_frame = read_u32(),
+            2 => self.unit_limit = read_u32(),
+            3 => self.unit_price = read_u64(),
+            4 => self.loaded_accounts_data_size_limit = read_u32(),
+            _ => {}
+        }
+    }
+}
+
+/// What a transaction cost, in lamports
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CostBreakdown {
+    /// Signature fees
+    pub base_fee: u64,
+    /// Compute unit price times limit; 0 without a price
+    pub priority_fee: u64,
+    pub total_fee: u64,
+    /// Compute units consumed, when reported
+    pub compute_units: Option<u64>,
+    /// Total fee per compute unit consumed, when any were
+    pub lamports_per_cu: Option<f64>,
+}
+
+impl CostBreakdown {
+    /// Split the fee a landed transaction paid (its meta `fee`) into the
+    /// signature fees and the priority fee on top
+    pub fn from_fee(fee: u64, signatures: usize, compute_units: Option<u64>) -> Self {
+        let base_fee = LAMPORTS_PER_SIGNATURE
+            .saturating_mul(signatures as u64)
+            .min(fee);
+        Self::new(base_fee, fee - base_fee, compute_units)
+    }
+
+    /// Estimate the fee of a transaction that hasn't landed, e.g. a
+    /// simulation. `instructions` excludes Compute Budget instructions.
+    pub fn estimate(
+        signatures: usize,
+        budget: &ComputeBudget,
+        instructions: usize,
+        compute_units: Option<u64>,
+    ) -> Self {
+        Self::new(
+            LAMPORTS_PER_SIGNATURE.saturating_mul(signatures as u64),
+            budget.priority_fee(instructions),
+            compute_units,
+        )
+    }
+
+    fn new(base_fee: u64, priority_fee: u64, compute_units: Option<u64>) -> Self {
+        let total_fee = base_fee.saturating_add(priority_fee);
+        CostBreakdown {
+            base_fee,
+            priority_fee,
+            total_fee,
+            compute_units,
+            lamports_per_cu: compute_units
+                .filter(|&units| units > 0)
+                .map(|units| total_fee as f64 / units as f64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_compute_budget_from_json_encoding() {
+        let mut set_limit = vec![2];
+        set_limit.extend_from_slice(&300_000u32.to_le_bytes());
+        let mut set_price = vec![3];
+        set_price.extend_from_slice(&5_000u64.to_le_bytes());
+
+        let tx = json!({
+            "signatures": [],
+            "message": {
+                "accountKeys": [
+                    "11111111111111111111111111111111",
+                    COMPUTE_BUDGET_PROGRAM_ID
+                ],
+                "instructions": [
+                    {"programIdIndex": 1, "accounts": [], "data": bs58::encode(&set_limit).into_string()},
+                    {"programIdIndex": 1, "accounts": [], "data": bs58::encode(&set_price).into_string()},
+                    {"programIdIndex": 0, "accounts": [], "data": "3Bxs4h24hBtQy9rw"}
+                ]
+            }
+        });
+
+        let budget = ComputeBudget::from_transaction_json(&tx);
+
+        assert!(budget.is_explicit());
+        assert_eq!(budget.unit_limit, Some(300_000));
+        assert_eq!(budget.unit_price, Some(5_000));
+        assert_eq!(budget.heap_frame, None);
+    }
+
+    #[test]
+    fn test_default_budget_is_not_explicit() {
+        let tx = json!({
+            "message": {
+                "accountKeys": [{"pubkey": "11111111111111111111111111111111"}],
+                "instructions": [
+                    {"programId": "11111111111111111111111111111111", "parsed": {}}
+                ]
+            }
+        });
+
+        assert!(!ComputeBudget::from_transaction_json(&tx).is_explicit());
+    }
+
+    #[test]
+    fn test_cost_breakdown() {
+        // 5,000 micro-lamports over 300k units is 1,500 lamports
+        let budget = ComputeBudget {
+            unit_limit: Some(300_000),
+            unit_price: Some(5_000),
+            ..Default::default()
+        };
+        let estimate = CostBreakdown::estimate(1, &budget, 1, Some(150_000));
+        assert_eq!(estimate.base_fee, 5_000);
+        assert_eq!(estimate.priority_fee, 1_500);
+        assert_eq!(estimate.total_fee, 6_500);
+        assert_eq!(estimate.lamports_per_cu, Some(6_500.0 / 150_000.0));
+
+        // No price: no priority fee, and the default limit is capped
+        let no_price = ComputeBudget::default();
+        assert_eq!(no_price.priority_fee(3), 0);
+        assert_eq!(no_price.effective_unit_limit(10), 1_400_000);
+
+        let paid = CostBreakdown::from_fee(10_000, 1, Some(0));
+        assert_eq!(paid.base_fee, 5_000);
+        assert_eq!(paid.priority_fee, 5_000);
+        assert_eq!(paid.lamports_per_cu, None);
+    }
+}
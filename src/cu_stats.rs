@@ -0,0 +1,188 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use solana_sdk::pubkey::Pubkey;
+
+use crate::output::ParsedTransaction;
+use crate::parser::{TxLogParser, VOTE_PROGRAM_ID, builtin_compute_units};
+
+/// Compute unit usage of one program across the aggregated transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProgramCuStats {
+    pub program_id: String,
+    /// Transactions the program reported compute units in
+    pub transactions: usize,
+    pub total: u64,
+    pub average: f64,
+    pub min: u64,
+    pub max: u64,
+    /// Nearest-rank percentiles of the per-transaction usage
+    pub p50: u64,
+    pub p90: u64,
+    pub p99: u64,
+}
+
+/// Collects per-program compute units from many parsed transactions, e.g.
+/// to answer how much a program typically uses. Transactions need to have
+/// been parsed with `include_cu_logs`. Vote transactions, those invoking the
+/// Vote program, are skipped unless `with_vote_program(true)` is set.
+#[derive(Debug, Clone, Default)]
+pub struct ComputeUnitAggregator {
+    samples: BTreeMap<String, Vec<u64>>,
+    transactions: usize,
+    include_vote_program: bool,
+}
+
+impl ComputeUnitAggregator {
+    pub fn new() -> Self {
+        ComputeUnitAggregator::default()
+    }
+
+    /// Aggregate vote transactions too. The Vote program is a builtin and
+    /// logs no `consumed` line, so its usage is taken as its per-instruction
+    /// cost for each of its invocations.
+    pub fn with_vote_program(mut self, include: bool) -> Self {
+        self.include_vote_program = include;
+        self
+    }
+
+    pub fn add(&mut self, parsed: &ParsedTransaction) {
+        let is_vote = parsed
+            .programs_invoked
+            .iter()
+            .any(|program_id| program_id == VOTE_PROGRAM_ID);
+        if is_vote && !self.include_vote_program {
+            return;
+        }
+
+        self.transactions += 1;
+        if is_vote {
+            let vote_program = Pubkey::from_str(VOTE_PROGRAM_ID).unwrap();
+            let invocations = TxLogParser::invocation_counts(&parsed.raw_logs);
+            let count = invocations.get(&vote_program).copied().unwrap_or(0);
+            if count > 0 {
+                self.samples
+                    .entry(VOTE_PROGRAM_ID.to_string())
+                    .or_default()
+                    .push(builtin_compute_units(&vote_program).unwrap_or(0) * count as u64);
+            }
+        }
+        for compute_units in parsed.compute_units.iter() {
+            self.samples
+                .entry(compute_units.program_id.clone())
+                .or_default()
+                .push(compute_units.consumed);
+        }
+    }
+
+    /// Number of transactions aggregated, with or without compute units;
+    /// skipped vote transactions don't count
+    pub fn transactions(&self) -> usize {
+        self.transactions
+    }
+
+    /// Statistics per program, highest total first, ties broken by program id
+    pub fn stats(&self) -> Vec<ProgramCuStats> {
+        let mut stats: Vec<ProgramCuStats> = self
+            .samples
+            .iter()
+            .map(|(program_id, samples)| {
+                let mut sorted = samples.clone();
+                sorted.sort_unstable();
+                let total: u64 = sorted.iter().sum();
+
+                ProgramCuStats {
+                    program_id: program_id.clone(),
+                    transactions: sorted.len(),
+                    total,
+                    average: total as f64 / sorted.len() as f64,
+                    min: sorted[0],
+                    max: sorted[sorted.len() - 1],
+                    p50: percentile(&sorted, 50),
+                    p90: percentile(&sorted, 90),
+                    p99: percentile(&sorted, 99),
+                }
+            })
+            .collect();
+
+        // BTreeMap order already sorts by program id, and the sort is stable
+        stats.sort_by_key(|stat| std::cmp::Reverse(stat.total));
+        stats
+    }
+}
+
+impl<'a> Extend<&'a ParsedTransaction> for ComputeUnitAggregator {
+    fn extend<I: IntoIterator<Item = &'a ParsedTransaction>>(&mut self, iter: I) {
+        for parsed in iter {
+            self.add(parsed);
+        }
+    }
+}
+
+/// Nearest-rank percentile of non-empty sorted samples
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::ParsedComputeUnits;
+
+    /// A vote transaction as the RPC returns it: builtins log only their
+    /// invoke and success lines
+    fn vote_transaction() -> ParsedTransaction {
+        ParsedTransaction {
+            raw_logs: vec![
+                format!("Program {} invoke [1]", VOTE_PROGRAM_ID),
+                format!("Program {} success", VOTE_PROGRAM_ID),
+            ],
+            programs_invoked: vec![VOTE_PROGRAM_ID.to_string()],
+            compute_units_consumed: Some(2100),
+            ..Default::default()
+        }
+    }
+
+    fn parsed(usage: &[(&str, u64)]) -> ParsedTransaction {
+        ParsedTransaction {
+            compute_units: usage
+                .iter()
+                .map(|(program_id, consumed)| ParsedComputeUnits {
+                    program_id: program_id.to_string(),
+                    consumed: *consumed,
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_aggregate_programs_across_transactions() {
+        let mut aggregator = ComputeUnitAggregator::new();
+        for consumed in 1..=10 {
+            aggregator.add(&parsed(&[("token", consumed * 100), ("memo", 50)]));
+        }
+        aggregator.add(&parsed(&[]));
+        aggregator.add(&vote_transaction());
+
+        let stats = aggregator.stats();
+        assert_eq!(aggregator.transactions(), 11);
+        assert_eq!(stats.len(), 2);
+
+        let token = &stats[0];
+        assert_eq!(token.program_id, "token");
+        assert_eq!(token.transactions, 10);
+        assert_eq!(token.total, 5500);
+        assert_eq!(token.average, 550.0);
+        assert_eq!((token.min, token.max), (100, 1000));
+        assert_eq!((token.p50, token.p90, token.p99), (500, 900, 1000));
+        assert_eq!(stats[1].p50, 50);
+
+        let mut with_votes = ComputeUnitAggregator::new().with_vote_program(true);
+        with_votes.add(&vote_transaction());
+        assert_eq!(with_votes.transactions(), 1);
+        assert_eq!(with_votes.stats()[0].program_id, VOTE_PROGRAM_ID);
+        assert_eq!(with_votes.stats()[0].total, 2100);
+    }
+}
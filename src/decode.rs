@@ -0,0 +1,526 @@
+use serde_json::Value;
+
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const ADDRESS_LOOKUP_TABLE_PROGRAM_ID: &str = "AddressLookupTab1e1111111111111111111111111";
+
+/// A System, SPL Token or Address Lookup Table instruction decoded into
+/// named fields
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DecodedInstruction {
+    /// `system`, `spl-token`, `spl-token-2022` or `address-lookup-table`
+    pub program: String,
+    /// Instruction name, e.g. `Transfer` or `InitializeMint`
+    pub name: String,
+    /// Accounts and arguments as `(name, value)`, in instruction order
+    pub fields: Vec<(String, String)>,
+}
+
+/// Short name of the programs whose instructions can be decoded
+pub fn program_name(program_id: &str) -> Option<&'static str> {
+    match program_id {
+        SYSTEM_PROGRAM_ID => Some("system"),
+        TOKEN_PROGRAM_ID => Some("spl-token"),
+        TOKEN_2022_PROGRAM_ID => Some("spl-token-2022"),
+        ADDRESS_LOOKUP_TABLE_PROGRAM_ID => Some("address-lookup-table"),
+        _ => None,
+    }
+}
+
+/// Decode the top-level System, Token and Address Lookup Table instructions
+/// from the JSON form of a transaction. Instructions the RPC already parsed
+/// (`jsonParsed`) are taken as is; raw ones (`json`) are decoded from their
+/// data.
+pub fn decode_transaction_json(tx: &Value) -> Vec<DecodedInstruction> {
+    let Some(message) = tx.get("message") else {
+        return Vec::new();
+    };
+
+    let account_keys: Vec<&str> = message
+        .get("accountKeys")
+        .and_then(|keys| keys.as_array())
+        .map(|keys| {
+            keys.iter()
+                .filter_map(|key| key.as_str().or_else(|| key.get("pubkey")?.as_str()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let instructions = message
+        .get("instructions")
+        .and_then(|instructions| instructions.as_array())
+        .map(|instructions| instructions.as_slice())
+        .unwrap_or_default();
+
+    instructions
+        .iter()
+        .filter_map(|instruction| {
+            let program_id = instruction
+                .get("programId")
+                .and_then(|program_id| program_id.as_str())
+                .or_else(|| {
+                    let idx = instruction.get("programIdIndex")?.as_u64()?;
+                    account_keys.get(idx as usize).copied()
+                })?;
+            let program = program_name(program_id)?;
+
+            if let Some(parsed) = instruction.get("parsed") {
+                return from_parsed_json(program, parsed);
+            }
+
+            let accounts: Vec<String> = instruction
+                .get("accounts")
+                .and_then(|accounts| accounts.as_array())
+                .map(|accounts| {
+                    accounts
+                        .iter()
+                        .filter_map(|account| match account.as_u64() {
+                            Some(idx) => account_keys.get(idx as usize).map(|key| key.to_string()),
+                            None => account.as_str().map(|key| key.to_string()),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            let data = bs58::decode(instruction.get("data")?.as_str()?)
+                .into_vec()
+                .ok()?;
+
+            decode_instruction(program_id, &data, &accounts)
+        })
+        .collect()
+}
+
+/// Decode one System, Token or Address Lookup Table instruction from its raw
+/// data and account addresses. Returns `None` for other programs and unknown
+/// layouts.
+pub fn decode_instruction(
+    program_id: &str,
+    data: &[u8],
+    accounts: &[String],
+) -> Option<DecodedInstruction> {
+    let program = program_name(program_id)?;
+    let (name, account_names, args) = match program {
+        "system" => decode_system(data)?,
+        "address-lookup-table" => decode_lookup_table(data)?,
+        _ => decode_token(data)?,
+    };
+
+    let mut fields: Vec<(String, String)> = account_names
+        .iter()
+        .zip(accounts)
+        .map(|(name, account)| (name.to_string(), account.clone()))
+        .collect();
+    fields.extend(args);
+
+    Some(DecodedInstruction {
+        program: program.to_string(),
+        name: name.to_string(),
+        fields,
+    })
+}
+
+type Decoded = (&'static str, &'static [&'static str], Vec<(String, String)>);
+
+/// System instructions are bincode: a `u32` tag, then the arguments
+fn decode_system(data: &[u8]) -> Option<Decoded> {
+    let mut reader = Reader(data);
+    let tag = reader.u32()?;
+    let mut args = Vec::new();
+
+    let (name, accounts): (_, &[_]) = match tag {
+        0 => {
+            args.push(("lamports".to_string(), reader.u64()?.to_string()));
+            args.push(("space".to_string(), reader.u64()?.to_string()));
+            args.push(("owner".to_string(), reader.pubkey()?));
+            ("CreateAccount", &["source", "newAccount"])
+        }
+        1 => {
+            args.push(("owner".to_string(), reader.pubkey()?));
+            ("Assign", &["account"])
+        }
+        2 => {
+            args.push(("lamports".to_string(), reader.u64()?.to_string()));
+            ("Transfer", &["source", "destination"])
+        }
+        3 => ("CreateAccountWithSeed", &["source", "newAccount", "base"]),
+        4 => ("AdvanceNonceAccount", &["nonceAccount"]),
+        5 => {
+            args.push(("lamports".to_string(), reader.u64()?.to_string()));
+            ("WithdrawNonceAccount", &["nonceAccount", "destination"])
+        }
+        6 => {
+            args.push(("nonceAuthority".to_string(), reader.pubkey()?));
+            ("InitializeNonceAccount", &["nonceAccount"])
+        }
+        7 => {
+            args.push(("newAuthorized".to_string(), reader.pubkey()?));
+            ("AuthorizeNonceAccount", &["nonceAccount", "nonceAuthority"])
+        }
+        8 => {
+            args.push(("space".to_string(), reader.u64()?.to_string()));
+            ("Allocate", &["account"])
+        }
+        9 => ("AllocateWithSeed", &["account", "base"]),
+        10 => ("AssignWithSeed", &["account", "base"]),
+        11 => {
+            args.push(("lamports".to_string(), reader.u64()?.to_string()));
+            ("TransferWithSeed", &["source", "sourceBase", "destination"])
+        }
+        12 => ("UpgradeNonceAccount", &["nonceAccount"]),
+        _ => return None,
+    };
+
+    Some((name, accounts, args))
+}
+
+/// Address Lookup Table instructions are bincode like System ones: a `u32`
+/// tag, then the arguments
+fn decode_lookup_table(data: &[u8]) -> Option<Decoded> {
+    let mut reader = Reader(data);
+    let tag = reader.u32()?;
+    let mut args = Vec::new();
+
+    let (name, accounts): (_, &[_]) = match tag {
+        0 => {
+            args.push(("recentSlot".to_string(), reader.u64()?.to_string()));
+            args.push(("bumpSeed".to_string(), reader.u8()?.to_string()));
+            (
+                "CreateLookupTable",
+                &[
+                    "lookupTableAccount",
+                    "lookupTableAuthority",
+                    "payerAccount",
+                    "systemProgram",
+                ],
+            )
+        }
+        1 => (
+            "FreezeLookupTable",
+            &["lookupTableAccount", "lookupTableAuthority"],
+        ),
+        2 => {
+            // bincode `Vec<Pubkey>`: a `u64` length, then the keys
+            let len = reader.u64()?;
+            let new_addresses = (0..len)
+                .map(|_| reader.pubkey())
+                .collect::<Option<Vec<_>>>()?;
+            args.push(("newAddresses".to_string(), new_addresses.join(",")));
+            (
+                "ExtendLookupTable",
+                &[
+                    "lookupTableAccount",
+                    "lookupTableAuthority",
+                    "payerAccount",
+                    "systemProgram",
+                ],
+            )
+        }
+        3 => (
+            "DeactivateLookupTable",
+            &["lookupTableAccount", "lookupTableAuthority"],
+        ),
+        4 => (
+            "CloseLookupTable",
+            &["lookupTableAccount", "lookupTableAuthority", "recipient"],
+        ),
+        _ => return None,
+    };
+
+    Some((name, accounts, args))
+}
+
+/// Token instructions are a `u8` tag followed by packed arguments. Token-2022
+/// shares the layouts of the original program's instructions.
+fn decode_token(data: &[u8]) -> Option<Decoded> {
+    let mut reader = Reader(data);
+    let tag = reader.u8()?;
+    let mut args = Vec::new();
+
+    let (name, accounts): (_, &[_]) = match tag {
+        0 | 20 => {
+            args.push(("decimals".to_string(), reader.u8()?.to_string()));
+            args.push(("mintAuthority".to_string(), reader.pubkey()?));
+            if let Some(freeze_authority) = reader.optional_pubkey() {
+                args.push(("freezeAuthority".to_string(), freeze_authority));
+            }
+            if tag == 0 {
+                ("InitializeMint", &["mint", "rentSysvar"])
+            } else {
+                ("InitializeMint2", &["mint"])
+            }
+        }
+        1 => (
+            "InitializeAccount",
+            &["account", "mint", "owner", "rentSysvar"],
+        ),
+        2 => {
+            args.push(("m".to_string(), reader.u8()?.to_string()));
+            ("InitializeMultisig", &["multisig", "rentSysvar"])
+        }
+        3 => {
+            args.push(("amount".to_string(), reader.u64()?.to_string()));
+            ("Transfer", &["source", "destination", "authority"])
+        }
+        4 => {
+            args.push(("amount".to_string(), reader.u64()?.to_string()));
+            ("Approve", &["source", "delegate", "owner"])
+        }
+        5 => ("Revoke", &["source", "owner"]),
+        6 => {
+            args.push(("authorityType".to_string(), reader.u8()?.to_string()));
+            let new_authority = reader.optional_pubkey();
+            args.push((
+                "newAuthority".to_string(),
+                new_authority.unwrap_or_default(),
+            ));
+            ("SetAuthority", &["account", "authority"])
+        }
+        7 => {
+            args.push(("amount".to_string(), reader.u64()?.to_string()));
+            ("MintTo", &["mint", "account", "mintAuthority"])
+        }
+        8 => {
+            args.push(("amount".to_string(), reader.u64()?.to_string()));
+            ("Burn", &["account", "mint", "authority"])
+        }
+        9 => ("CloseAccount", &["account", "destination", "owner"]),
+        10 => ("FreezeAccount", &["account", "mint", "freezeAuthority"]),
+        11 => ("ThawAccount", &["account", "mint", "freezeAuthority"]),
+        12..=15 => {
+            args.push(("amount".to_string(), reader.u64()?.to_string()));
+            args.push(("decimals".to_string(), reader.u8()?.to_string()));
+            match tag {
+                12 => (
+                    "TransferChecked",
+                    &["source", "mint", "destination", "authority"],
+                ),
+                13 => ("ApproveChecked", &["source", "mint", "delegate", "owner"]),
+                14 => ("MintToChecked", &["mint", "account", "mintAuthority"]),
+                _ => ("BurnChecked", &["account", "mint", "authority"]),
+            }
+        }
+        16 | 18 => {
+            args.push(("owner".to_string(), reader.pubkey()?));
+            if tag == 16 {
+                ("InitializeAccount2", &["account", "mint", "rentSysvar"])
+            } else {
+                ("InitializeAccount3", &["account", "mint"])
+            }
+        }
+        17 => ("SyncNative", &["account"]),
+        _ => return None,
+    };
+
+    Some((name, accounts, args))
+}
+
+/// Map an instruction the RPC parsed (`{"type": "transfer", "info": {..}}`)
+fn from_parsed_json(program: &str, parsed: &Value) -> Option<DecodedInstruction> {
+    let kind = parsed.get("type")?.as_str()?;
+    let mut name = String::with_capacity(kind.len());
+    let mut chars = kind.chars();
+    if let Some(first) = chars.next() {
+        name.extend(first.to_uppercase());
+        name.extend(chars);
+    }
+
+    let fields = parsed
+        .get("info")
+        .and_then(|info| info.as_object())
+        .map(|info| {
+            info.iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(value) => value.clone(),
+                        // e.g. the `tokenAmount` of checked transfers
+                        Value::Object(amount) => amount
+                            .get("uiAmountString")
+                            .and_then(|amount| amount.as_str())
+                            .map_or_else(|| value.to_string(), |amount| amount.to_string()),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Some(DecodedInstruction {
+        program: program.to_string(),
+        name,
+        fields,
+    })
+}
+
+/// Little-endian reader over instruction or event data
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl Reader<'_> {
+    pub(crate) fn take(&mut self, len: usize) -> Option<&[u8]> {
+        let (head, rest) = self.0.split_at_checked(len)?;
+        self.0 = rest;
+        Some(head)
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.take(2)
+            .map(|b| u16::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn u128(&mut self) -> Option<u128> {
+        self.take(16)
+            .map(|b| u128::from_le_bytes(b.try_into().unwrap()))
+    }
+
+    pub(crate) fn pubkey(&mut self) -> Option<String> {
+        self.take(32).map(|b| bs58::encode(b).into_string())
+    }
+
+    /// SPL `COption<Pubkey>`: a `u8` flag, then the key when it's 1
+    fn optional_pubkey(&mut self) -> Option<String> {
+        match self.u8()? {
+            1 => self.pubkey(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_decode_raw_instructions() {
+        let mut transfer = 2u32.to_le_bytes().to_vec();
+        transfer.extend_from_slice(&1_000_000u64.to_le_bytes());
+        let mut transfer_checked = vec![12];
+        transfer_checked.extend_from_slice(&2_500u64.to_le_bytes());
+        transfer_checked.push(6);
+
+        let tx = json!({
+            "message": {
+                "accountKeys": ["A", "B", "M", SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID],
+                "instructions": [
+                    {"programIdIndex": 3, "accounts": [0, 1], "data": bs58::encode(&transfer).into_string()},
+                    {"programIdIndex": 4, "accounts": [0, 2, 1, 0], "data": bs58::encode(&transfer_checked).into_string()},
+                    {"programIdIndex": 2, "accounts": [], "data": ""}
+                ]
+            }
+        });
+
+        let decoded = decode_transaction_json(&tx);
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].program, "system");
+        assert_eq!(decoded[0].name, "Transfer");
+        assert_eq!(
+            decoded[0].fields,
+            vec![
+                ("source".to_string(), "A".to_string()),
+                ("destination".to_string(), "B".to_string()),
+                ("lamports".to_string(), "1000000".to_string()),
+            ]
+        );
+        assert_eq!(decoded[1].name, "TransferChecked");
+        assert_eq!(decoded[1].fields[1], ("mint".to_string(), "M".to_string()));
+        assert_eq!(
+            decoded[1].fields[5],
+            ("decimals".to_string(), "6".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_lookup_table_instructions() {
+        let accounts: Vec<String> = ["Table", "Authority", "Payer", SYSTEM_PROGRAM_ID]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        let mut create = 0u32.to_le_bytes().to_vec();
+        create.extend_from_slice(&250_000_000u64.to_le_bytes());
+        create.push(254);
+        let decoded =
+            decode_instruction(ADDRESS_LOOKUP_TABLE_PROGRAM_ID, &create, &accounts).unwrap();
+        assert_eq!(decoded.program, "address-lookup-table");
+        assert_eq!(decoded.name, "CreateLookupTable");
+        assert_eq!(
+            decoded.fields[4..],
+            [
+                ("recentSlot".to_string(), "250000000".to_string()),
+                ("bumpSeed".to_string(), "254".to_string()),
+            ]
+        );
+
+        let mut extend = 2u32.to_le_bytes().to_vec();
+        extend.extend_from_slice(&2u64.to_le_bytes());
+        extend.extend_from_slice(&[1u8; 32]);
+        extend.extend_from_slice(&[2u8; 32]);
+        let decoded =
+            decode_instruction(ADDRESS_LOOKUP_TABLE_PROGRAM_ID, &extend, &accounts).unwrap();
+        assert_eq!(decoded.name, "ExtendLookupTable");
+        assert_eq!(
+            decoded.fields[4].1,
+            format!(
+                "{},{}",
+                bs58::encode([1u8; 32]).into_string(),
+                bs58::encode([2u8; 32]).into_string()
+            )
+        );
+
+        let close = 4u32.to_le_bytes();
+        let decoded =
+            decode_instruction(ADDRESS_LOOKUP_TABLE_PROGRAM_ID, &close, &accounts[..3]).unwrap();
+        assert_eq!(decoded.name, "CloseLookupTable");
+        assert_eq!(
+            decoded.fields[2],
+            ("recipient".to_string(), "Payer".to_string())
+        );
+
+        // Truncated address list
+        assert!(
+            decode_instruction(ADDRESS_LOOKUP_TABLE_PROGRAM_ID, &extend[..40], &accounts).is_none()
+        );
+    }
+
+    #[test]
+    fn test_decode_parsed_instruction() {
+        let tx = json!({
+            "message": {
+                "accountKeys": [{"pubkey": "A"}],
+                "instructions": [{
+                    "program": "system",
+                    "programId": SYSTEM_PROGRAM_ID,
+                    "parsed": {
+                        "type": "createAccount",
+                        "info": {"source": "A", "newAccount": "B", "lamports": 2039280, "space": 165}
+                    }
+                }]
+            }
+        });
+
+        let decoded = decode_transaction_json(&tx);
+
+        assert_eq!(decoded[0].name, "CreateAccount");
+        assert!(
+            decoded[0]
+                .fields
+                .contains(&("lamports".to_string(), "2039280".to_string()))
+        );
+    }
+}
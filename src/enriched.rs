@@ -0,0 +1,258 @@
+use std::str::FromStr;
+
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{Value, json};
+
+/// Helius enhanced transactions endpoint
+pub const HELIUS_API_URL: &str = "https://api.helius.xyz/v0/transactions";
+
+/// Kind of RPC provider the parser talks to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProviderKind {
+    /// Plain Solana JSON-RPC; everything comes from log scraping
+    #[default]
+    Standard,
+    /// Helius, whose enhanced transactions API returns pre-parsed data
+    Helius,
+}
+
+impl FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "standard" | "rpc" => Ok(ProviderKind::Standard),
+            "helius" => Ok(ProviderKind::Helius),
+            _ => Err(format!("Unknown provider: {} (expected standard or helius)", s)),
+        }
+    }
+}
+
+/// A top-level instruction as decoded by the provider
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichedInstruction {
+    pub program_id: String,
+    pub inner_instructions: usize,
+}
+
+/// A SOL or token transfer. `mint` is `None` for native SOL, whose amount is
+/// in lamports; token amounts are UI amounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichedTransfer {
+    pub from: String,
+    pub to: String,
+    pub amount: String,
+    pub mint: Option<String>,
+}
+
+/// A provider-decoded event (swap, NFT sale, ...) kept as JSON
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichedEvent {
+    pub kind: String,
+    pub json: String,
+}
+
+/// Pre-parsed transaction data from an enhanced-API provider
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnrichedTransaction {
+    /// Provider classification, e.g. `SWAP` or `TRANSFER`
+    pub transaction_type: String,
+    /// Protocol the transaction went through, e.g. `JUPITER`
+    pub source: String,
+    /// Human-readable summary
+    pub description: String,
+    pub instructions: Vec<EnrichedInstruction>,
+    pub transfers: Vec<EnrichedTransfer>,
+    pub events: Vec<EnrichedEvent>,
+}
+
+impl EnrichedTransaction {
+    /// Map one entry of a Helius `/v0/transactions` response
+    pub fn from_helius_json(value: &Value) -> Self {
+        let text = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let array = |key: &str| {
+            value
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|v| v.as_slice())
+                .unwrap_or_default()
+        };
+        let field = |entry: &Value, key: &str| {
+            entry
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        let instructions = array("instructions")
+            .iter()
+            .map(|instruction| EnrichedInstruction {
+                program_id: field(instruction, "programId"),
+                inner_instructions: instruction
+                    .get("innerInstructions")
+                    .and_then(|inner| inner.as_array())
+                    .map_or(0, |inner| inner.len()),
+            })
+            .collect();
+
+        let native_transfers = array("nativeTransfers").iter().map(|transfer| EnrichedTransfer {
+            from: field(transfer, "fromUserAccount"),
+            to: field(transfer, "toUserAccount"),
+            amount: transfer.get("amount").map(|a| a.to_string()).unwrap_or_default(),
+            mint: None,
+        });
+        let token_transfers = array("tokenTransfers").iter().map(|transfer| EnrichedTransfer {
+            from: field(transfer, "fromUserAccount"),
+            to: field(transfer, "toUserAccount"),
+            amount: transfer.get("tokenAmount").map(|a| a.to_string()).unwrap_or_default(),
+            mint: Some(field(transfer, "mint")),
+        });
+
+        let events = value
+            .get("events")
+            .and_then(|events| events.as_object())
+            .map(|events| {
+                events
+                    .iter()
+                    .filter(|(_, event)| !event.is_null())
+                    .map(|(kind, event)| EnrichedEvent {
+                        kind: kind.clone(),
+                        json: event.to_string(),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        EnrichedTransaction {
+            transaction_type: text("type"),
+            source: text("source"),
+            description: text("description"),
+            instructions,
+            transfers: native_transfers.chain(token_transfers).collect(),
+            events,
+        }
+    }
+}
+
+/// An enhanced-API provider to query alongside the standard RPC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnrichedProvider {
+    pub kind: ProviderKind,
+    pub api_url: String,
+    pub api_key: String,
+}
+
+impl EnrichedProvider {
+    pub fn helius(api_key: String) -> Self {
+        EnrichedProvider {
+            kind: ProviderKind::Helius,
+            api_url: HELIUS_API_URL.to_string(),
+            api_key,
+        }
+    }
+
+    /// Fetch the provider's parsed view of a transaction. Returns `None` for
+    /// standard providers, which have no enhanced API.
+    pub async fn fetch(
+        &self,
+        client: &reqwest::Client,
+        signature: &str,
+    ) -> Result<Option<EnrichedTransaction>, String> {
+        match self.kind {
+            ProviderKind::Standard => Ok(None),
+            ProviderKind::Helius => {
+                // The key travels in the query string, so errors are
+                // reported without their URL to keep it out of logs
+                let body = json!({ "transactions": [signature] });
+                let response = client
+                    .post(format!("{}?api-key={}", self.api_url, self.api_key))
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(body.to_string())
+                    .send()
+                    .await
+                    .map_err(|e| format!("Enhanced API request failed: {}", e.without_url()))?;
+
+                let status = response.status();
+                let text = response.text().await.map_err(|e| {
+                    format!("Failed to read enhanced API response: {}", e.without_url())
+                })?;
+                if !status.is_success() {
+                    return Err(format!("Enhanced API returned {}: {}", status, text));
+                }
+
+                let value: Value = serde_json::from_str(&text)
+                    .map_err(|e| format!("Invalid enhanced API response: {}", e))?;
+
+                Ok(value
+                    .as_array()
+                    .and_then(|transactions| transactions.first())
+                    .map(EnrichedTransaction::from_helius_json))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_helius_json() {
+        let value = json!({
+            "description": "A transferred 0.5 SOL to B.",
+            "type": "TRANSFER",
+            "source": "SYSTEM_PROGRAM",
+            "signature": "5h6xBEauJ3PK6SWC",
+            "nativeTransfers": [
+                {"fromUserAccount": "A", "toUserAccount": "B", "amount": 500000000}
+            ],
+            "tokenTransfers": [
+                {"fromUserAccount": "A", "toUserAccount": "C", "tokenAmount": 1.5, "mint": "M"}
+            ],
+            "instructions": [
+                {"programId": "11111111111111111111111111111111", "innerInstructions": []},
+                {"programId": "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", "innerInstructions": [{}, {}]}
+            ],
+            "events": {"swap": {"nativeInput": null}, "nft": null}
+        });
+
+        let enriched = EnrichedTransaction::from_helius_json(&value);
+
+        assert_eq!(enriched.transaction_type, "TRANSFER");
+        assert_eq!(enriched.instructions[1].inner_instructions, 2);
+        assert_eq!(enriched.transfers.len(), 2);
+        assert_eq!(enriched.transfers[0].amount, "500000000");
+        assert_eq!(enriched.transfers[1].mint.as_deref(), Some("M"));
+        assert_eq!(enriched.events.len(), 1);
+        assert_eq!(enriched.events[0].kind, "swap");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_error_hides_api_key() {
+        // Nothing listens on a port whose listener was just dropped
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let provider = EnrichedProvider {
+            kind: ProviderKind::Helius,
+            api_url: format!("http://{}/v0/transactions", addr),
+            api_key: "secret-key".to_string(),
+        };
+
+        let error = provider
+            .fetch(&reqwest::Client::new(), "sig")
+            .await
+            .unwrap_err();
+
+        assert!(error.starts_with("Enhanced API request failed"));
+        assert!(!error.contains("secret-key"));
+    }
+}
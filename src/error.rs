@@ -0,0 +1,56 @@
+use std::fmt;
+
+/// Errors returned by `TxLogParser`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The caller's input was malformed: a bad signature, account, filter or
+    /// file
+    InvalidInput(String),
+    /// The parser's own setup is broken: a bad configured header or user
+    /// agent, or an HTTP client that couldn't be built
+    Config(String),
+    /// The RPC answered but the transaction or signature doesn't exist
+    NotFound(String),
+    /// The RPC request itself failed or timed out
+    Rpc(String),
+    /// The RPC rejected the request with HTTP 429 Too Many Requests
+    RateLimited(String),
+    /// The transaction data couldn't be decoded
+    Decode(String),
+    /// The transaction's version is newer than the parser accepts
+    UnsupportedVersion(String),
+    /// The transaction landed outside the slot range the caller asked for,
+    /// or further back in an account's history than a lookup walks
+    OutOfRange(String),
+    /// The RPC node is lagging or can't serve the requested commitment yet;
+    /// the transaction may well exist, so retry later
+    NodeBehind(String),
+}
+
+impl ParseError {
+    /// Whether the error points at the upstream RPC rather than the request
+    pub fn is_upstream(&self) -> bool {
+        matches!(
+            self,
+            ParseError::Rpc(_) | ParseError::RateLimited(_) | ParseError::NodeBehind(_)
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidInput(msg)
+            | ParseError::Config(msg)
+            | ParseError::NotFound(msg)
+            | ParseError::Rpc(msg)
+            | ParseError::RateLimited(msg)
+            | ParseError::Decode(msg)
+            | ParseError::UnsupportedVersion(msg)
+            | ParseError::OutOfRange(msg)
+            | ParseError::NodeBehind(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
@@ -0,0 +1,153 @@
+use crate::output::ParsedTransaction;
+
+const INSTRUCTION_LOG_PREFIX: &str = "Program log: Instruction: ";
+
+/// Assertions checked against a parsed transaction, for CI pipelines
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Expectations {
+    /// Substrings that must each appear in some raw log line (case-insensitive)
+    pub logs: Vec<String>,
+    /// The transaction must have succeeded
+    pub success: bool,
+    /// Total compute units consumed must be below this
+    pub cu_under: Option<u64>,
+    /// Instruction names (`Program log: Instruction: <name>`) that must have run
+    pub instructions: Vec<String>,
+}
+
+impl Expectations {
+    pub fn is_empty(&self) -> bool {
+        self.count() == 0
+    }
+
+    /// Number of individual expectations
+    pub fn count(&self) -> usize {
+        self.logs.len()
+            + usize::from(self.success)
+            + usize::from(self.cu_under.is_some())
+            + self.instructions.len()
+    }
+
+    /// Check every expectation, returning one message per failure with the
+    /// expected and actual values
+    pub fn check(&self, parsed: &ParsedTransaction) -> Vec<String> {
+        let mut failures = Vec::new();
+
+        if self.success
+            && let Some(ref err) = parsed.error
+        {
+            let location = match (err.instruction_index, &err.program_id) {
+                (Some(idx), Some(program_id)) => format!(" at instruction #{} ({})", idx, program_id),
+                (Some(idx), None) => format!(" at instruction #{}", idx),
+                _ => String::new(),
+            };
+            failures.push(format!(
+                "expected success\n    expected: success\n    actual:   failed with {}{}",
+                err.error, location
+            ));
+        }
+
+        for expected in self.logs.iter() {
+            let needle = expected.to_lowercase();
+            if !parsed.raw_logs.iter().any(|log| log.to_lowercase().contains(&needle)) {
+                failures.push(format!(
+                    "expected log\n    expected: a log containing {:?}\n    actual:   no match in {} raw logs",
+                    expected,
+                    parsed.raw_logs.len()
+                ));
+            }
+        }
+
+        if let Some(limit) = self.cu_under {
+            match parsed.compute_units_consumed {
+                Some(consumed) if consumed < limit => {}
+                Some(consumed) => failures.push(format!(
+                    "expected compute units under {}\n    expected: < {}\n    actual:   {}",
+                    limit, limit, consumed
+                )),
+                None => failures.push(format!(
+                    "expected compute units under {}\n    expected: < {}\n    actual:   not reported",
+                    limit, limit
+                )),
+            }
+        }
+
+        let executed = instruction_names(&parsed.raw_logs);
+        for expected in self.instructions.iter() {
+            if !executed.iter().any(|name| name.eq_ignore_ascii_case(expected)) {
+                failures.push(format!(
+                    "expected instruction\n    expected: {}\n    actual:   [{}]",
+                    expected,
+                    executed.join(", ")
+                ));
+            }
+        }
+
+        failures
+    }
+}
+
+/// Names from `Program log: Instruction: <name>` lines, in execution order
+pub fn instruction_names(raw_logs: &[String]) -> Vec<String> {
+    raw_logs
+        .iter()
+        .filter_map(|log| log.strip_prefix(INSTRUCTION_LOG_PREFIX))
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::ParsedError;
+
+    fn parsed() -> ParsedTransaction {
+        ParsedTransaction {
+            raw_logs: [
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+                "Program log: Instruction: Transfer",
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            compute_units_consumed: Some(4645),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_expectations_pass() {
+        let expectations = Expectations {
+            logs: vec!["instruction: transfer".to_string()],
+            success: true,
+            cu_under: Some(5000),
+            instructions: vec!["Transfer".to_string()],
+        };
+
+        assert_eq!(expectations.count(), 4);
+        assert!(expectations.check(&parsed()).is_empty());
+    }
+
+    #[test]
+    fn test_expectations_report_each_failure() {
+        let mut parsed = parsed();
+        parsed.error = Some(ParsedError {
+            error: "Custom(1)".to_string(),
+            instruction_index: Some(0),
+            program_id: None,
+        });
+
+        let expectations = Expectations {
+            logs: vec!["MintTo".to_string()],
+            success: true,
+            cu_under: Some(1000),
+            instructions: vec!["Burn".to_string()],
+        };
+
+        let failures = expectations.check(&parsed);
+        assert_eq!(failures.len(), 4);
+        assert!(failures[0].contains("failed with Custom(1) at instruction #0"));
+        assert!(failures[3].contains("actual:   [Transfer]"));
+    }
+}
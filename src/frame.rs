@@ -0,0 +1,165 @@
+//! Compact length-prefixed binary frames for low-latency stream consumers.
+//!
+//! All integers are little-endian. Each frame is:
+//!
+//! | Offset | Size   | Field                                             |
+//! | ------ | ------ | ------------------------------------------------- |
+//! | 0      | 4      | `u32` length of the rest of the frame, in bytes   |
+//! | 4      | 1      | `u8` version, currently `1`                       |
+//! | 5      | 64     | transaction signature                             |
+//! | 69     | 8      | `u64` slot                                        |
+//! | 77     | 1      | `u8` flags: bit 0 = failed, bit 1 = truncated     |
+//! | 78     | 8      | `u64` total compute units consumed, 0 if unknown  |
+//! | 86     | 2      | `u16` number of compute unit entries `N`          |
+//! | 88     | 44 * N | entries: 32-byte program id, `u64` consumed, `u32` invocations |
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+pub const FRAME_VERSION: u8 = 1;
+
+pub const FLAG_FAILED: u8 = 0b01;
+pub const FLAG_TRUNCATED: u8 = 0b10;
+
+const HEADER_LEN: usize = 1 + 64 + 8 + 1 + 8 + 2;
+const ENTRY_LEN: usize = 32 + 8 + 4;
+
+/// Compute unit usage of one program within a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameComputeUnits {
+    pub program_id: Pubkey,
+    pub consumed: u64,
+    pub invocations: u32,
+}
+
+/// One streamed transaction, reduced to what fits in a binary frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub signature: Signature,
+    pub slot: u64,
+    pub failed: bool,
+    pub truncated: bool,
+    pub compute_units_consumed: u64,
+    pub compute_units: Vec<FrameComputeUnits>,
+}
+
+impl Frame {
+    /// Encode including the 4-byte length prefix. Entries past `u16::MAX`
+    /// are dropped.
+    pub fn encode(&self) -> Vec<u8> {
+        let entries = &self.compute_units[..self.compute_units.len().min(u16::MAX as usize)];
+        let body_len = HEADER_LEN + ENTRY_LEN * entries.len();
+
+        let mut flags = 0;
+        if self.failed {
+            flags |= FLAG_FAILED;
+        }
+        if self.truncated {
+            flags |= FLAG_TRUNCATED;
+        }
+
+        let mut buf = Vec::with_capacity(4 + body_len);
+        buf.extend_from_slice(&(body_len as u32).to_le_bytes());
+        buf.push(FRAME_VERSION);
+        buf.extend_from_slice(self.signature.as_ref());
+        buf.extend_from_slice(&self.slot.to_le_bytes());
+        buf.push(flags);
+        buf.extend_from_slice(&self.compute_units_consumed.to_le_bytes());
+        buf.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+
+        for entry in entries {
+            buf.extend_from_slice(entry.program_id.as_ref());
+            buf.extend_from_slice(&entry.consumed.to_le_bytes());
+            buf.extend_from_slice(&entry.invocations.to_le_bytes());
+        }
+
+        buf
+    }
+
+    /// Decode a frame body, i.e. the bytes after the length prefix
+    pub fn decode(body: &[u8]) -> Result<Self, String> {
+        if body.len() < HEADER_LEN {
+            return Err(format!("Frame too short: {} bytes", body.len()));
+        }
+        if body[0] != FRAME_VERSION {
+            return Err(format!("Unsupported frame version: {}", body[0]));
+        }
+
+        let signature = Signature::try_from(&body[1..65])
+            .map_err(|_| "Invalid signature in frame".to_string())?;
+        let slot = u64::from_le_bytes(body[65..73].try_into().unwrap());
+        let flags = body[73];
+        let compute_units_consumed = u64::from_le_bytes(body[74..82].try_into().unwrap());
+        let count = u16::from_le_bytes(body[82..84].try_into().unwrap()) as usize;
+
+        let expected = HEADER_LEN + ENTRY_LEN * count;
+        if body.len() != expected {
+            return Err(format!(
+                "Frame length mismatch: expected {} bytes, got {}",
+                expected,
+                body.len()
+            ));
+        }
+
+        let compute_units = body[HEADER_LEN..]
+            .chunks_exact(ENTRY_LEN)
+            .map(|entry| FrameComputeUnits {
+                program_id: Pubkey::new_from_array(entry[..32].try_into().unwrap()),
+                consumed: u64::from_le_bytes(entry[32..40].try_into().unwrap()),
+                invocations: u32::from_le_bytes(entry[40..44].try_into().unwrap()),
+            })
+            .collect();
+
+        Ok(Frame {
+            signature,
+            slot,
+            failed: flags & FLAG_FAILED != 0,
+            truncated: flags & FLAG_TRUNCATED != 0,
+            compute_units_consumed,
+            compute_units,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let frame = Frame {
+            signature: Signature::from([3; 64]),
+            slot: 312_000_123,
+            failed: true,
+            truncated: false,
+            compute_units_consumed: 29933,
+            compute_units: vec![FrameComputeUnits {
+                program_id: Pubkey::new_from_array([9; 32]),
+                consumed: 4645,
+                invocations: 2,
+            }],
+        };
+
+        let encoded = frame.encode();
+        assert_eq!(encoded.len(), 4 + HEADER_LEN + ENTRY_LEN);
+        assert_eq!(
+            u32::from_le_bytes(encoded[..4].try_into().unwrap()) as usize,
+            encoded.len() - 4
+        );
+        assert_eq!(Frame::decode(&encoded[4..]).unwrap(), frame);
+    }
+
+    #[test]
+    fn test_decode_rejects_bad_length() {
+        let frame = Frame {
+            signature: Signature::from([1; 64]),
+            slot: 1,
+            failed: false,
+            truncated: false,
+            compute_units_consumed: 0,
+            compute_units: Vec::new(),
+        };
+
+        let encoded = frame.encode();
+        assert!(Frame::decode(&encoded[4..encoded.len() - 1]).is_err());
+    }
+}
@@ -0,0 +1,382 @@
+use base64::{Engine, prelude::BASE64_STANDARD};
+use futures_util::{Stream, StreamExt};
+use tonic::{Code, Status, transport::Channel};
+
+use crate::anchor::AnchorEvent;
+use crate::error::ParseError;
+use crate::output::{ParsedComputeUnits, ParsedError, ParsedTransaction};
+use crate::parser::TxLogParser;
+use crate::proto::{
+    self,
+    ContainsLogRequest, GetTxBatchRequest, GetTxRequest, GetTxResponse, StreamProgramRequest,
+    StreamTransactionResponse, solana_tx_log_client::SolanaTxLogClient,
+};
+use crate::status::status_error;
+
+/// Client for an Odin server, returning the same `ParsedTransaction` the
+/// library builds locally. Options set with the `with_*` methods apply to
+/// every request.
+#[derive(Debug, Clone)]
+pub struct OdinClient {
+    client: SolanaTxLogClient<Channel>,
+    rpc_url: String,
+    filter: String,
+    include_cu_logs: bool,
+}
+
+impl OdinClient {
+    /// Connect to a server, e.g. `http://[::1]:50051`
+    pub async fn connect(server: &str) -> Result<Self, ParseError> {
+        let client = SolanaTxLogClient::connect(server.to_string())
+            .await
+            .map_err(|e| ParseError::Rpc(format!("Failed to connect to {}: {}", server, e)))?;
+
+        Ok(OdinClient {
+            client,
+            rpc_url: String::new(),
+            filter: String::new(),
+            include_cu_logs: false,
+        })
+    }
+
+    /// RPC the server fetches from; the server's default when unset
+    pub fn with_rpc_url(mut self, rpc_url: &str) -> Self {
+        self.rpc_url = rpc_url.to_string();
+        self
+    }
+
+    /// Keep only program logs containing `filter` (case-insensitive)
+    pub fn with_filter(mut self, filter: &str) -> Self {
+        self.filter = filter.to_string();
+        self
+    }
+
+    /// Include per-program compute unit usage
+    pub fn with_cu_logs(mut self, include_cu_logs: bool) -> Self {
+        self.include_cu_logs = include_cu_logs;
+        self
+    }
+
+    /// Fetch and parse one transaction
+    pub async fn get_tx(&mut self, signature: &str) -> Result<ParsedTransaction, ParseError> {
+        let response = self
+            .client
+            .get_tx_logs(GetTxRequest {
+                rpc_url: self.rpc_url.clone(),
+                tx_sig: signature.to_string(),
+                include_cu_logs: self.include_cu_logs,
+                filter: self.filter.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(parsed_from_response(signature, &response))
+    }
+
+    /// Fetch and parse up to 100 transactions in one request; the server
+    /// fetches them concurrently. Results are in the order of `signatures`,
+    /// and a failed signature doesn't fail the others: its error maps back
+    /// from the status the server reported for it, as for `get_tx`.
+    pub async fn get_tx_batch(
+        &mut self,
+        signatures: &[String],
+    ) -> Result<Vec<Result<ParsedTransaction, ParseError>>, ParseError> {
+        let response = self
+            .client
+            .get_tx_logs_batch(GetTxBatchRequest {
+                rpc_url: self.rpc_url.clone(),
+                tx_sigs: signatures.to_vec(),
+                include_cu_logs: self.include_cu_logs,
+                filter: self.filter.clone(),
+                include_labels: false,
+            })
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|result| match result.response {
+                Some(ref tx_response) if result.error.is_empty() => {
+                    Ok(parsed_from_response(&result.tx_sig, tx_response))
+                }
+                _ => Err(status_error(Status::new(Code::from(result.code), result.error))),
+            })
+            .collect())
+    }
+
+    /// First raw log line of the transaction containing `needle`
+    /// (case-insensitive), with its index
+    pub async fn contains_log(
+        &mut self,
+        signature: &str,
+        needle: &str,
+    ) -> Result<Option<(usize, String)>, ParseError> {
+        let response = self
+            .client
+            .contains_log(ContainsLogRequest {
+                rpc_url: self.rpc_url.clone(),
+                tx_sig: signature.to_string(),
+                needle: needle.to_string(),
+            })
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(response
+            .found
+            .then(|| (response.index.unwrap_or_default() as usize, response.line)))
+    }
+
+    /// Stream every transaction mentioning `program`, parsed. Follow-up
+    /// status messages are skipped; the stream ends with an error when the
+    /// server gives up on the subscription.
+    pub async fn stream_program(
+        &mut self,
+        program: &str,
+    ) -> Result<impl Stream<Item = Result<ParsedTransaction, ParseError>> + use<>, ParseError> {
+        let stream = self
+            .client
+            .stream_program_logs(StreamProgramRequest {
+                rpc_url: self.rpc_url.clone(),
+                program_address: program.to_string(),
+                include_cu_logs: self.include_cu_logs,
+                filter: self.filter.clone(),
+                ..Default::default()
+            })
+            .await
+            .map_err(status_error)?
+            .into_inner();
+
+        Ok(stream.filter_map(|message| async move {
+            match message {
+                Ok(message) if message.status_update.is_some() => None,
+                Ok(message) => Some(Ok(parsed_from_stream(message))),
+                Err(status) => Some(Err(status_error(status))),
+            }
+        }))
+    }
+}
+
+/// Convert a server response to the library's output shape
+pub fn parsed_from_response(signature: &str, tx_response: &GetTxResponse) -> ParsedTransaction {
+    ParsedTransaction {
+        signature: signature.to_string(),
+        version: (!tx_response.version.is_empty()).then(|| tx_response.version.clone()),
+        logs: tx_response.logs.clone(),
+        raw_logs: tx_response.raw_logs.clone(),
+        programs_invoked: tx_response.programs.clone(),
+        compute_units: tx_response
+            .compute_units
+            .iter()
+            .map(|cu_log| ParsedComputeUnits {
+                program_id: cu_log.program_id.clone(),
+                consumed: cu_log.consumed,
+                budget: cu_log.budget,
+                invocations: cu_log.invocations,
+            })
+            .collect(),
+        compute_units_consumed: tx_response.compute_units_consumed,
+        compute_budget_explicit: tx_response.compute_budget_explicit,
+        compute_unit_limit: tx_response.compute_unit_limit,
+        compute_unit_price: tx_response.compute_unit_price,
+        top_level_instructions: tx_response.top_level_instructions as usize,
+        inner_instructions: tx_response.inner_instructions as usize,
+        error: tx_response.error.as_ref().map(|err| ParsedError {
+            error: err.error.clone(),
+            instruction_index: err.instruction_index.and_then(|idx| u8::try_from(idx).ok()),
+            program_id: (!err.program_id.is_empty()).then(|| err.program_id.clone()),
+        }),
+        truncated: tx_response.truncated,
+    }
+}
+
+/// An Anchor event as sent in responses. Hex payloads are sent as is rather
+/// than as a quoted JSON string.
+pub fn anchor_event_to_proto(event: AnchorEvent) -> proto::AnchorEvent {
+    let data = match event.data {
+        serde_json::Value::String(hex) => hex,
+        data => data.to_string(),
+    };
+    proto::AnchorEvent {
+        name: event.name,
+        data,
+    }
+}
+
+/// The transaction's return data as sent in responses: base64 payloads,
+/// sorted by program id
+pub fn return_data_to_proto(parser: &TxLogParser) -> Vec<proto::ReturnData> {
+    let mut return_data: Vec<proto::ReturnData> = parser
+        .get_return_data()
+        .into_iter()
+        .map(|(program_id, data)| proto::ReturnData {
+            program_id: program_id.to_string(),
+            data: BASE64_STANDARD.encode(data),
+        })
+        .collect();
+    return_data.sort_by(|a, b| a.program_id.cmp(&b.program_id));
+    return_data
+}
+
+fn parsed_from_stream(message: StreamTransactionResponse) -> ParsedTransaction {
+    let tx_response = GetTxResponse {
+        logs: message.logs,
+        raw_logs: message.raw_logs,
+        programs: message.programs,
+        compute_units: message.compute_units,
+        compute_units_consumed: message.compute_units_consumed,
+        compute_budget_explicit: message.compute_budget_explicit,
+        compute_unit_limit: message.compute_unit_limit,
+        compute_unit_price: message.compute_unit_price,
+        top_level_instructions: message.top_level_instructions,
+        inner_instructions: message.inner_instructions,
+        error: message.error,
+        truncated: message.truncated,
+        version: message.version,
+        ..Default::default()
+    };
+    parsed_from_response(&message.signature, &tx_response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::proto::solana_tx_log_server::{SolanaTxLog, SolanaTxLogServer};
+    use crate::proto::{
+        BatchStreamMessage, BatchTxResult, ContainsLogResponse, GetRelatedRequest,
+        GetRelatedResponse, GetTxBatchResponse, SimulateRequest,
+    };
+    use serde_json::json;
+    use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+    use tonic::{Request, Response};
+
+    /// Answers GetTxLogsBatch with logs for every signature but `bad`, an
+    /// invalid argument, and `behind`, a lagging node
+    struct MockServer;
+
+    #[tonic::async_trait]
+    impl SolanaTxLog for MockServer {
+        type StreamProgramLogsStream = ReceiverStream<Result<StreamTransactionResponse, Status>>;
+        type StreamTxLogsBatchStream = ReceiverStream<Result<BatchStreamMessage, Status>>;
+
+        async fn get_tx_logs_batch(
+            &self,
+            request: Request<GetTxBatchRequest>,
+        ) -> Result<Response<GetTxBatchResponse>, Status> {
+            let results = request
+                .into_inner()
+                .tx_sigs
+                .into_iter()
+                .map(|tx_sig| {
+                    let status = match tx_sig.as_str() {
+                        "bad" => Status::invalid_argument("Invalid transaction signature: bad"),
+                        "behind" => Status::unavailable("Node is behind by 42 slots"),
+                        _ => {
+                            return BatchTxResult {
+                                tx_sig,
+                                response: Some(GetTxResponse {
+                                    logs: vec!["Instruction: Transfer".to_string()],
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            };
+                        }
+                    };
+                    BatchTxResult {
+                        tx_sig,
+                        error: status.message().to_string(),
+                        code: status.code() as i32,
+                        ..Default::default()
+                    }
+                })
+                .collect();
+            Ok(Response::new(GetTxBatchResponse { results }))
+        }
+
+        async fn get_tx_logs(
+            &self,
+            _: Request<GetTxRequest>,
+        ) -> Result<Response<GetTxResponse>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+
+        async fn stream_tx_logs_batch(
+            &self,
+            _: Request<GetTxBatchRequest>,
+        ) -> Result<Response<Self::StreamTxLogsBatchStream>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+
+        async fn get_related_transactions(
+            &self,
+            _: Request<GetRelatedRequest>,
+        ) -> Result<Response<GetRelatedResponse>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+
+        async fn stream_program_logs(
+            &self,
+            _: Request<StreamProgramRequest>,
+        ) -> Result<Response<Self::StreamProgramLogsStream>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+
+        async fn simulate_transaction(
+            &self,
+            _: Request<SimulateRequest>,
+        ) -> Result<Response<GetTxResponse>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+
+        async fn contains_log(
+            &self,
+            _: Request<ContainsLogRequest>,
+        ) -> Result<Response<ContainsLogResponse>, Status> {
+            Err(Status::unimplemented("mock"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_tx_batch() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(SolanaTxLogServer::new(MockServer))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let mut client = OdinClient::connect(&format!("http://{}", addr)).await.unwrap();
+        let signatures = ["ok", "bad", "behind"].map(String::from);
+        let results = client.get_tx_batch(&signatures).await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        let parsed = results[0].as_ref().unwrap();
+        assert_eq!(parsed.signature, "ok");
+        assert_eq!(parsed.logs, vec!["Instruction: Transfer".to_string()]);
+        // A bad signature stays the caller's fault and a lagging node stays
+        // retryable, rather than both reading as an outage
+        assert!(matches!(results[1], Err(ParseError::InvalidInput(_))));
+        assert!(matches!(results[2], Err(ParseError::NodeBehind(_))));
+    }
+
+    #[test]
+    fn test_anchor_event_to_proto() {
+        let raw = anchor_event_to_proto(AnchorEvent {
+            name: "e445a52e51cb9a1d".to_string(),
+            data: json!("0102ab"),
+        });
+        assert_eq!(raw.data, "0102ab");
+
+        let decoded = anchor_event_to_proto(AnchorEvent {
+            name: "SwapEvent".to_string(),
+            data: json!({"amount": 5}),
+        });
+        assert_eq!(decoded.name, "SwapEvent");
+        assert_eq!(decoded.data, r#"{"amount":5}"#);
+    }
+}
@@ -0,0 +1,70 @@
+use solana_transaction_status_client_types::{
+    UiInnerInstructions, UiInstruction, UiParsedInstruction,
+};
+
+/// An instruction a program invoked (CPI) while the transaction ran, from
+/// the meta's `innerInstructions`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InnerInstruction {
+    /// Index of the top-level instruction the call happened under
+    pub instruction_index: u8,
+    /// Program called, `None` when its index is outside the account keys
+    pub program_id: Option<String>,
+    /// Accounts passed to the call; empty for instructions the RPC returned
+    /// fully parsed
+    pub accounts: Vec<String>,
+    /// Invoke depth, 2 for a call made by a top-level instruction, when the
+    /// RPC reports it
+    pub stack_height: Option<u32>,
+}
+
+/// Resolve the inner instructions of a transaction. `account_keys` must be
+/// the full key space compiled indices refer to: the static keys, then the
+/// writable and the readonly addresses loaded from lookup tables, so that a
+/// v0 transaction can call programs only its lookup tables list.
+pub fn inner_instructions(
+    inner: &[UiInnerInstructions],
+    account_keys: &[String],
+) -> Vec<InnerInstruction> {
+    let key = |idx: u8| account_keys.get(idx as usize).cloned();
+
+    inner
+        .iter()
+        .flat_map(|inner| {
+            inner
+                .instructions
+                .iter()
+                .map(move |instruction| (inner.index, instruction))
+        })
+        .map(|(instruction_index, instruction)| {
+            let (program_id, accounts, stack_height) = match instruction {
+                UiInstruction::Compiled(compiled) => (
+                    key(compiled.program_id_index),
+                    compiled
+                        .accounts
+                        .iter()
+                        .map(|&idx| key(idx).unwrap_or_else(|| format!("#{}", idx)))
+                        .collect(),
+                    compiled.stack_height,
+                ),
+                UiInstruction::Parsed(UiParsedInstruction::Parsed(parsed)) => (
+                    Some(parsed.program_id.clone()),
+                    Vec::new(),
+                    parsed.stack_height,
+                ),
+                UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(decoded)) => (
+                    Some(decoded.program_id.clone()),
+                    decoded.accounts.clone(),
+                    decoded.stack_height,
+                ),
+            };
+
+            InnerInstruction {
+                instruction_index,
+                program_id,
+                accounts,
+                stack_height,
+            }
+        })
+        .collect()
+}
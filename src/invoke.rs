@@ -0,0 +1,414 @@
+use std::str::FromStr;
+
+use solana_sdk::pubkey::Pubkey;
+
+/// A raw transaction log line classified by kind
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LogEvent {
+    /// `Program <id> invoke [<depth>]`
+    Invoke { program_id: Pubkey, depth: usize },
+    /// `Program <id> consumed <consumed> of <budget> compute units`
+    Consumed {
+        program_id: Pubkey,
+        consumed: u64,
+        budget: u64,
+    },
+    /// `Program <id> success`
+    Success { program_id: Pubkey },
+    /// `Program <id> failed: <reason>`
+    Failed { program_id: Pubkey, reason: String },
+    /// `Program log: <message>`
+    Log(String),
+    /// `Program data: <base64>`
+    Data(String),
+    /// `Program return: <id> <base64>`
+    Return { program_id: Pubkey, data: String },
+    /// Anything else, e.g. truncation notices or runtime messages
+    Other(String),
+}
+
+impl LogEvent {
+    pub fn parse(line: &str) -> Self {
+        if let Some(message) = line.strip_prefix("Program log:") {
+            return LogEvent::Log(message.trim().to_string());
+        }
+        if let Some(data) = line.strip_prefix("Program data:") {
+            return LogEvent::Data(data.trim().to_string());
+        }
+        if let Some(rest) = line.strip_prefix("Program return: ") {
+            if let Some((program_id, data)) = rest.split_once(' ')
+                && let Ok(program_id) = Pubkey::from_str(program_id)
+            {
+                return LogEvent::Return {
+                    program_id,
+                    data: data.trim().to_string(),
+                };
+            }
+            return LogEvent::Other(line.to_string());
+        }
+
+        let Some((program_id, tail)) = line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+        else {
+            return LogEvent::Other(line.to_string());
+        };
+        let Ok(program_id) = Pubkey::from_str(program_id) else {
+            return LogEvent::Other(line.to_string());
+        };
+
+        if let Some(depth) = tail
+            .strip_prefix("invoke [")
+            .and_then(|depth| depth.strip_suffix(']'))
+            .and_then(|depth| depth.parse().ok())
+        {
+            return LogEvent::Invoke { program_id, depth };
+        }
+        if tail == "success" {
+            return LogEvent::Success { program_id };
+        }
+        if let Some(reason) = tail.strip_prefix("failed:") {
+            return LogEvent::Failed {
+                program_id,
+                reason: reason.trim().to_string(),
+            };
+        }
+        if let Some((consumed, budget)) = tail
+            .strip_prefix("consumed ")
+            .and_then(|rest| rest.strip_suffix(" compute units"))
+            .and_then(|rest| rest.split_once(" of "))
+            && let (Ok(consumed), Ok(budget)) = (consumed.parse(), budget.parse())
+        {
+            return LogEvent::Consumed {
+                program_id,
+                consumed,
+                budget,
+            };
+        }
+
+        LogEvent::Other(line.to_string())
+    }
+}
+
+/// One program invocation in the call graph. `caller` is `None` for
+/// top-level instructions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallEdge {
+    pub caller: Option<Pubkey>,
+    pub callee: Pubkey,
+    pub consumed: Option<u64>,
+}
+
+/// Reconstruct the program call graph from raw logs, one edge per
+/// invocation in on-chain order
+pub fn call_graph(logs: &[String]) -> Vec<CallEdge> {
+    let mut edges: Vec<CallEdge> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+
+    for log in logs {
+        match LogEvent::parse(log) {
+            LogEvent::Invoke { program_id, .. } => {
+                let caller = stack.last().map(|&idx| edges[idx].callee);
+                edges.push(CallEdge {
+                    caller,
+                    callee: program_id,
+                    consumed: None,
+                });
+                stack.push(edges.len() - 1);
+            }
+            LogEvent::Consumed {
+                program_id,
+                consumed,
+                ..
+            } => {
+                if let Some(&idx) = stack.last()
+                    && edges[idx].callee == program_id
+                {
+                    edges[idx].consumed = Some(consumed);
+                }
+            }
+            LogEvent::Success { .. } | LogEvent::Failed { .. } => {
+                stack.pop();
+            }
+            _ => {}
+        }
+    }
+
+    edges
+}
+
+/// A single program invocation with the compute units reported for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invocation {
+    pub program: Pubkey,
+    /// 1 for top-level instructions, 2 for their CPIs, and so on
+    pub depth: usize,
+    /// Units consumed by this invocation, its own CPIs included; `None` when
+    /// no `consumed` line was logged for it
+    pub consumed: Option<u64>,
+    /// `None` when the logs end before the invocation does, e.g. when they
+    /// were truncated
+    pub success: Option<bool>,
+}
+
+/// Every invocation in on-chain order, each with the compute units and
+/// outcome from the lines closing it
+pub fn invocations(logs: &[String]) -> Vec<Invocation> {
+    let mut invocations: Vec<Invocation> = Vec::new();
+    let mut stack: Vec<usize> = Vec::new();
+
+    for log in logs {
+        match LogEvent::parse(log) {
+            LogEvent::Invoke { program_id, depth } => {
+                invocations.push(Invocation {
+                    program: program_id,
+                    depth,
+                    consumed: None,
+                    success: None,
+                });
+                stack.push(invocations.len() - 1);
+            }
+            LogEvent::Consumed {
+                program_id,
+                consumed,
+                ..
+            } => {
+                if let Some(&idx) = stack.last()
+                    && invocations[idx].program == program_id
+                {
+                    invocations[idx].consumed = Some(consumed);
+                }
+            }
+            LogEvent::Success { .. } => {
+                if let Some(idx) = stack.pop() {
+                    invocations[idx].success = Some(true);
+                }
+            }
+            LogEvent::Failed { .. } => {
+                if let Some(idx) = stack.pop() {
+                    invocations[idx].success = Some(false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    invocations
+}
+
+/// Render the call graph as Graphviz DOT: one node per program and one edge
+/// per invocation, labeled with call order and compute units consumed
+pub fn to_dot(logs: &[String]) -> String {
+    let edges = call_graph(logs);
+    let mut dot = String::from("digraph transaction {\n");
+    dot.push_str("    rankdir=LR;\n");
+    dot.push_str("    node [shape=box];\n");
+    dot.push_str("    \"transaction\" [shape=ellipse];\n");
+
+    let mut programs: Vec<Pubkey> = Vec::new();
+    for edge in edges.iter() {
+        if !programs.contains(&edge.callee) {
+            programs.push(edge.callee);
+            dot.push_str(&format!("    \"{}\";\n", edge.callee));
+        }
+    }
+
+    for (idx, edge) in edges.iter().enumerate() {
+        let caller = edge
+            .caller
+            .map_or("transaction".to_string(), |caller| caller.to_string());
+        let label = match edge.consumed {
+            Some(consumed) => format!("#{} {} CU", idx + 1, consumed),
+            None => format!("#{}", idx + 1),
+        };
+        dot.push_str(&format!(
+            "    \"{}\" -> \"{}\" [label=\"{}\"];\n",
+            caller, edge.callee, label
+        ));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Re-render raw logs the way the Solana Explorer shows them: one section
+/// per top-level instruction, with `>` lines indented by invocation depth
+pub fn format_explorer_style(logs: &[String]) -> String {
+    let mut out = String::new();
+    let mut depth = 0usize;
+    let mut instruction = 0usize;
+
+    for log in logs {
+        match LogEvent::parse(log) {
+            LogEvent::Invoke {
+                program_id,
+                depth: invoke_depth,
+            } => {
+                if invoke_depth == 1 {
+                    instruction += 1;
+                    if instruction > 1 {
+                        out.push('\n');
+                    }
+                    out.push_str(&format!("#{} {} Instruction\n", instruction, program_id));
+                } else {
+                    push_explorer_line(&mut out, depth, &format!("Program invoked: {}", program_id));
+                }
+                depth = invoke_depth;
+            }
+            LogEvent::Log(message) => {
+                push_explorer_line(&mut out, depth, &format!("Program logged: \"{}\"", message));
+            }
+            LogEvent::Data(data) => {
+                push_explorer_line(&mut out, depth, &format!("Program data: {}", data));
+            }
+            LogEvent::Return { data, .. } => {
+                push_explorer_line(&mut out, depth, &format!("Program return: {}", data));
+            }
+            LogEvent::Consumed {
+                consumed, budget, ..
+            } => {
+                push_explorer_line(
+                    &mut out,
+                    depth,
+                    &format!("Program consumed: {} of {} compute units", consumed, budget),
+                );
+            }
+            LogEvent::Success { .. } => {
+                push_explorer_line(&mut out, depth, "Program returned success");
+                depth = depth.saturating_sub(1);
+            }
+            LogEvent::Failed { reason, .. } => {
+                push_explorer_line(&mut out, depth, &format!("Program returned error: \"{}\"", reason));
+                depth = depth.saturating_sub(1);
+            }
+            LogEvent::Other(line) => push_explorer_line(&mut out, depth, &line),
+        }
+    }
+
+    out
+}
+
+fn push_explorer_line(out: &mut String, depth: usize, text: &str) {
+    out.push_str(&"  ".repeat(depth.saturating_sub(1)));
+    out.push_str("> ");
+    out.push_str(text);
+    out.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logs(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_log_event_parse() {
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+
+        assert_eq!(
+            LogEvent::parse("Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]"),
+            LogEvent::Invoke {
+                program_id: token,
+                depth: 2
+            }
+        );
+        assert_eq!(
+            LogEvent::parse(
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 174712 compute units"
+            ),
+            LogEvent::Consumed {
+                program_id: token,
+                consumed: 4645,
+                budget: 174712
+            }
+        );
+        assert_eq!(
+            LogEvent::parse("Program log: Instruction: Transfer"),
+            LogEvent::Log("Instruction: Transfer".to_string())
+        );
+        assert_eq!(
+            LogEvent::parse("Log truncated"),
+            LogEvent::Other("Log truncated".to_string())
+        );
+    }
+
+    #[test]
+    fn test_call_graph_and_dot() {
+        let logs = logs(&[
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 1569 of 194193 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL consumed 24988 of 199700 compute units",
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL success",
+        ]);
+
+        let edges = call_graph(&logs);
+        assert_eq!(edges.len(), 2);
+        assert_eq!(edges[0].caller, None);
+        assert_eq!(edges[0].consumed, Some(24988));
+        assert_eq!(edges[1].caller, Some(edges[0].callee));
+        assert_eq!(edges[1].consumed, Some(1569));
+
+        let dot = to_dot(&logs);
+        assert!(dot.starts_with("digraph transaction {"));
+        assert!(dot.contains(
+            "\"ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL\" -> \"TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA\" [label=\"#2 1569 CU\"];"
+        ));
+    }
+
+    #[test]
+    fn test_invocations_carry_their_own_compute_units() {
+        let logs = logs(&[
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 1569 of 194193 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 2100 of 190000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: insufficient funds",
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL consumed 24988 of 199700 compute units",
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL failed: insufficient funds",
+            "Program MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr invoke [1]",
+        ]);
+
+        let invocations = invocations(&logs);
+        let summary: Vec<_> = invocations
+            .iter()
+            .map(|invocation| (invocation.depth, invocation.consumed, invocation.success))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, Some(24988), Some(false)),
+                (2, Some(1569), Some(true)),
+                (2, Some(2100), Some(false)),
+                (1, None, None),
+            ]
+        );
+        assert_eq!(invocations[1].program, invocations[2].program);
+    }
+
+    #[test]
+    fn test_format_explorer_style() {
+        let logs = logs(&[
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL invoke [1]",
+            "Program log: Create",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL success",
+        ]);
+
+        let expected = [
+            "#1 ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL Instruction",
+            "> Program logged: \"Create\"",
+            "> Program invoked: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+            "  > Program returned success",
+            "> Program returned success",
+        ];
+
+        assert_eq!(format_explorer_style(&logs), expected.join("\n") + "\n");
+    }
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, path::Path, str::FromStr};
+
+use solana_sdk::pubkey::Pubkey;
+
+/// Built-in labels for well-known programs
+const WELL_KNOWN_PROGRAMS: &[(&str, &str)] = &[
+    ("11111111111111111111111111111111", "System Program"),
+    ("ComputeBudget111111111111111111111111111111", "Compute Budget Program"),
+    ("AddressLookupTab1e1111111111111111111111111", "Address Lookup Table Program"),
+    ("Stake11111111111111111111111111111111111111", "Stake Program"),
+    ("Vote111111111111111111111111111111111111111", "Vote Program"),
+    ("BPFLoaderUpgradeab1e11111111111111111111111", "BPF Upgradeable Loader"),
+    ("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA", "Token Program"),
+    ("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb", "Token-2022 Program"),
+    ("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL", "Associated Token Account Program"),
+    ("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", "Memo Program"),
+    ("Memo1UhkJRfHyvLMcVucJwxXeuD728EqVDDwQDxFMNo", "Memo Program v1"),
+    ("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s", "Metaplex Token Metadata"),
+    ("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", "Jupiter Aggregator v6"),
+    ("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8", "Raydium AMM v4"),
+    ("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK", "Raydium CLMM"),
+    ("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc", "Orca Whirlpools"),
+    ("LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo", "Meteora DLMM"),
+    ("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY", "Phoenix"),
+    ("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX", "OpenBook"),
+    ("6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P", "Pump.fun"),
+];
+
+/// Human-readable labels for program ids
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramLabels(HashMap<Pubkey, String>);
+
+impl Default for ProgramLabels {
+    fn default() -> Self {
+        ProgramLabels(
+            WELL_KNOWN_PROGRAMS
+                .iter()
+                .map(|(program_id, label)| {
+                    (Pubkey::from_str(program_id).unwrap(), label.to_string())
+                })
+                .collect(),
+        )
+    }
+}
+
+impl ProgramLabels {
+    /// Labels for the built-in well-known programs
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, program_id: Pubkey, label: String) {
+        self.0.insert(program_id, label);
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&str> {
+        self.0.get(program_id).map(|label| label.as_str())
+    }
+
+    /// Add labels from a JSON file mapping program ids to labels, e.g.
+    /// `{"<program id>": "My Program"}`. Entries override built-in labels.
+    pub fn extend_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), String> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let labels: HashMap<String, String> = serde_json::from_str(&contents)
+            .map_err(|e| format!("Invalid labels file {}: {}", path.display(), e))?;
+
+        for (program_id, label) in labels {
+            let program_id = Pubkey::from_str(&program_id)
+                .map_err(|_| format!("Invalid program ID in {}: {}", path.display(), program_id))?;
+            self.insert(program_id, label);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_and_custom_labels() {
+        let mut labels = ProgramLabels::new();
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let custom = Pubkey::new_from_array([7; 32]);
+
+        assert_eq!(labels.get(&token), Some("Token Program"));
+        assert_eq!(labels.get(&custom), None);
+
+        labels.insert(custom, "My Program".to_string());
+        assert_eq!(labels.get(&custom), Some("My Program"));
+    }
+}
@@ -1 +1,42 @@
+//! Odin fetches Solana transactions and parses their logs: program logs,
+//! compute units, errors, token balance changes and more.
+//!
+//! Use [`TxLogParser`] to parse transactions straight from an RPC,
+//! [`subscribe`] to stream them, or [`OdinClient`] to talk to a running
+//! `odin-server`. The generated gRPC types live in [`proto`], so other crates
+//! can build their own clients and servers on the same messages.
+
+pub mod accounts;
+pub mod aimd;
+pub mod anchor;
+pub mod audit;
+pub mod backoff;
+pub mod breaker;
+pub mod cache;
+pub mod compute_budget;
+pub mod cu_stats;
+pub mod decode;
+pub mod enriched;
+pub mod error;
+pub mod expect;
+pub mod frame;
+pub mod grpc_client;
+pub mod inner;
+pub mod invoke;
+pub mod labels;
+pub mod output;
 pub mod parser;
+pub mod proto;
+pub mod rewards;
+pub mod rpc_pool;
+pub mod severity;
+pub mod status;
+pub mod subscribe;
+pub mod token_balance;
+pub mod tx_error;
+
+pub use error::ParseError;
+pub use grpc_client::OdinClient;
+pub use output::{FieldNaming, ParsedTransaction};
+pub use parser::{LogOrder, TxLogParser};
+pub use subscribe::{SubscribeOptions, subscribe_program_logs};
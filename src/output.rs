@@ -0,0 +1,388 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+
+use crate::expect::instruction_names;
+use crate::parser::TxLogParser;
+
+/// Key style used when serializing output to JSON
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FieldNaming {
+    /// `raw_logs`, matching the Rust field names
+    #[default]
+    SnakeCase,
+    /// `rawLogs`, for JavaScript consumers
+    CamelCase,
+}
+
+impl FromStr for FieldNaming {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "snake" | "snake_case" => Ok(FieldNaming::SnakeCase),
+            "camel" | "camelcase" => Ok(FieldNaming::CamelCase),
+            _ => Err(format!("Unknown field naming: {} (expected snake or camel)", s)),
+        }
+    }
+}
+
+/// Compute unit usage of one program
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParsedComputeUnits {
+    pub program_id: String,
+    pub consumed: u64,
+    pub budget: Option<u64>,
+    pub invocations: u32,
+}
+
+/// Why and where the transaction failed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParsedError {
+    pub error: String,
+    pub instruction_index: Option<u8>,
+    pub program_id: Option<String>,
+}
+
+/// JSON-friendly summary of a parsed transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ParsedTransaction {
+    pub signature: String,
+    /// `legacy` or the version number, e.g. `0`
+    pub version: Option<String>,
+    pub logs: Vec<String>,
+    pub raw_logs: Vec<String>,
+    pub programs_invoked: Vec<String>,
+    pub compute_units: Vec<ParsedComputeUnits>,
+    pub compute_units_consumed: Option<u64>,
+    pub compute_budget_explicit: bool,
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>,
+    pub top_level_instructions: usize,
+    pub inner_instructions: usize,
+    pub error: Option<ParsedError>,
+    pub truncated: bool,
+}
+
+impl ParsedTransaction {
+    pub fn from_parser(parser: &TxLogParser) -> Self {
+        ParsedTransaction {
+            signature: parser.tx_sig.clone(),
+            version: parser.get_version(),
+            logs: parser.get_tx_logs(),
+            raw_logs: parser.get_raw_logs(),
+            programs_invoked: parser
+                .get_programs_invoked()
+                .iter()
+                .map(|program_id| program_id.to_string())
+                .collect(),
+            compute_units: parser
+                .get_compute_unit_logs()
+                .sorted()
+                .into_iter()
+                .map(|(program_id, log)| ParsedComputeUnits {
+                    program_id: program_id.to_string(),
+                    consumed: log.consumed,
+                    budget: log.budget,
+                    invocations: log.invocations,
+                })
+                .collect(),
+            compute_units_consumed: parser.get_compute_units_consumed(),
+            compute_budget_explicit: parser.is_compute_budget_explicit(),
+            compute_unit_limit: parser.get_compute_budget().and_then(|budget| budget.unit_limit),
+            compute_unit_price: parser.get_compute_budget().and_then(|budget| budget.unit_price),
+            top_level_instructions: parser.get_instruction_counts().0,
+            inner_instructions: parser.get_instruction_counts().1,
+            error: parser.get_tx_error().map(|err| ParsedError {
+                error: err.error,
+                instruction_index: err.instruction_index,
+                program_id: err.program_id.map(|program_id| program_id.to_string()),
+            }),
+            truncated: parser.is_truncated(),
+        }
+    }
+
+    /// Stable hex SHA-256 of the transaction's structure, equal for
+    /// transactions doing the same thing on behalf of different users. The
+    /// hash covers, one per line:
+    ///
+    /// - `programs:` the invoked program ids, sorted and comma-separated
+    /// - `instructions:` the names from `Program log: Instruction: <name>`
+    ///   lines, in execution order and comma-separated
+    /// - `outcome:` `ok`, or `failed` when the transaction has an error
+    ///
+    /// Signature, slot, accounts, amounts, compute units and fees are left
+    /// out, so they don't split otherwise identical transactions.
+    pub fn fingerprint(&self) -> String {
+        let mut programs = self.programs_invoked.clone();
+        programs.sort();
+        programs.dedup();
+
+        let outcome = if self.error.is_some() { "failed" } else { "ok" };
+        let input = format!(
+            "programs:{}\ninstructions:{}\noutcome:{}",
+            programs.join(","),
+            instruction_names(&self.raw_logs).join(","),
+            outcome
+        );
+
+        Sha256::digest(input)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Serialize to a JSON value with keys in the requested style
+    pub fn to_json(&self, naming: FieldNaming) -> Value {
+        let value = serde_json::to_value(self).unwrap_or_default();
+
+        match naming {
+            FieldNaming::SnakeCase => value,
+            FieldNaming::CamelCase => camel_case_keys(value),
+        }
+    }
+
+    /// Like `to_json`, keeping only the named top-level fields. Names may be
+    /// in either key style; unknown names are an error.
+    pub fn to_json_fields(&self, naming: FieldNaming, fields: &[String]) -> Result<Value, String> {
+        let Value::Object(mut all) = self.to_json(FieldNaming::SnakeCase) else {
+            return Ok(Value::Null);
+        };
+
+        let mut selected = Map::new();
+        for field in fields {
+            let key = field_key(field)?;
+            if let Some(value) = all.remove(&key) {
+                selected.insert(key, value);
+            }
+        }
+
+        let selected = Value::Object(selected);
+        Ok(match naming {
+            FieldNaming::SnakeCase => selected,
+            FieldNaming::CamelCase => camel_case_keys(selected),
+        })
+    }
+
+    /// Flat, single-level event for log-based observability tools such as
+    /// Honeycomb or Datadog: conventional top-level keys and dotted `tx.*`
+    /// keys instead of nesting. `duration` is how long fetching and parsing
+    /// took; missing values are left out rather than sent as null.
+    pub fn to_observability_event(
+        &self,
+        service: &str,
+        slot: Option<u64>,
+        duration: Duration,
+    ) -> Value {
+        let mut event = Map::new();
+        event.insert("service".to_string(), service.into());
+        event.insert(
+            "timestamp".to_string(),
+            chrono::Utc::now()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+                .into(),
+        );
+        event.insert(
+            "duration_ms".to_string(),
+            (duration.as_secs_f64() * 1000.0).into(),
+        );
+        event.insert("tx.signature".to_string(), self.signature.clone().into());
+        if let Some(slot) = slot {
+            event.insert("tx.slot".to_string(), slot.into());
+        }
+        if let Some(ref version) = self.version {
+            event.insert("tx.version".to_string(), version.clone().into());
+        }
+        event.insert("tx.success".to_string(), self.error.is_none().into());
+        if let Some(ref error) = self.error {
+            event.insert("tx.error".to_string(), error.error.clone().into());
+        }
+        if let Some(consumed) = self.compute_units_consumed {
+            event.insert("tx.compute_units".to_string(), consumed.into());
+        }
+        event.insert(
+            "tx.programs".to_string(),
+            self.programs_invoked.join(",").into(),
+        );
+        event.insert(
+            "tx.instructions".to_string(),
+            self.top_level_instructions.into(),
+        );
+        event.insert("tx.log_count".to_string(), self.raw_logs.len().into());
+        event.insert("tx.truncated".to_string(), self.truncated.into());
+
+        Value::Object(event)
+    }
+
+    /// Check names for `to_json_fields` without serializing anything
+    pub fn check_fields(fields: &[String]) -> Result<(), String> {
+        fields
+            .iter()
+            .try_for_each(|field| field_key(field).map(|_| ()))
+    }
+}
+
+/// The snake_case top-level key `field` names, in either key style
+fn field_key(field: &str) -> Result<String, String> {
+    let Value::Object(keys) =
+        serde_json::to_value(ParsedTransaction::default()).unwrap_or_default()
+    else {
+        return Err(format!("Unknown field: {}", field));
+    };
+
+    keys.keys()
+        .find(|key| *key == field || to_camel_case(key) == field)
+        .cloned()
+        .ok_or_else(|| {
+            format!(
+                "Unknown field: {} (expected one of {})",
+                field,
+                keys.keys().cloned().collect::<Vec<_>>().join(", ")
+            )
+        })
+}
+
+/// Recursively rename object keys from snake_case to camelCase
+fn camel_case_keys(value: Value) -> Value {
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| (to_camel_case(&key), camel_case_keys(value)))
+                .collect::<Map<String, Value>>(),
+        ),
+        Value::Array(values) => Value::Array(values.into_iter().map(camel_case_keys).collect()),
+        other => other,
+    }
+}
+
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper = false;
+
+    for c in key.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.extend(c.to_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_camel_case_naming() {
+        let parsed = ParsedTransaction {
+            raw_logs: vec!["Program log: hi".to_string()],
+            compute_units_consumed: Some(150),
+            error: Some(ParsedError {
+                error: "Custom(1)".to_string(),
+                instruction_index: Some(2),
+                program_id: None,
+            }),
+            ..Default::default()
+        };
+
+        let snake = parsed.to_json(FieldNaming::SnakeCase);
+        assert_eq!(snake["compute_units_consumed"], 150);
+
+        let camel = parsed.to_json(FieldNaming::CamelCase);
+        assert_eq!(camel["computeUnitsConsumed"], 150);
+        assert_eq!(camel["rawLogs"][0], "Program log: hi");
+        assert_eq!(camel["error"]["instructionIndex"], 2);
+        assert!(camel.get("raw_logs").is_none());
+    }
+
+    #[test]
+    fn test_observability_event_is_flat() {
+        let parsed = ParsedTransaction {
+            signature: "sig".to_string(),
+            compute_units_consumed: Some(4645),
+            programs_invoked: vec!["a".to_string(), "b".to_string()],
+            error: Some(ParsedError {
+                error: "Custom(1)".to_string(),
+                instruction_index: None,
+                program_id: None,
+            }),
+            ..Default::default()
+        };
+
+        let event = parsed.to_observability_event("odin", Some(42), Duration::from_millis(12));
+
+        assert_eq!(event["service"], "odin");
+        assert_eq!(event["duration_ms"], 12.0);
+        assert_eq!(event["tx.signature"], "sig");
+        assert_eq!(event["tx.slot"], 42);
+        assert_eq!(event["tx.success"], false);
+        assert_eq!(event["tx.error"], "Custom(1)");
+        assert_eq!(event["tx.compute_units"], 4645);
+        assert_eq!(event["tx.programs"], "a,b");
+        assert!(event.get("tx.version").is_none());
+        assert!(
+            event
+                .as_object()
+                .unwrap()
+                .values()
+                .all(|value| !value.is_object() && !value.is_array())
+        );
+    }
+
+    #[test]
+    fn test_select_fields() {
+        let parsed = ParsedTransaction {
+            logs: vec!["hi".to_string()],
+            compute_units_consumed: Some(150),
+            ..Default::default()
+        };
+        let fields = ["computeUnitsConsumed".to_string(), "logs".to_string()];
+
+        let selected = parsed
+            .to_json_fields(FieldNaming::SnakeCase, &fields)
+            .unwrap();
+        assert_eq!(
+            selected,
+            serde_json::json!({"compute_units_consumed": 150, "logs": ["hi"]})
+        );
+        assert_eq!(
+            parsed
+                .to_json_fields(FieldNaming::CamelCase, &fields[..1])
+                .unwrap(),
+            serde_json::json!({"computeUnitsConsumed": 150})
+        );
+
+        let err = ParsedTransaction::check_fields(&["fee".to_string()]).unwrap_err();
+        assert!(err.starts_with("Unknown field: fee"));
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_who_and_when() {
+        let transfer = |signature: &str, programs: &[&str]| ParsedTransaction {
+            signature: signature.to_string(),
+            raw_logs: vec!["Program log: Instruction: Transfer".to_string()],
+            programs_invoked: programs.iter().map(|p| p.to_string()).collect(),
+            ..Default::default()
+        };
+
+        let a = transfer("sigA", &["Token", "ComputeBudget"]);
+        let b = transfer("sigB", &["ComputeBudget", "Token"]);
+        assert_eq!(a.fingerprint(), b.fingerprint());
+        assert_eq!(a.fingerprint().len(), 64);
+
+        let failed = ParsedTransaction {
+            error: Some(ParsedError::default()),
+            ..a.clone()
+        };
+        assert_ne!(a.fingerprint(), failed.fingerprint());
+        assert_ne!(a.fingerprint(), transfer("sigA", &["Token"]).fingerprint());
+    }
+}
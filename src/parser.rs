@@ -1,147 +1,2088 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::Path,
+    str::FromStr,
+    sync::{Arc, LazyLock},
+    time::Duration,
+};
 
+use base64::{Engine, prelude::BASE64_STANDARD};
+use futures_util::{FutureExt, StreamExt, stream::FuturesUnordered};
 use regex::Regex;
+use reqwest::{
+    StatusCode,
+    header::{HeaderMap, HeaderName, HeaderValue, USER_AGENT},
+};
 use solana_commitment_config::CommitmentConfig;
-use solana_rpc_client::nonblocking::rpc_client;
-use solana_rpc_client_api::config::RpcTransactionConfig;
-use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    nonblocking::rpc_client,
+    rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClientConfig},
+};
+use solana_rpc_client_api::{
+    client_error::{Error as ClientError, ErrorKind as ClientErrorKind},
+    config::RpcTransactionConfig,
+    request::{RpcError, RpcRequest},
+    response::{Response as RpcResponse, RpcSimulateTransactionResult},
+};
+use solana_sdk::{
+    pubkey::Pubkey,
+    signature::Signature,
+    transaction::{TransactionVersion, VersionedTransaction},
+};
 use solana_transaction_status_client_types::{
-    UiTransactionEncoding, UiTransactionStatusMeta, option_serializer::OptionSerializer,
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, TransactionBinaryEncoding,
+    UiMessage, UiTransactionEncoding, UiTransactionStatusMeta, option_serializer::OptionSerializer,
 };
+use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
+
+use crate::accounts::AccountAccessSummary;
+use crate::anchor::{ANCHOR_DISCRIMINATOR_LEN, AnchorEvent, Idl, RawEvent, raw_events};
+use crate::cache::TxCache;
+use crate::compute_budget::{COMPUTE_BUDGET_PROGRAM_ID, ComputeBudget, CostBreakdown};
+use crate::decode::{self, DecodedInstruction};
+use crate::enriched::{EnrichedProvider, EnrichedTransaction};
+use crate::error::ParseError;
+use crate::inner::{InnerInstruction, inner_instructions};
+use crate::invoke::{self, LogEvent};
+use crate::rewards::{Reward, rewards};
+use crate::rpc_pool::SharedRpcClient;
+use crate::severity::{Severity, SeverityKeywords};
+use crate::token_balance::{TokenBalanceChange, token_balance_changes};
+use crate::tx_error::TxError;
 
 pub const PROGRAM_LOG_PREFIX: &str = "Program log:";
 pub const COMPUTE_UNIT_LOG_DISC: &str = "compute units";
+pub const PROGRAM_INVOKE_LOG_DISC: &str = " invoke [";
+
+/// User-Agent sent with RPC requests unless overridden
+pub const DEFAULT_USER_AGENT: &str = concat!("odin/", env!("CARGO_PKG_VERSION"));
+
+// getSignaturesForAddress returns at most this many signatures per call
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+
+// Most signature pages one related lookup walks back through before giving
+// up, so a busy account can't turn one request into endless RPC calls
+const MAX_RELATED_PAGES: usize = 10;
+
+/// Highest transaction version fetched unless overridden
+pub const DEFAULT_MAX_SUPPORTED_VERSION: u8 = 0;
+
+// JSON-RPC error code for a transaction newer than maxSupportedTransactionVersion
+const UNSUPPORTED_TRANSACTION_VERSION_CODE: i64 = -32015;
+
+/// JSON-RPC errors meaning the node can't answer yet rather than that the
+/// transaction doesn't exist: block not available, node unhealthy (behind),
+/// block status not available yet, minimum context slot not reached
+const NODE_BEHIND_CODES: [i64; 4] = [-32004, -32005, -32014, -32016];
+const INVALID_PARAMS_CODE: i64 = -32602;
+const INTERNAL_ERROR_CODE: i64 = -32603;
+
+/// `Program <id> consumed <consumed> of <budget> compute units`, compiled
+/// once for the whole process rather than per transaction
+static COMPUTE_UNIT_LOG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"Program (\w+) consumed (\d+) of (\d+) compute units")
+        .expect("compute unit regex should compile")
+});
+
+/// Encodings `getTransaction` is tried with, in order. Some RPCs can't
+/// produce `jsonParsed` for every transaction; the rest fall back to the
+/// raw message.
+const FETCH_ENCODINGS: [UiTransactionEncoding; 3] = [
+    UiTransactionEncoding::JsonParsed,
+    UiTransactionEncoding::Json,
+    UiTransactionEncoding::Base64,
+];
+
+// Matches the timeout of the stock Solana HTTP sender
+const RPC_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Compute unit usage reported for a single program
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputeUnitLog {
+    /// Units consumed, summed over the program's `consumed` lines
+    pub consumed: u64,
+    /// Units that were available to the last invocation (`consumed X of <budget>`)
+    pub budget: Option<u64>,
+    /// Number of `consumed` lines seen for the program
+    pub invocations: u32,
+}
+
+/// A program's place in a transaction's compute unit usage
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuRank {
+    pub program_id: Pubkey,
+    pub consumed: u64,
+    /// Percentage of the summed per-program usage
+    pub pct_of_total: f64,
+    /// 1 for the program that consumed the most
+    pub rank: u32,
+}
+
+/// A transaction's compute unit usage with and without framework overhead
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CuTotals {
+    /// Units consumed by the whole transaction
+    pub gross: u64,
+    /// `gross` less the units of the excluded programs
+    pub net: u64,
+}
+
+/// Program validators vote through; vote transactions consume compute units
+/// but are rarely what CU analysis is about
+pub const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+
+/// Units a builtin program is charged per instruction. Builtins never log a
+/// `consumed` line, so this is all there is to go on for them.
+const BUILTIN_COMPUTE_UNITS: &[(&str, u64)] = &[
+    (COMPUTE_BUDGET_PROGRAM_ID, 150),
+    (decode::SYSTEM_PROGRAM_ID, 150),
+    (VOTE_PROGRAM_ID, 2100),
+];
+
+/// Units `program_id` is charged per instruction if it's a builtin
+pub fn builtin_compute_units(program_id: &Pubkey) -> Option<u64> {
+    let program_id = program_id.to_string();
+    BUILTIN_COMPUTE_UNITS
+        .iter()
+        .find(|(builtin, _)| *builtin == program_id)
+        .map(|(_, units)| *units)
+}
+
+/// Programs left out of net compute unit totals unless overridden: the
+/// Compute Budget program's instructions, System program transfers and
+/// validator votes
+pub fn default_cu_excluded_programs() -> Vec<Pubkey> {
+    [
+        COMPUTE_BUDGET_PROGRAM_ID,
+        decode::SYSTEM_PROGRAM_ID,
+        VOTE_PROGRAM_ID,
+    ]
+    .iter()
+    .map(|program_id| Pubkey::from_str(program_id).unwrap())
+    .collect()
+}
+
+/// Per-program compute unit usage for a transaction
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ComputeUnitLogs(HashMap<Pubkey, ComputeUnitLog>);
+
+impl ComputeUnitLogs {
+    pub fn new() -> Self {
+        ComputeUnitLogs(HashMap::new())
+    }
+
+    /// Record a `Program <id> consumed <consumed> of <budget> compute units`
+    /// line, adding to what earlier invocations of the program consumed
+    pub fn record(&mut self, program_id: Pubkey, consumed: u64, budget: u64) {
+        let entry = self.0.entry(program_id).or_default();
+        entry.consumed += consumed;
+        entry.budget = Some(budget);
+        entry.invocations += 1;
+    }
+
+    pub fn get(&self, program_id: &Pubkey) -> Option<&ComputeUnitLog> {
+        self.0.get(program_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Pubkey, &ComputeUnitLog)> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn total_consumed(&self) -> u64 {
+        self.0.values().map(|log| log.consumed).sum()
+    }
+
+    /// Entries sorted by units consumed, heaviest first, ties broken by
+    /// program id, so output doesn't depend on `HashMap` order
+    pub fn sorted(&self) -> Vec<(&Pubkey, &ComputeUnitLog)> {
+        let mut entries: Vec<_> = self.0.iter().collect();
+        entries.sort_by(|a, b| {
+            b.1.consumed
+                .cmp(&a.1.consumed)
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        entries
+    }
+
+    /// Programs ranked in `sorted` order
+    pub fn ranking(&self) -> Vec<CuRank> {
+        let total = self.total_consumed();
+
+        self.sorted()
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (program_id, log))| CuRank {
+                program_id: *program_id,
+                consumed: log.consumed,
+                pct_of_total: if total == 0 {
+                    0.0
+                } else {
+                    log.consumed as f64 * 100.0 / total as f64
+                },
+                rank: idx as u32 + 1,
+            })
+            .collect()
+    }
+
+    /// Gross and net usage, `net` leaving out what the `excluded` programs
+    /// used: the units they logged, or for builtins, which log none, their
+    /// per-instruction cost times their count in `invocations`
+    pub fn totals(
+        &self,
+        gross: u64,
+        excluded: &[Pubkey],
+        invocations: &HashMap<Pubkey, u32>,
+    ) -> CuTotals {
+        let excluded: u64 = excluded
+            .iter()
+            .map(|program_id| match self.0.get(program_id) {
+                Some(log) => log.consumed,
+                None => {
+                    let count = invocations.get(program_id).copied().unwrap_or(0);
+                    builtin_compute_units(program_id).unwrap_or(0) * count as u64
+                }
+            })
+            .sum();
+        CuTotals {
+            gross,
+            net: gross.saturating_sub(excluded),
+        }
+    }
+
+    /// Flatten to the program id -> consumed map
+    pub fn to_consumed_map(&self) -> HashMap<Pubkey, u64> {
+        self.0
+            .iter()
+            .map(|(program_id, log)| (*program_id, log.consumed))
+            .collect()
+    }
+}
+
+/// First log line containing `needle` (case-insensitive), with its index
+pub fn find_first_log<'a>(logs: &'a [String], needle: &str) -> Option<(usize, &'a String)> {
+    let needle = needle.to_lowercase();
+    logs.iter()
+        .enumerate()
+        .find(|(_, log)| log.to_lowercase().contains(&needle))
+}
 
-pub type ComputeUnitLog = HashMap<Pubkey, u64>;
+/// Compile log exclusion patterns (regular expressions matched against the
+/// program log text), rejecting the first invalid one
+pub fn compile_exclude_patterns(patterns: &[String]) -> Result<Vec<Regex>, ParseError> {
+    patterns
+        .iter()
+        .map(|pattern| {
+            Regex::new(pattern).map_err(|e| {
+                ParseError::InvalidInput(format!("Invalid exclude pattern {:?}: {}", pattern, e))
+            })
+        })
+        .collect()
+}
+
+/// Order of the program logs in `tx_logs`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogOrder {
+    /// As emitted by the runtime
+    #[default]
+    Chronological,
+    /// All logs of a program together, programs in the order they first
+    /// logged; each program's own logs stay chronological
+    GroupedByProgram,
+}
+
+impl FromStr for LogOrder {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "chronological" => Ok(LogOrder::Chronological),
+            "grouped" | "grouped-by-program" | "grouped_by_program" => {
+                Ok(LogOrder::GroupedByProgram)
+            }
+            _ => Err(format!(
+                "Unknown log order: {} (expected chronological or grouped)",
+                s
+            )),
+        }
+    }
+}
+
+/// How the log filter matches program logs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FilterMode {
+    /// Case-insensitive substring
+    #[default]
+    Substring,
+    /// Regular expression, case-sensitive unless it starts with `(?i)`
+    Regex,
+}
+
+impl FromStr for FilterMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "substring" => Ok(FilterMode::Substring),
+            "regex" => Ok(FilterMode::Regex),
+            _ => Err(format!(
+                "Unknown filter mode: {} (expected substring or regex)",
+                s
+            )),
+        }
+    }
+}
+
+/// Commitment named by a request: `processed`, `confirmed` (the default,
+/// also for an empty string) or `finalized`
+pub fn parse_commitment(s: &str) -> Result<CommitmentConfig, String> {
+    match s.to_lowercase().as_str() {
+        "" | "confirmed" => Ok(CommitmentConfig::confirmed()),
+        "processed" => Ok(CommitmentConfig::processed()),
+        "finalized" => Ok(CommitmentConfig::finalized()),
+        _ => Err(format!(
+            "Unknown commitment: {} (expected processed, confirmed or finalized)",
+            s
+        )),
+    }
+}
+
+/// A page of an address's history from `fetch_signature_page`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignaturePage {
+    /// `(signature, slot)` pairs, newest first
+    pub signatures: Vec<(String, u64)>,
+    /// Cursor for the next page (`before`), `None` on the last page
+    pub last_signature: Option<String>,
+}
+
+/// Cursor for the page after `signatures`: its oldest signature when the
+/// page came back full, `None` on a short page since nothing older is left
+fn page_cursor(signatures: &[(String, u64)], limit: usize) -> Option<String> {
+    (signatures.len() == limit)
+        .then(|| signatures.last().map(|(signature, _)| signature.clone()))
+        .flatten()
+}
+
+/// Transactions that followed a starting one, from `fetch_related_signatures`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelatedSignatures {
+    /// `(signature, slot)` pairs ordered by slot
+    pub signatures: Vec<(String, u64)>,
+    /// Cursor for the transactions after these (`until`), `None` when none
+    /// are left
+    pub next_cursor: Option<String>,
+}
+
+/// Why `tx_logs` holds what it does, so "no logs" can be told apart from
+/// "everything was filtered out"
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogSource {
+    /// Program logs survived filtering
+    Logs,
+    /// The transaction had program logs, but the filter and exclusions
+    /// removed all of them
+    Filtered,
+    /// The transaction logged, but none of its lines were program logs,
+    /// e.g. a plain SOL transfer
+    NoProgramLogs,
+    /// The transaction ran without logging anything
+    Empty,
+    /// The RPC didn't return logs at all, e.g. log recording is disabled
+    Unavailable,
+}
+
+impl LogSource {
+    /// Classify raw logs as returned by the RPC (`None` when missing) given
+    /// how many program logs they held and how many were kept from them
+    pub fn of(raw_logs: Option<&[String]>, program_logs: usize, kept: usize) -> Self {
+        match raw_logs {
+            None => LogSource::Unavailable,
+            Some([]) => LogSource::Empty,
+            Some(_) if program_logs == 0 => LogSource::NoProgramLogs,
+            Some(_) if kept == 0 => LogSource::Filtered,
+            Some(_) => LogSource::Logs,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogSource::Logs => "logs",
+            LogSource::Filtered => "filtered",
+            LogSource::NoProgramLogs => "no_program_logs",
+            LogSource::Empty => "empty",
+            LogSource::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// Encoding of a fetched transaction, judged by its shape
+fn encoding_of(tx: &EncodedTransaction) -> UiTransactionEncoding {
+    match tx {
+        EncodedTransaction::Json(ui) => match ui.message {
+            UiMessage::Parsed(_) => UiTransactionEncoding::JsonParsed,
+            UiMessage::Raw(_) => UiTransactionEncoding::Json,
+        },
+        EncodedTransaction::Binary(_, TransactionBinaryEncoding::Base64) => {
+            UiTransactionEncoding::Base64
+        }
+        EncodedTransaction::Binary(_, TransactionBinaryEncoding::Base58)
+        | EncodedTransaction::LegacyBinary(_) => UiTransactionEncoding::Base58,
+        EncodedTransaction::Accounts(_) => UiTransactionEncoding::JsonParsed,
+    }
+}
+
+/// Name the RPC uses for an encoding
+pub fn encoding_label(encoding: UiTransactionEncoding) -> &'static str {
+    match encoding {
+        UiTransactionEncoding::Binary => "binary",
+        UiTransactionEncoding::Base58 => "base58",
+        UiTransactionEncoding::Base64 => "base64",
+        UiTransactionEncoding::Json => "json",
+        UiTransactionEncoding::JsonParsed => "jsonParsed",
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// HTTP client that sends `user_agent` and `headers` with every request
+fn new_http_client(
+    user_agent: &str,
+    headers: &[(String, String)],
+) -> Result<reqwest::Client, ParseError> {
+    let mut header_map = HeaderMap::new();
+
+    for (name, value) in headers.iter() {
+        let header_name = HeaderName::from_str(name)
+            .map_err(|_| ParseError::Config(format!("Invalid header name: {}", name)))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|_| ParseError::Config(format!("Invalid value for header {}", name)))?;
+        header_map.insert(header_name, header_value);
+    }
+
+    let user_agent_value = HeaderValue::from_str(user_agent)
+        .map_err(|_| ParseError::Config(format!("Invalid user agent: {}", user_agent)))?;
+    header_map.insert(USER_AGENT, user_agent_value);
+
+    reqwest::Client::builder()
+        .default_headers(header_map)
+        .timeout(RPC_REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| ParseError::Config(format!("Failed to build HTTP client: {}", e)))
+}
+
+/// RPC client on top of `new_http_client`
+pub(crate) fn new_rpc_client(
+    rpc_url: &str,
+    user_agent: &str,
+    headers: &[(String, String)],
+) -> Result<rpc_client::RpcClient, ParseError> {
+    Ok(rpc_client::RpcClient::new_sender(
+        HttpSender::new_with_client(rpc_url.to_string(), new_http_client(user_agent, headers)?),
+        RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+    ))
+}
+
+/// Whether a failed `getTransaction` may succeed with a plainer encoding:
+/// the RPC rejected the params or failed to build the response, or what it
+/// sent back couldn't be read
+fn is_encoding_failure(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            matches!(*code, INVALID_PARAMS_CODE | INTERNAL_ERROR_CODE)
+        }
+        ClientErrorKind::SerdeJson(_) => true,
+        _ => false,
+    }
+}
+
+/// `legacy` or the version number. The RPC leaves the version out for
+/// legacy transactions, so a missing version is legacy too.
+fn version_label(version: Option<&TransactionVersion>) -> String {
+    match version {
+        None | Some(TransactionVersion::Legacy(_)) => "legacy".to_string(),
+        Some(TransactionVersion::Number(number)) => number.to_string(),
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct TxLogParser {
     pub rpc_url: String,
     pub tx_sig: String,
     pub log_filter: Option<String>,
+    pub filter_regex: Option<Regex>,
+    pub raw_filter: Option<String>,
+    pub commitment: CommitmentConfig,
+    pub exclude_patterns: Vec<Regex>,
+    pub log_order: LogOrder,
     pub include_cu_logs: bool,
+    pub cu_excluded_programs: Vec<Pubkey>,
     pub tx_logs: Option<Vec<String>>,
     pub raw_logs: Option<Vec<String>>,
-    pub compute_unit_logs: Option<ComputeUnitLog>,
+    pub log_source: Option<LogSource>,
+    pub compute_unit_logs: Option<ComputeUnitLogs>,
     pub compute_units_consumed: Option<u64>,
+    pub programs_invoked: Option<Vec<Pubkey>>,
+    pub tx_error: Option<TxError>,
+    pub severity_keywords: SeverityKeywords,
+    pub warn_on_filter_mismatch: bool,
+    pub user_agent: String,
+    pub headers: Vec<(String, String)>,
+    pub max_logs: Option<usize>,
+    pub truncated: bool,
+    pub compute_budget: Option<ComputeBudget>,
+    pub cost_breakdown: Option<CostBreakdown>,
+    pub account_access: Option<AccountAccessSummary>,
+    pub enriched_provider: Option<EnrichedProvider>,
+    pub enriched: Option<EnrichedTransaction>,
+    pub instruction_counts: (usize, usize),
+    pub decoded_instructions: Vec<DecodedInstruction>,
+    pub inner_instructions: Vec<InnerInstruction>,
+    pub version: Option<String>,
+    pub slot: Option<u64>,
+    pub block_time: Option<i64>,
+    pub max_supported_version: u8,
+    pub encoding: Option<UiTransactionEncoding>,
+    pub cache: Option<Arc<TxCache>>,
+    pub rpc_permits: Option<Arc<Semaphore>>,
+    pub hedge_rpc_urls: Vec<String>,
+    pub upstream_outcomes: Vec<(String, Result<(), ParseError>)>,
+    pub rpc: Option<SharedRpcClient>,
+    pub event_discriminator_len: usize,
+    pub idl: Option<Idl>,
+    pub token_balance_changes: Vec<TokenBalanceChange>,
+    pub rewards: Vec<Reward>,
+    pub return_data: Option<HashMap<Pubkey, Vec<u8>>>,
+    pub status: Option<Result<(), String>>,
+    pub work_budget: Option<usize>,
+    pub work_used: usize,
+    pub events_granted: usize,
+    pub limited: bool,
 }
 
-impl TxLogParser {
-    pub fn new(
-        rpc_url: String,
-        tx_sig: String,
-        log_filter: Option<&str>,
-        include_cu_logs: bool,
-    ) -> Self {
-        TxLogParser {
-            tx_sig,
-            log_filter: log_filter.map(|s| s.to_string()),
-            rpc_url,
-            include_cu_logs,
-            tx_logs: None,
-            raw_logs: None,
-            compute_units_consumed: None,
-            compute_unit_logs: None,
-        }
+impl TxLogParser {
+    pub fn new(
+        rpc_url: String,
+        tx_sig: String,
+        log_filter: Option<&str>,
+        include_cu_logs: bool,
+    ) -> Self {
+        TxLogParser {
+            tx_sig,
+            log_filter: log_filter.map(|s| s.to_string()),
+            filter_regex: None,
+            raw_filter: None,
+            commitment: CommitmentConfig::confirmed(),
+            exclude_patterns: Vec::new(),
+            log_order: LogOrder::default(),
+            rpc_url,
+            include_cu_logs,
+            cu_excluded_programs: default_cu_excluded_programs(),
+            tx_logs: None,
+            raw_logs: None,
+            log_source: None,
+            compute_units_consumed: None,
+            compute_unit_logs: None,
+            programs_invoked: None,
+            tx_error: None,
+            severity_keywords: SeverityKeywords::default(),
+            warn_on_filter_mismatch: true,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            headers: Vec::new(),
+            max_logs: None,
+            truncated: false,
+            compute_budget: None,
+            cost_breakdown: None,
+            account_access: None,
+            enriched_provider: None,
+            enriched: None,
+            instruction_counts: (0, 0),
+            decoded_instructions: Vec::new(),
+            inner_instructions: Vec::new(),
+            version: None,
+            slot: None,
+            block_time: None,
+            max_supported_version: DEFAULT_MAX_SUPPORTED_VERSION,
+            encoding: None,
+            cache: None,
+            rpc_permits: None,
+            hedge_rpc_urls: Vec::new(),
+            upstream_outcomes: Vec::new(),
+            rpc: None,
+            event_discriminator_len: ANCHOR_DISCRIMINATOR_LEN,
+            idl: None,
+            token_balance_changes: Vec::new(),
+            rewards: Vec::new(),
+            return_data: None,
+            status: None,
+            work_budget: None,
+            work_used: 0,
+            events_granted: usize::MAX,
+            limited: false,
+        }
+    }
+
+    /// A parser sending its RPC requests through `client`, e.g. one from an
+    /// `RpcClientPool`, so its connections are reused. `with_user_agent` and
+    /// `with_header` don't apply to it, only to hedge URLs.
+    pub fn with_client(
+        client: Arc<rpc_client::RpcClient>,
+        tx_sig: String,
+        log_filter: Option<&str>,
+        include_cu_logs: bool,
+    ) -> Self {
+        let mut parser = TxLogParser::new(client.url(), tx_sig, log_filter, include_cu_logs);
+        parser.rpc = Some(SharedRpcClient(client));
+        parser
+    }
+
+    /// Keep at most `max_logs` raw log lines. Lines past the cap are still
+    /// scanned for compute units but not stored, and `is_truncated` is set.
+    pub fn with_max_logs(mut self, max_logs: usize) -> Self {
+        self.max_logs = Some(max_logs);
+        self
+    }
+
+    /// Cap the work spent on one transaction: log lines processed, compute
+    /// unit lines parsed, events decoded and instructions decoded each cost
+    /// one unit. Work
+    /// past the budget is skipped and `is_limited` is set, so results are
+    /// partial rather than unbounded.
+    pub fn with_work_budget(mut self, units: usize) -> Self {
+        self.work_budget = Some(units);
+        self
+    }
+
+    /// Highest transaction version to accept (default 0). Newer transactions
+    /// fail with `ParseError::UnsupportedVersion`.
+    pub fn with_max_supported_version(mut self, max_supported_version: u8) -> Self {
+        self.max_supported_version = max_supported_version;
+        self
+    }
+
+    /// Set the User-Agent sent with RPC requests (default `odin/<version>`)
+    pub fn with_user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Add a custom HTTP header to every RPC request, e.g. a provider API key
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Also query an enhanced-API provider (e.g. Helius) for its parsed view
+    /// of the transaction, available from `get_enriched` after `parse`
+    pub fn with_enriched_provider(mut self, provider: EnrichedProvider) -> Self {
+        self.enriched_provider = Some(provider);
+        self
+    }
+
+    /// Drop program logs matching any of `patterns` before the log filter
+    /// runs. Raw logs are kept as they are. Adds to any patterns already set.
+    pub fn with_exclude_patterns(mut self, patterns: &[Regex]) -> Self {
+        self.exclude_patterns.extend_from_slice(patterns);
+        self
+    }
+
+    /// Look fetched transactions up in, and add them to, a cache shared with
+    /// other parsers
+    pub fn with_cache(mut self, cache: Arc<TxCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Programs whose compute units `get_cu_totals` leaves out of the net
+    /// figure, replacing the default Compute Budget, System and Vote programs
+    pub fn with_cu_excluded_programs(mut self, programs: &[Pubkey]) -> Self {
+        self.cu_excluded_programs = programs.to_vec();
+        self
+    }
+
+    /// Hold a permit from `permits` for every upstream call, so parsers
+    /// sharing it never have more calls in flight than it has permits
+    pub fn with_rpc_permits(mut self, permits: Arc<Semaphore>) -> Self {
+        self.rpc_permits = Some(permits);
+        self
+    }
+
+    /// Race `getTransaction` against these endpoints as well as `rpc_url`
+    /// and keep the first successful response, dropping the slower requests.
+    /// They must serve the same cluster as `rpc_url`.
+    pub fn with_hedge_rpc_urls(mut self, urls: &[String]) -> Self {
+        self.hedge_rpc_urls = urls.to_vec();
+        self
+    }
+
+    /// Discriminator length used to split `Program data:` events when no IDL
+    /// is given (default 8, as in Anchor). Programs with their own event
+    /// conventions may use e.g. 4. Decoding against an IDL is unaffected.
+    pub fn with_event_discriminator_len(mut self, len: usize) -> Self {
+        self.event_discriminator_len = len;
+        self
+    }
+
+    /// IDL used by `parse_anchor_events` to name and decode the events it
+    /// declares
+    pub fn with_idl(mut self, idl: Idl) -> Self {
+        self.idl = Some(idl);
+        self
+    }
+
+    /// Match the log filter as a substring (the default) or as a regular
+    /// expression. Fails when the filter isn't a valid regular expression.
+    pub fn with_filter_mode(mut self, mode: FilterMode) -> Result<Self, ParseError> {
+        self.filter_regex = match (mode, &self.log_filter) {
+            (FilterMode::Regex, Some(log_filter)) => Some(Regex::new(log_filter).map_err(|e| {
+                ParseError::InvalidInput(format!("Invalid filter regex {:?}: {}", log_filter, e))
+            })?),
+            _ => None,
+        };
+        Ok(self)
+    }
+
+    /// Only return raw log lines containing `raw_filter` (case-insensitive)
+    /// from `get_raw_logs`, e.g. to drop CPI
+    /// scaffolding. Everything derived from the raw logs (programs, errors,
+    /// events, compute units) still sees all of them. Empty keeps every line.
+    pub fn with_raw_filter(mut self, raw_filter: &str) -> Self {
+        self.raw_filter = (!raw_filter.is_empty()).then(|| raw_filter.to_lowercase());
+        self
+    }
+
+    /// Commitment the transaction is fetched (and cached) at. `getTransaction`
+    /// doesn't serve `processed`, so that fetches at `confirmed`.
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Order of the program logs returned by `get_tx_logs`
+    pub fn with_log_order(mut self, log_order: LogOrder) -> Self {
+        self.log_order = log_order;
+        self
+    }
+
+    /// Enable or suppress the warning logged when the filter matches none or
+    /// all of a transaction's logs
+    pub fn with_filter_warnings(mut self, enabled: bool) -> Self {
+        self.warn_on_filter_mismatch = enabled;
+        self
+    }
+
+    /// Override the keyword sets used by `get_logs_by_severity`
+    pub fn with_severity_keywords(mut self, severity_keywords: SeverityKeywords) -> Self {
+        self.severity_keywords = severity_keywords;
+        self
+    }
+
+    /// Parse a saved `getTransaction` result without any RPC. Accepts either
+    /// the bare result or the full JSON-RPC response wrapping it.
+    pub fn from_json(
+        value: serde_json::Value,
+        log_filter: Option<&str>,
+        include_cu_logs: bool,
+    ) -> Result<Self, ParseError> {
+        let value = match value {
+            serde_json::Value::Object(mut response) if response.contains_key("result") => {
+                response.remove("result").unwrap_or_default()
+            }
+            value => value,
+        };
+
+        let tx_sig = value
+            .pointer("/transaction/signatures/0")
+            .and_then(|sig| sig.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(value)
+            .map_err(|e| ParseError::Decode(format!("Invalid transaction JSON: {}", e)))?;
+
+        let mut parser = TxLogParser::new(String::new(), tx_sig, log_filter, include_cu_logs);
+        parser.parse_transaction(&tx)?;
+        Ok(parser)
+    }
+
+    /// Parse a `getTransaction` result saved to a JSON file
+    pub fn from_file(
+        path: impl AsRef<Path>,
+        log_filter: Option<&str>,
+        include_cu_logs: bool,
+    ) -> Result<Self, ParseError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::InvalidInput(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        let value = serde_json::from_str(&contents).map_err(|e| {
+            ParseError::Decode(format!("Invalid JSON in {}: {}", path.display(), e))
+        })?;
+
+        Self::from_json(value, log_filter, include_cu_logs)
+    }
+
+    pub async fn parse(&mut self) -> Result<(), ParseError> {
+        let tx = self.fetch_recorded().await?;
+        self.parse_transaction(&tx)?;
+
+        // Enriched data is a bonus on top of log scraping, so a failing
+        // provider doesn't fail the parse
+        if let Some(ref provider) = self.enriched_provider {
+            let _permit = self.rpc_permit().await;
+            match provider.fetch(&self.http_client()?, &self.tx_sig).await {
+                Ok(enriched) => self.enriched = enriched,
+                Err(e) => eprintln!("⚠️  Enhanced API lookup for {} failed: {}", self.tx_sig, e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetch the transaction and send each raw log line to `events`,
+    /// classified, before anything else is parsed, so a consumer can start
+    /// rendering the call tree right away. Sending waits while the channel
+    /// is full; once the receiver is dropped the remaining lines are
+    /// skipped. The channel is closed after the last line and the parser is
+    /// then filled in as by `parse`, minus the enriched data.
+    pub async fn parse_streaming(
+        &mut self,
+        events: mpsc::Sender<LogEvent>,
+    ) -> Result<(), ParseError> {
+        let tx = self.fetch_recorded().await?;
+
+        if let Some(UiTransactionStatusMeta {
+            log_messages: OptionSerializer::Some(ref logs),
+            ..
+        }) = tx.transaction.meta
+        {
+            for log in logs.iter().take(self.max_logs.unwrap_or(usize::MAX)) {
+                if events.send(LogEvent::parse(log)).await.is_err() {
+                    break;
+                }
+            }
+        }
+        drop(events);
+
+        self.parse_transaction(&tx)
+    }
+
+    /// Run parsing over an already fetched transaction
+    pub fn parse_transaction(
+        &mut self,
+        tx: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<(), ParseError> {
+        let encoding = encoding_of(&tx.transaction.transaction);
+        self.encoding = Some(encoding);
+
+        // Binary encodings carry the compiled message; the JSON ones are
+        // read as JSON whether parsed or raw
+        let binary = matches!(
+            encoding,
+            UiTransactionEncoding::Base64 | UiTransactionEncoding::Base58
+        );
+        // `jsonParsed` keys already include addresses loaded from lookup
+        // tables; the others get them from the meta below
+        let (top_level, signatures, mut account_keys, keys_include_loaded) = match binary
+            .then(|| tx.transaction.transaction.decode())
+            .flatten()
+        {
+            Some(decoded) => {
+                self.parse_message(&decoded);
+                let account_keys: Vec<String> = decoded
+                    .message
+                    .static_account_keys()
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect();
+                (
+                    decoded.message.instructions().len(),
+                    decoded.signatures.len(),
+                    account_keys,
+                    false,
+                )
+            }
+            None => {
+                let transaction =
+                    serde_json::to_value(&tx.transaction.transaction).map_err(|e| {
+                        ParseError::Decode(format!("Failed to read transaction: {}", e))
+                    })?;
+                self.compute_budget = Some(ComputeBudget::from_transaction_json(&transaction));
+                self.account_access =
+                    Some(AccountAccessSummary::from_transaction_json(&transaction));
+                self.decoded_instructions = decode::decode_transaction_json(&transaction);
+                let granted = self.spend_work(self.decoded_instructions.len());
+                self.decoded_instructions.truncate(granted);
+
+                let top_level = transaction
+                    .pointer("/message/instructions")
+                    .and_then(|instructions| instructions.as_array())
+                    .map_or(0, |instructions| instructions.len());
+                let signatures = transaction
+                    .get("signatures")
+                    .and_then(|signatures| signatures.as_array())
+                    .map_or(0, |signatures| signatures.len());
+                let keys = transaction
+                    .pointer("/message/accountKeys")
+                    .and_then(|keys| keys.as_array())
+                    .map(|keys| keys.as_slice())
+                    .unwrap_or_default();
+                let account_keys: Vec<String> = keys
+                    .iter()
+                    .filter_map(|key| key.as_str().or_else(|| key.get("pubkey")?.as_str()))
+                    .map(|key| key.to_string())
+                    .collect();
+                let parsed_keys = keys.iter().any(|key| key.is_object());
+                (top_level, signatures, account_keys, parsed_keys)
+            }
+        };
+
+        if let (
+            Some(account_access),
+            Some(UiTransactionStatusMeta {
+                loaded_addresses: OptionSerializer::Some(loaded),
+                ..
+            }),
+        ) = (self.account_access.as_mut(), tx.transaction.meta.as_ref())
+        {
+            account_access.add_loaded(&loaded.writable, &loaded.readonly);
+        }
+        if let Some(UiTransactionStatusMeta {
+            loaded_addresses: OptionSerializer::Some(loaded),
+            ..
+        }) = tx.transaction.meta.as_ref()
+            && !keys_include_loaded
+        {
+            account_keys.extend(loaded.writable.iter().cloned());
+            account_keys.extend(loaded.readonly.iter().cloned());
+        }
+
+        self.inner_instructions = match tx.transaction.meta {
+            Some(UiTransactionStatusMeta {
+                inner_instructions: OptionSerializer::Some(ref inner),
+                ..
+            }) => inner_instructions(inner, &account_keys),
+            _ => Vec::new(),
+        };
+        self.instruction_counts = (top_level, self.inner_instructions.len());
+        self.version = Some(version_label(tx.transaction.version.as_ref()));
+        self.slot = Some(tx.slot);
+        self.block_time = tx.block_time;
+
+        match tx.transaction.meta {
+            Some(ref meta) => {
+                self.parse_meta(meta)?;
+                self.token_balance_changes = token_balance_changes(meta, &account_keys);
+                self.rewards = rewards(meta);
+                self.cost_breakdown = Some(CostBreakdown::from_fee(
+                    meta.fee,
+                    signatures,
+                    self.compute_units_consumed,
+                ));
+                Ok(())
+            }
+            None => {
+                self.parse_logs(&[])?;
+                self.log_source = Some(LogSource::Unavailable);
+                Ok(())
+            }
+        }
+    }
+
+    /// Simulate a base64-encoded (bincode) transaction and parse the logs it
+    /// would produce, without broadcasting it. The signature doesn't need to
+    /// be valid and the blockhash is replaced, so unsigned or stale
+    /// transactions can be previewed.
+    pub async fn simulate(&mut self, encoded_tx: &str) -> Result<(), ParseError> {
+        let encoded_tx = encoded_tx.trim();
+        let bytes = BASE64_STANDARD
+            .decode(encoded_tx)
+            .map_err(|e| ParseError::InvalidInput(format!("Invalid base64 transaction: {}", e)))?;
+        let tx: VersionedTransaction = bincode::deserialize(&bytes).map_err(|e| {
+            ParseError::InvalidInput(format!("Invalid transaction encoding: {}", e))
+        })?;
+
+        if self.tx_sig.is_empty()
+            && let Some(signature) = tx.signatures.first()
+        {
+            self.tx_sig = signature.to_string();
+        }
+
+        let rpc = self.rpc_client()?;
+        let _permit = self.rpc_permit().await;
+        let response: RpcResponse<RpcSimulateTransactionResult> = rpc
+            .send(
+                RpcRequest::SimulateTransaction,
+                serde_json::json!([
+                    encoded_tx,
+                    {
+                        "encoding": "base64",
+                        "sigVerify": false,
+                        "replaceRecentBlockhash": true,
+                        "commitment": "confirmed",
+                        "innerInstructions": true
+                    }
+                ]),
+            )
+            .await
+            .map_err(|e| ParseError::Rpc(format!("Failed to simulate transaction: {}", e)))?;
+        let simulation = response.value;
+
+        let budgeted_instructions = self.parse_message(&tx);
+        self.check_version(Some(&tx.version()))?;
+        self.version = Some(version_label(Some(&tx.version())));
+
+        // Simulations don't report loaded addresses, so calls to programs
+        // from lookup tables are left without a program id
+        let static_keys: Vec<String> = tx
+            .message
+            .static_account_keys()
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+        self.inner_instructions = simulation
+            .inner_instructions
+            .as_ref()
+            .map_or(Vec::new(), |inner| inner_instructions(inner, &static_keys));
+        self.instruction_counts = (
+            tx.message.instructions().len(),
+            self.inner_instructions.len(),
+        );
+
+        self.compute_units_consumed = simulation.units_consumed;
+        let program_logs = self.parse_logs(simulation.logs.as_deref().unwrap_or(&[]))?;
+        self.log_source = Some(LogSource::of(
+            simulation.logs.as_deref(),
+            program_logs,
+            self.tx_logs.as_ref().map_or(0, Vec::len),
+        ));
+
+        // Nothing was charged, so estimate what landing it would cost
+        self.cost_breakdown = Some(CostBreakdown::estimate(
+            tx.message.header().num_required_signatures as usize,
+            &self.compute_budget.unwrap_or_default(),
+            budgeted_instructions,
+            self.compute_units_consumed,
+        ));
+
+        self.tx_error = match simulation.err {
+            Some(ref err) => {
+                let err = serde_json::to_value(err).map_err(|e| {
+                    ParseError::Decode(format!("Failed to decode transaction error: {}", e))
+                })?;
+                Some(TxError::from_json(
+                    &err,
+                    self.raw_logs.as_deref().unwrap_or(&[]),
+                ))
+            }
+            None => None,
+        };
+        self.record_status();
+
+        Ok(())
+    }
+
+    /// Compute budget, System/Token instructions and account access straight
+    /// from a compiled message. Returns the number of instructions that
+    /// count towards the default compute unit limit.
+    fn parse_message(&mut self, tx: &VersionedTransaction) -> usize {
+        let account_keys = tx.message.static_account_keys();
+        let mut compute_budget = ComputeBudget::default();
+        let mut budgeted_instructions = 0;
+        self.decoded_instructions.clear();
+        for instruction in tx.message.instructions() {
+            let Some(program_id) = account_keys.get(instruction.program_id_index as usize) else {
+                continue;
+            };
+            let program_id = program_id.to_string();
+
+            if program_id == COMPUTE_BUDGET_PROGRAM_ID {
+                compute_budget.decode_instruction(&instruction.data);
+            } else {
+                budgeted_instructions += 1;
+            }
+
+            // Accounts from address lookup tables aren't resolved here
+            let accounts: Vec<String> = instruction
+                .accounts
+                .iter()
+                .map(|&idx| {
+                    account_keys
+                        .get(idx as usize)
+                        .map_or_else(|| format!("#{}", idx), |key| key.to_string())
+                })
+                .collect();
+            if let Some(decoded) =
+                decode::decode_instruction(&program_id, &instruction.data, &accounts)
+            {
+                self.decoded_instructions.push(decoded);
+            }
+        }
+        self.compute_budget = Some(compute_budget);
+        // Nor are they here; fetched transactions add them from the meta
+        self.account_access = Some(AccountAccessSummary::from_static_keys(
+            tx.message.header(),
+            account_keys,
+        ));
+
+        budgeted_instructions
+    }
+
+    /// Fetch the transaction and return the `getTransaction` result as JSON,
+    /// unparsed, in the shape `from_json` reads back
+    pub async fn fetch_json(&self) -> Result<serde_json::Value, ParseError> {
+        let tx = self.fetch_transaction().await?;
+        serde_json::to_value(tx.as_ref())
+            .map_err(|e| ParseError::Decode(format!("Failed to encode transaction: {}", e)))
+    }
+
+    /// Fetch the transaction and return the first raw log line containing
+    /// `needle` (case-insensitive) with its index, without building any of
+    /// the log vectors. `None` when no line matches.
+    pub async fn find_first(&self, needle: &str) -> Result<Option<(usize, String)>, ParseError> {
+        let tx = self.fetch_transaction().await?;
+
+        let Some(UiTransactionStatusMeta {
+            log_messages: OptionSerializer::Some(ref logs),
+            ..
+        }) = tx.transaction.meta
+        else {
+            return Ok(None);
+        };
+
+        Ok(find_first_log(logs, needle).map(|(idx, log)| (idx, log.clone())))
+    }
+
+    /// Fetch the transaction but only materialize logs when it failed, or
+    /// when a log filter is set and matches one of its logs. Returns whether
+    /// the transaction was parsed.
+    pub async fn parse_errors_only(&mut self) -> Result<bool, ParseError> {
+        let tx = self.fetch_recorded().await?;
+
+        let Some(ref meta) = tx.transaction.meta else {
+            return Ok(false);
+        };
+
+        if meta.err.is_none() && !self.filter_matches_any(meta) {
+            return Ok(false);
+        }
+
+        self.parse_meta(meta)?;
+        Ok(true)
+    }
+
+    /// Fetch only the compute units of the transaction: the total from the
+    /// meta (or the sum of per-program usage when the meta lacks it) and the
+    /// per-program usage. The log vectors are never built, which makes this
+    /// the cheap path for CU dashboards. `include_cu_logs` doesn't apply.
+    pub async fn fetch_compute_units_only(&mut self) -> Result<(u64, ComputeUnitLogs), ParseError> {
+        let tx = self.fetch_recorded().await?;
+
+        let Some(ref meta) = tx.transaction.meta else {
+            return Ok((0, ComputeUnitLogs::new()));
+        };
+
+        let compute_unit_logs = match meta.log_messages {
+            OptionSerializer::Some(ref logs) => Self::compute_unit_logs_from(logs, usize::MAX)?,
+            _ => ComputeUnitLogs::new(),
+        };
+        let consumed = match meta.compute_units_consumed {
+            OptionSerializer::Some(consumed) => consumed,
+            _ => compute_unit_logs.total_consumed(),
+        };
+
+        Ok((consumed, compute_unit_logs))
+    }
+
+    async fn fetch_transaction(
+        &self,
+    ) -> Result<Arc<EncodedConfirmedTransactionWithStatusMeta>, ParseError> {
+        self.fetch_transaction_recording(&mut Vec::new()).await
+    }
+
+    /// `fetch_transaction`, keeping what each hedged endpoint answered for
+    /// `get_upstream_outcomes`
+    async fn fetch_recorded(
+        &mut self,
+    ) -> Result<Arc<EncodedConfirmedTransactionWithStatusMeta>, ParseError> {
+        let mut outcomes = Vec::new();
+        let tx = self.fetch_transaction_recording(&mut outcomes).await;
+        self.upstream_outcomes = outcomes;
+        tx
+    }
+
+    /// `fetch_transaction`, adding the outcome of every endpoint a hedged
+    /// fetch heard from to `outcomes`
+    async fn fetch_transaction_recording(
+        &self,
+        outcomes: &mut Vec<(String, Result<(), ParseError>)>,
+    ) -> Result<Arc<EncodedConfirmedTransactionWithStatusMeta>, ParseError> {
+        // Reject a malformed signature before any cache or RPC work
+        let tx_sig = self.signature()?;
+        let commitment = if self.commitment.is_at_least_confirmed() {
+            self.commitment
+        } else {
+            CommitmentConfig::confirmed()
+        };
+        if let Some(tx) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(&self.rpc_url, &self.tx_sig, commitment.commitment))
+        {
+            self.check_version(tx.transaction.version.as_ref())?;
+            return Ok(tx);
+        }
+
+        let tx = Arc::new(if self.hedge_rpc_urls.is_empty() {
+            self.fetch_from(&self.rpc_url, &tx_sig, commitment).await?
+        } else {
+            self.fetch_hedged(&tx_sig, commitment, outcomes).await?
+        });
+
+        self.check_version(tx.transaction.version.as_ref())?;
+        if let Some(ref cache) = self.cache {
+            cache.insert(&self.rpc_url, &self.tx_sig, commitment.commitment, tx.clone());
+        }
+        Ok(tx)
+    }
+
+    /// Race `rpc_url` and the hedge URLs: whichever endpoint answers first
+    /// wins and the others are cancelled when their futures are dropped.
+    /// Every endpoint that answered, the winner and those that failed
+    /// before it, goes into `outcomes`; cancelled ones don't. When all fail
+    /// the last error is returned.
+    async fn fetch_hedged(
+        &self,
+        tx_sig: &Signature,
+        commitment: CommitmentConfig,
+        outcomes: &mut Vec<(String, Result<(), ParseError>)>,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ParseError> {
+        let mut fetches: FuturesUnordered<_> = std::iter::once(&self.rpc_url)
+            .chain(&self.hedge_rpc_urls)
+            .map(|url| self.fetch_from(url, tx_sig, commitment).map(move |result| (url, result)))
+            .collect();
+
+        let mut last_error = None;
+        while let Some((url, result)) = fetches.next().await {
+            match result {
+                Ok(tx) => {
+                    outcomes.push((url.clone(), Ok(())));
+                    return Ok(tx);
+                }
+                Err(e) => {
+                    outcomes.push((url.clone(), Err(e.clone())));
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.expect("at least one endpoint"))
+    }
+
+    /// Fetch the transaction from one endpoint, falling back through
+    /// `FETCH_ENCODINGS` when the node can't produce an encoding
+    async fn fetch_from(
+        &self,
+        rpc_url: &str,
+        tx_sig: &Signature,
+        commitment: CommitmentConfig,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, ParseError> {
+        let rpc = self.rpc_client_for(rpc_url)?;
+
+        let mut encodings = FETCH_ENCODINGS.iter().peekable();
+        loop {
+            let encoding = *encodings.next().expect("at least one encoding");
+            let _permit = self.rpc_permit().await;
+            // A transaction the node doesn't have (yet) comes back as null
+            let result: Result<Option<EncodedConfirmedTransactionWithStatusMeta>, _> = rpc
+                .send(
+                    RpcRequest::GetTransaction,
+                    serde_json::json!([
+                        tx_sig.to_string(),
+                        RpcTransactionConfig {
+                            encoding: Some(encoding),
+                            commitment: Some(commitment),
+                            max_supported_transaction_version: Some(self.max_supported_version),
+                        }
+                    ]),
+                )
+                .await;
+
+            match (result, encodings.peek()) {
+                (Ok(Some(tx)), _) => return Ok(tx),
+                (Ok(None), _) => {
+                    return Err(ParseError::NotFound(format!(
+                        "Transaction not found: {}",
+                        self.tx_sig
+                    )));
+                }
+                (Err(e), Some(&&fallback)) if is_encoding_failure(&e) => {
+                    eprintln!(
+                        "⚠️  {} encoding failed for {} ({}), retrying with {}",
+                        encoding_label(encoding),
+                        self.tx_sig,
+                        e,
+                        encoding_label(fallback)
+                    );
+                }
+                (Err(e), _) => return Err(self.fetch_error(e)),
+            }
+        }
+    }
+
+    fn fetch_error(&self, e: ClientError) -> ParseError {
+        match e.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. })
+                if *code == UNSUPPORTED_TRANSACTION_VERSION_CODE =>
+            {
+                ParseError::UnsupportedVersion(format!(
+                    "{} (max supported version is {})",
+                    message, self.max_supported_version
+                ))
+            }
+            ClientErrorKind::RpcError(RpcError::RpcResponseError { code, message, .. })
+                if NODE_BEHIND_CODES.contains(code) || message.contains("behind") =>
+            {
+                ParseError::NodeBehind(format!(
+                    "RPC node can't serve the transaction yet: {}",
+                    message
+                ))
+            }
+            ClientErrorKind::Reqwest(e) if e.status() == Some(StatusCode::TOO_MANY_REQUESTS) => {
+                ParseError::RateLimited(format!("Failed to get transaction: {}", e))
+            }
+            _ => ParseError::Rpc(format!("Failed to get transaction: {}", e)),
+        }
+    }
+
+    fn check_version(&self, version: Option<&TransactionVersion>) -> Result<(), ParseError> {
+        match version {
+            Some(TransactionVersion::Number(version)) if *version > self.max_supported_version => {
+                Err(ParseError::UnsupportedVersion(format!(
+                    "Transaction version {} is not supported (max supported version is {})",
+                    version, self.max_supported_version
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Signatures of the transactions that touched `account` from this
+    /// transaction onwards, ordered by slot: this transaction, followed by at
+    /// most `limit` others. `until` continues after an earlier page's
+    /// `next_cursor` instead, without repeating this transaction, and
+    /// `before` leaves out everything from that signature on. Fails with
+    /// `OutOfRange` when the start is more than `MAX_RELATED_PAGES` pages
+    /// back; the error names a `before` cursor to resume the walk from.
+    pub async fn fetch_related_signatures(
+        &self,
+        account: &str,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> Result<RelatedSignatures, ParseError> {
+        let tx_sig = self.signature()?;
+        Pubkey::from_str(account)
+            .map_err(|_| ParseError::InvalidInput(format!("Invalid account: {}", account)))?;
+
+        let mut signatures = Vec::new();
+        if until.is_none() {
+            let rpc = self.rpc_client()?;
+            let start_slot = self
+                .with_rpc_permit(rpc.get_signature_statuses_with_history(&[tx_sig]))
+                .await
+                .map_err(|e| ParseError::Rpc(format!("Failed to get signature status: {}", e)))?
+                .value
+                .into_iter()
+                .next()
+                .flatten()
+                .map(|status| status.slot)
+                .ok_or_else(|| {
+                    ParseError::NotFound(format!("Transaction not found: {}", self.tx_sig))
+                })?;
+            signatures.push((self.tx_sig.clone(), start_slot));
+        }
+        let until = until.unwrap_or(&self.tx_sig);
+
+        // Signatures come back newest first and `until` stops the walk at the
+        // starting signature, so the last `limit` seen are the transactions
+        // right after it
+        let mut following: VecDeque<(String, u64)> = VecDeque::new();
+        let mut truncated = false;
+        let mut before = before.map(str::to_string);
+        for _ in 0..MAX_RELATED_PAGES {
+            let page = self
+                .fetch_signature_page(
+                    account,
+                    before.as_deref(),
+                    Some(until),
+                    SIGNATURES_PAGE_SIZE,
+                )
+                .await?;
+
+            for entry in page.signatures {
+                following.push_back(entry);
+                if following.len() > limit {
+                    following.pop_front();
+                    truncated = true;
+                }
+            }
+            match page.last_signature {
+                Some(last) => before = Some(last),
+                None => {
+                    // The newest one kept is where the next page picks up
+                    let next_cursor = truncated
+                        .then(|| following.front().map(|(signature, _)| signature.clone()))
+                        .flatten();
+
+                    signatures.extend(following.into_iter().rev());
+                    signatures.sort_by_key(|(_, slot)| *slot);
+
+                    return Ok(RelatedSignatures {
+                        signatures,
+                        next_cursor,
+                    });
+                }
+            }
+        }
+
+        Err(ParseError::OutOfRange(format!(
+            "{} is more than {} pages back in the history of {}; continue with before = {}",
+            until,
+            MAX_RELATED_PAGES,
+            account,
+            before.unwrap_or_default()
+        )))
+    }
+
+    /// One page of `address`'s history, newest first, using the RPC's
+    /// signature cursors: only signatures older than `before` and newer than
+    /// `until`. Pass the returned `last_signature` as `before` to fetch the
+    /// next page; it is `None` once the history is exhausted.
+    pub async fn fetch_signature_page(
+        &self,
+        address: &str,
+        before: Option<&str>,
+        until: Option<&str>,
+        limit: usize,
+    ) -> Result<SignaturePage, ParseError> {
+        let rpc = self.rpc_client()?;
+
+        let address = Pubkey::from_str(address)
+            .map_err(|_| ParseError::InvalidInput(format!("Invalid address: {}", address)))?;
+        let cursor = |cursor: Option<&str>| {
+            cursor
+                .map(|sig| {
+                    Signature::from_str(sig).map_err(|_| {
+                        ParseError::InvalidInput(format!("Invalid signature cursor: {}", sig))
+                    })
+                })
+                .transpose()
+        };
+        let limit = limit.clamp(1, SIGNATURES_PAGE_SIZE);
+
+        let page = self
+            .with_rpc_permit(rpc.get_signatures_for_address_with_config(
+                &address,
+                GetConfirmedSignaturesForAddress2Config {
+                    before: cursor(before)?,
+                    until: cursor(until)?,
+                    limit: Some(limit),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                },
+            ))
+            .await
+            .map_err(|e| {
+                ParseError::Rpc(format!("Failed to get signatures for {}: {}", address, e))
+            })?;
+
+        let signatures: Vec<(String, u64)> = page
+            .into_iter()
+            .map(|status| (status.signature, status.slot))
+            .collect();
+        let last_signature = page_cursor(&signatures, limit);
+
+        Ok(SignaturePage {
+            signatures,
+            last_signature,
+        })
+    }
+
+    /// A permit for one upstream call, when the parser shares a limit
+    async fn rpc_permit(&self) -> Option<OwnedSemaphorePermit> {
+        match self.rpc_permits {
+            Some(ref permits) => permits.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Run `call` while holding an upstream call permit
+    async fn with_rpc_permit<T>(&self, call: impl Future<Output = T>) -> T {
+        let _permit = self.rpc_permit().await;
+        call.await
+    }
+
+    fn signature(&self) -> Result<Signature, ParseError> {
+        Signature::from_str(&self.tx_sig).map_err(|_| {
+            ParseError::InvalidInput(format!("Invalid transaction signature: {}", self.tx_sig))
+        })
+    }
+
+    /// The injected client, or a new one sending the configured User-Agent
+    /// and headers
+    fn rpc_client(&self) -> Result<Arc<rpc_client::RpcClient>, ParseError> {
+        self.rpc_client_for(&self.rpc_url)
+    }
+
+    fn rpc_client_for(&self, rpc_url: &str) -> Result<Arc<rpc_client::RpcClient>, ParseError> {
+        match self.rpc {
+            Some(SharedRpcClient(ref rpc)) if rpc_url == self.rpc_url => Ok(rpc.clone()),
+            _ => Ok(Arc::new(new_rpc_client(
+                rpc_url,
+                &self.user_agent,
+                &self.headers,
+            )?)),
+        }
+    }
+
+    /// HTTP client for the enhanced API, with the configured User-Agent and
+    /// headers
+    fn http_client(&self) -> Result<reqwest::Client, ParseError> {
+        new_http_client(&self.user_agent, &self.headers)
+    }
+
+    fn filter_matches_any(&self, meta: &UiTransactionStatusMeta) -> bool {
+        let (Some(log_filter), OptionSerializer::Some(logs)) = (&self.log_filter, &meta.log_messages)
+        else {
+            return false;
+        };
+
+        if let Some(ref filter_regex) = self.filter_regex {
+            return logs.iter().any(|log| filter_regex.is_match(log));
+        }
+        let log_filter = log_filter.to_lowercase();
+        logs.iter().any(|log| log.to_lowercase().contains(&log_filter))
+    }
+
+    /// Run `parse_meta` over a transaction meta saved as JSON, the `meta`
+    /// object of a `getTransaction` result
+    pub fn parse_from_json(&mut self, meta_json: &str) -> Result<(), ParseError> {
+        let meta: UiTransactionStatusMeta = serde_json::from_str(meta_json)
+            .map_err(|e| ParseError::Decode(format!("Invalid transaction meta JSON: {}", e)))?;
+        self.parse_meta(&meta)
+    }
+
+    /// Run `parse_meta` over a transaction meta saved to a JSON file
+    pub fn parse_from_file(&mut self, path: impl AsRef<Path>) -> Result<(), ParseError> {
+        let path = path.as_ref();
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ParseError::InvalidInput(format!("Failed to read {}: {}", path.display(), e))
+        })?;
+        self.parse_from_json(&contents)
+    }
+
+    /// Run log and compute unit extraction over an already fetched
+    /// transaction meta, without touching the network
+    pub fn parse_meta(&mut self, meta: &UiTransactionStatusMeta) -> Result<(), ParseError> {
+        if let OptionSerializer::Some(compute_units) = meta.compute_units_consumed {
+            self.compute_units_consumed = Some(compute_units);
+        }
+
+        let raw_logs = match meta.log_messages {
+            OptionSerializer::Some(ref logs) => Some(logs.as_slice()),
+            _ => None,
+        };
+        let program_logs = self.parse_logs(raw_logs.unwrap_or(&[]))?;
+        self.log_source = Some(LogSource::of(
+            raw_logs,
+            program_logs,
+            self.tx_logs.as_ref().map_or(0, Vec::len),
+        ));
+
+        self.tx_error = match meta.err {
+            Some(ref err) => {
+                let err = serde_json::to_value(err).map_err(|e| {
+                    ParseError::Decode(format!("Failed to decode transaction error: {}", e))
+                })?;
+                Some(TxError::from_json(&err, self.raw_logs.as_deref().unwrap_or(&[])))
+            }
+            None => None,
+        };
+        self.record_status();
+
+        Ok(())
+    }
+
+    /// Take up to `units` from the work budget; returns how many were
+    /// granted, marking the parse as limited when that's fewer
+    fn spend_work(&mut self, units: usize) -> usize {
+        let Some(budget) = self.work_budget else {
+            return units;
+        };
+
+        let granted = units.min(budget.saturating_sub(self.work_used));
+        self.work_used += granted;
+        if granted < units {
+            self.limited = true;
+        }
+        granted
+    }
+
+    /// Per-program compute unit usage from the first `max_lines` `consumed`
+    /// lines of the logs
+    fn compute_unit_logs_from(
+        logs: &[String],
+        max_lines: usize,
+    ) -> Result<ComputeUnitLogs, ParseError> {
+        let mut compute_unit_logs = ComputeUnitLogs::new();
+
+        for log in logs
+            .iter()
+            .filter(|log| log.contains(COMPUTE_UNIT_LOG_DISC))
+            .take(max_lines)
+        {
+            if let Some(captures) = COMPUTE_UNIT_LOG_REGEX.captures(log) {
+                // Truncated or odd validator output shouldn't cost the rest
+                // of the transaction, so a bad line is skipped
+                let (Ok(program_id), Ok(consumed), Ok(budget)) = (
+                    Pubkey::from_str(&captures[1]),
+                    captures[2].parse::<u64>(),
+                    captures[3].parse::<u64>(),
+                ) else {
+                    eprintln!("⚠️  Skipping malformed compute unit log: {}", log);
+                    continue;
+                };
+                compute_unit_logs.record(program_id, consumed, budget);
+            }
+        }
+
+        Ok(compute_unit_logs)
+    }
+
+    /// Parse raw log lines, returning how many program logs they held
+    /// before exclusions and the filter
+    fn parse_logs(&mut self, logs: &[String]) -> Result<usize, ParseError> {
+        let logs = &logs[..self.spend_work(logs.len())];
+
+        // Program logs with the program that emitted them, i.e. the top of
+        // the invoke stack
+        let mut tx_logs: Vec<(Option<Pubkey>, String)> = Vec::new();
+        let mut raw_tx_logs: Vec<String> = Vec::new();
+        let max_logs = self.max_logs.unwrap_or(usize::MAX);
+        let mut truncated = false;
+        let mut stack: Vec<Pubkey> = Vec::new();
+        let mut return_data: HashMap<Pubkey, Vec<u8>> = HashMap::new();
+
+        for log in logs {
+            match LogEvent::parse(log) {
+                LogEvent::Invoke { program_id, .. } => stack.push(program_id),
+                LogEvent::Success { .. } | LogEvent::Failed { .. } => {
+                    stack.pop();
+                }
+                // Malformed payloads are skipped rather than failing the parse
+                LogEvent::Return { program_id, data } => {
+                    if let Ok(data) = BASE64_STANDARD.decode(data) {
+                        return_data.insert(program_id, data);
+                    }
+                }
+                _ => {}
+            }
+
+            // Store raw logs (unfiltered) up to the cap
+            let keep = raw_tx_logs.len() < max_logs;
+            if keep {
+                raw_tx_logs.push(log.clone());
+            } else {
+                truncated = true;
+            }
+
+            if keep && log.contains(PROGRAM_LOG_PREFIX) {
+                let mut log = log.replace(PROGRAM_LOG_PREFIX, "");
+                log = log.trim().to_string();
+
+                if log.is_empty() {
+                    continue;
+                }
+                tx_logs.push((stack.last().copied(), log));
+            }
+        }
+
+        let program_logs = tx_logs.len();
+
+        // Noise goes before the filter, so its warnings count what's left
+        if !self.exclude_patterns.is_empty() {
+            tx_logs.retain(|(_, log)| {
+                !self
+                    .exclude_patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(log))
+            });
+        }
+
+        if let Some(ref log_filter) = self.log_filter {
+            let total = tx_logs.len();
+            match self.filter_regex {
+                Some(ref filter_regex) => tx_logs.retain(|(_, log)| filter_regex.is_match(log)),
+                None => tx_logs
+                    .retain(|(_, log)| log.to_lowercase().contains(&log_filter.to_lowercase())),
+            }
+
+            // A filter matching nothing or everything is usually a mistake
+            if self.warn_on_filter_mismatch && total > 0 {
+                if tx_logs.is_empty() {
+                    eprintln!(
+                        "⚠️  Filter {:?} matched none of the {} logs in {}",
+                        log_filter, total, self.tx_sig
+                    );
+                } else if tx_logs.len() == total {
+                    eprintln!(
+                        "⚠️  Filter {:?} matched all {} logs in {}",
+                        log_filter, total, self.tx_sig
+                    );
+                }
+            }
+        }
+
+        if self.log_order == LogOrder::GroupedByProgram {
+            let mut first_seen: Vec<Option<Pubkey>> = Vec::new();
+            for (program_id, _) in tx_logs.iter() {
+                if !first_seen.contains(program_id) {
+                    first_seen.push(*program_id);
+                }
+            }
+            // Stable, so each program's logs keep their order
+            tx_logs.sort_by_key(|(program_id, _)| {
+                first_seen.iter().position(|seen| seen == program_id)
+            });
+        }
+
+        self.programs_invoked = Some(Self::programs_from_logs(&raw_tx_logs));
+        self.return_data = Some(return_data);
+        self.truncated = truncated;
+        self.tx_logs = Some(tx_logs.into_iter().map(|(_, log)| log).collect());
+        self.raw_logs = Some(raw_tx_logs);
+
+        if self.include_cu_logs {
+            let cu_lines = logs
+                .iter()
+                .filter(|log| log.contains(COMPUTE_UNIT_LOG_DISC))
+                .count();
+            let granted = self.spend_work(cu_lines);
+            self.compute_unit_logs = Some(Self::compute_unit_logs_from(logs, granted)?);
+        }
+
+        // Events are decoded on demand, but their share of the budget is
+        // taken here, where the rest of the parse pays
+        let event_lines = self
+            .raw_logs
+            .iter()
+            .flatten()
+            .filter(|log| log.starts_with("Program data:"))
+            .count();
+        self.events_granted = self.spend_work(event_lines);
+
+        Ok(program_logs)
+    }
+
+    /// `Program data:` events split into discriminator and payload with the
+    /// configured discriminator length, for programs without an IDL
+    pub fn get_raw_events(&self) -> Vec<RawEvent> {
+        // Stop at the first `Program data:` line the work budget didn't cover
+        let logs = self.raw_logs.as_deref().unwrap_or(&[]);
+        let end = logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| log.starts_with("Program data:"))
+            .nth(self.events_granted)
+            .map_or(logs.len(), |(idx, _)| idx);
+
+        raw_events(&logs[..end], self.event_discriminator_len)
+    }
+
+    /// Events from the `Program data:` lines. Events the IDL declares are
+    /// named and decoded to JSON fields; any other event is named by its
+    /// hex discriminator, with the rest of the bytes hex-encoded as a JSON
+    /// string. Lines that aren't valid base64 are skipped.
+    pub fn parse_anchor_events(&self) -> Vec<AnchorEvent> {
+        self.get_raw_events()
+            .into_iter()
+            .map(|event| {
+                let decoded = self.idl.as_ref().and_then(|idl| {
+                    idl.decode_event(&[event.discriminator.as_slice(), &event.payload].concat())
+                });
+                decoded.unwrap_or_else(|| AnchorEvent {
+                    name: hex(&event.discriminator),
+                    data: serde_json::Value::String(hex(&event.payload)),
+                })
+            })
+            .collect()
+    }
+
+    /// Return data from the `Program return: <id> <base64>` lines, keyed by
+    /// the returning program. A program returning more than once keeps its
+    /// last value; payloads that aren't valid base64 are skipped.
+    pub fn get_return_data(&self) -> HashMap<Pubkey, Vec<u8>> {
+        self.return_data.clone().unwrap_or_default()
+    }
+
+    /// Whether the program logs are empty because the transaction didn't
+    /// log, logged nothing from a program, the RPC didn't return logs or the
+    /// filter dropped them all
+    pub fn get_log_source(&self) -> Option<LogSource> {
+        self.log_source
+    }
+
+    pub fn get_tx_logs(&self) -> Vec<String> {
+        self.tx_logs
+            .as_ref()
+            .map_or(Vec::new(), |logs| logs.clone())
+    }
+
+    pub fn get_raw_logs(&self) -> Vec<String> {
+        self.visible_raw_logs().cloned().collect()
+    }
+
+    /// Raw logs passing `raw_filter`
+    fn visible_raw_logs(&self) -> impl Iterator<Item = &String> {
+        self.raw_logs
+            .as_deref()
+            .unwrap_or(&[])
+            .iter()
+            .filter(|log| match self.raw_filter {
+                Some(ref raw_filter) => log.to_lowercase().contains(raw_filter),
+                None => true,
+            })
+    }
+
+    /// Program id -> consumed compute units, kept for callers of the
+    /// original map-based API
+    pub fn get_cu_logs(&self) -> HashMap<Pubkey, u64> {
+        self.compute_unit_logs
+            .as_ref()
+            .map_or(HashMap::new(), |logs| logs.to_consumed_map())
+    }
+
+    pub fn get_compute_unit_logs(&self) -> ComputeUnitLogs {
+        self.compute_unit_logs
+            .as_ref()
+            .map_or(ComputeUnitLogs::new(), |logs| logs.clone())
+    }
+
+    /// Programs ranked by compute units consumed; empty unless
+    /// `include_cu_logs` was set
+    pub fn get_cu_ranking(&self) -> Vec<CuRank> {
+        self.compute_unit_logs
+            .as_ref()
+            .map_or(Vec::new(), |logs| logs.ranking())
+    }
+
+    pub fn get_compute_units_consumed(&self) -> Option<u64> {
+        self.compute_units_consumed
+    }
+
+    /// What each endpoint of the last hedged fetch answered, as
+    /// `(rpc_url, outcome)`: the winner and any that failed before it. Empty
+    /// when the fetch wasn't hedged or was served from the cache, and after
+    /// `fetch_json` or `find_first`, which don't record them.
+    pub fn get_upstream_outcomes(&self) -> &[(String, Result<(), ParseError>)] {
+        &self.upstream_outcomes
+    }
+
+    /// Units consumed by the transaction, gross and net of the programs set
+    /// with `with_cu_excluded_programs`. Needs `include_cu_logs` for the
+    /// per-program figures. Builtins are counted from their `invoke` lines,
+    /// so logs cut short by `with_max_logs` understate them.
+    pub fn get_cu_totals(&self) -> Option<CuTotals> {
+        let gross = self.compute_units_consumed?;
+        let logs = self.compute_unit_logs.as_ref()?;
+        let invocations = Self::invocation_counts(self.raw_logs.as_deref().unwrap_or(&[]));
+        Some(logs.totals(gross, &self.cu_excluded_programs, &invocations))
+    }
+
+    /// Programs invoked by the transaction, deduplicated in first-seen order
+    pub fn get_programs_invoked(&self) -> Vec<Pubkey> {
+        self.programs_invoked
+            .as_ref()
+            .map_or(Vec::new(), |programs| programs.clone())
+    }
+
+    /// Compute budget set by the transaction's Compute Budget instructions.
+    /// Only known when the full transaction was parsed, not just its meta.
+    pub fn get_compute_budget(&self) -> Option<ComputeBudget> {
+        self.compute_budget
+    }
+
+    /// Base, priority and total fee with the compute units they paid for.
+    /// Fetched transactions report the fee they were charged; simulated
+    /// ones an estimate from their signatures and compute budget.
+    pub fn get_cost_breakdown(&self) -> Option<CostBreakdown> {
+        self.cost_breakdown
+    }
+
+    /// Token accounts whose balance the meta reports, with their mint, owner,
+    /// token program (classic SPL or Token-2022), decimals and raw amounts
+    /// before and after. Empty for simulations.
+    pub fn get_token_balance_changes(&self) -> Vec<TokenBalanceChange> {
+        self.token_balance_changes.clone()
+    }
+
+    /// Fee, rent, staking and voting rewards the meta reports for the
+    /// transaction; usually empty. Empty for simulations.
+    pub fn get_rewards(&self) -> Vec<Reward> {
+        self.rewards.clone()
+    }
+
+    /// Instructions invoked by programs (CPIs), in execution order, with
+    /// program ids resolved against the static and loaded account keys
+    pub fn get_inner_instructions(&self) -> Vec<InnerInstruction> {
+        self.inner_instructions.clone()
+    }
+
+    /// Encoding the transaction was fetched in: `JsonParsed` unless the RPC
+    /// couldn't produce it and a plainer one was used
+    pub fn get_encoding(&self) -> Option<UiTransactionEncoding> {
+        self.encoding
     }
 
-    pub async fn parse(&mut self) -> Result<(), String> {
-        let cu_regex = Regex::new(r"Program (\w+) consumed (\d+) of (\d+) compute units")
-            .map_err(|e| format!("Failed to compile regex: {}", e))?;
+    /// Accounts the transaction write- and read-locks. Fetched transactions
+    /// include the addresses v0 messages load from lookup tables; simulated
+    /// ones only their static keys.
+    pub fn get_account_access_summary(&self) -> Option<AccountAccessSummary> {
+        self.account_access.clone()
+    }
 
-        let rpc = rpc_client::RpcClient::new_with_commitment(
-            self.rpc_url.clone(),
-            CommitmentConfig::confirmed(),
-        );
+    /// Parsed view from the enhanced-API provider, if one is configured and
+    /// the lookup succeeded
+    pub fn get_enriched(&self) -> Option<EnrichedTransaction> {
+        self.enriched.clone()
+    }
 
-        let tx_sig = Signature::from_str(&self.tx_sig)
-            .map_err(|_| format!("Invalid transaction signature: {}", self.tx_sig))?;
+    /// Number of top-level instructions in the message and of inner (CPI)
+    /// instructions recorded in the meta
+    pub fn get_instruction_counts(&self) -> (usize, usize) {
+        self.instruction_counts
+    }
 
-        let tx = rpc
-            .get_transaction_with_config(
-                &tx_sig,
-                RpcTransactionConfig {
-                    encoding: Some(UiTransactionEncoding::JsonParsed),
-                    commitment: Some(CommitmentConfig::confirmed()),
-                    max_supported_transaction_version: Some(0),
-                },
-            )
-            .await
-            .map_err(|e| format!("Failed to get transaction: {}", e))?;
+    /// `legacy` or the version number (`0`) of the transaction, once parsed
+    pub fn get_version(&self) -> Option<String> {
+        self.version.clone()
+    }
 
-        let mut tx_logs: Vec<String> = Vec::new();
-        let mut raw_tx_logs: Vec<String> = Vec::new();
-        let mut compute_unit_logs: ComputeUnitLog = ComputeUnitLog::new();
-
-        if let Some(meta) = tx.transaction.meta {
-            if let OptionSerializer::Some(logs) = meta.log_messages {
-                for log in logs {
-                    // Store raw logs (unfiltered)
-                    raw_tx_logs.push(log.clone());
-                    
-                    if log.contains(PROGRAM_LOG_PREFIX) {
-                        let mut log = log.replace(&PROGRAM_LOG_PREFIX, "");
-                        log = log.trim().to_string();
-
-                        if log.is_empty() {
-                            continue;
-                        }
-                        tx_logs.push(log);
-                    }
-                    if self.include_cu_logs {
-                        if log.contains(COMPUTE_UNIT_LOG_DISC) {
-                            if let Some(captures) = cu_regex.captures(&log) {
-                                let program_id = Pubkey::from_str(&captures[1])
-                                    .map_err(|_| format!("Invalid program ID: {}", &captures[1]))?;
-                                let consumed: u64 = captures[2].parse().unwrap();
-                                compute_unit_logs.insert(program_id, consumed);
-                            } else {
-                                println!("No match found!");
-                            }
-                        }
-                    }
-                }
-            }
+    /// Slot the transaction landed in, once fetched
+    pub fn get_slot(&self) -> Option<u64> {
+        self.slot
+    }
 
-            if let OptionSerializer::Some(compute_units) = meta.compute_units_consumed {
-                self.compute_units_consumed = Some(compute_units);
-            }
-        }
+    /// Estimated production time of the transaction's block, as a Unix
+    /// timestamp, when the RPC knows it
+    pub fn get_block_time(&self) -> Option<i64> {
+        self.block_time
+    }
 
-        if let Some(ref log_filter) = self.log_filter {
-            tx_logs.retain(|log| log.to_lowercase().contains(&log_filter.to_lowercase()));
+    /// Whether the transaction landed within `[min_slot, max_slot]`, either
+    /// bound optional. `None` until a transaction was fetched.
+    pub fn in_slot_range(&self, min_slot: Option<u64>, max_slot: Option<u64>) -> Option<bool> {
+        self.slot.map(|slot| {
+            min_slot.is_none_or(|min_slot| slot >= min_slot)
+                && max_slot.is_none_or(|max_slot| slot <= max_slot)
+        })
+    }
+
+    /// Fail with `ParseError::OutOfRange` when the transaction landed outside
+    /// `[min_slot, max_slot]`, e.g. after drifting out of a backfill window
+    pub fn check_slot_range(
+        &self,
+        min_slot: Option<u64>,
+        max_slot: Option<u64>,
+    ) -> Result<(), ParseError> {
+        match (self.slot, self.in_slot_range(min_slot, max_slot)) {
+            (Some(slot), Some(false)) => Err(ParseError::OutOfRange(format!(
+                "Transaction {} landed in slot {}, outside [{}, {}]",
+                self.tx_sig,
+                slot,
+                min_slot.map_or("..".to_string(), |slot| slot.to_string()),
+                max_slot.map_or("..".to_string(), |slot| slot.to_string())
+            ))),
+            _ => Ok(()),
         }
+    }
 
-        self.tx_logs = Some(tx_logs);
-        self.raw_logs = Some(raw_tx_logs);
+    /// Top-level System, SPL Token and Address Lookup Table instructions, decoded into named fields
+    pub fn get_decoded_instructions(&self) -> Vec<DecodedInstruction> {
+        self.decoded_instructions.clone()
+    }
 
-        if self.include_cu_logs {
-            self.compute_unit_logs = Some(compute_unit_logs);
-        }
+    /// Whether the transaction set its compute unit limit explicitly
+    pub fn is_compute_budget_explicit(&self) -> bool {
+        self.compute_budget.is_some_and(|budget| budget.is_explicit())
+    }
 
-        Ok(())
+    /// Whether logs were dropped because they exceeded `max_logs`
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
     }
 
-    pub fn get_tx_logs(&self) -> Vec<String> {
-        self.tx_logs
-            .as_ref()
-            .map_or(Vec::new(), |logs| logs.clone())
+    /// Whether work was skipped because it exceeded the work budget, making
+    /// the results partial
+    pub fn is_limited(&self) -> bool {
+        self.limited
     }
 
-    pub fn get_raw_logs(&self) -> Vec<String> {
+    /// Decoded failure of the transaction, `None` if it succeeded
+    pub fn get_tx_error(&self) -> Option<TxError> {
+        self.tx_error.clone()
+    }
+
+    /// `Ok` if the transaction succeeded, its decoded error if it failed, and
+    /// `None` before parsing or when the RPC returned no meta to tell
+    pub fn get_status(&self) -> Option<Result<(), String>> {
+        self.status.clone()
+    }
+
+    fn record_status(&mut self) {
+        self.status = Some(match self.tx_error {
+            Some(ref err) => Err(err.error.clone()),
+            None => Ok(()),
+        });
+    }
+
+    /// Index of the top-level instruction that failed, if the transaction
+    /// failed with an `InstructionError`
+    pub fn get_error_instruction_index(&self) -> Option<u8> {
+        self.tx_error.as_ref().and_then(|err| err.instruction_index)
+    }
+
+    /// Raw logs bucketed into errors, warnings and info using the
+    /// configured severity keywords
+    pub fn get_logs_by_severity(&self) -> HashMap<Severity, Vec<String>> {
         self.raw_logs
             .as_ref()
-            .map_or(Vec::new(), |logs| logs.clone())
+            .map_or(HashMap::new(), |logs| self.severity_keywords.categorize(logs))
     }
 
-    pub fn get_cu_logs(&self) -> ComputeUnitLog {
-        self.compute_unit_logs
-            .as_ref()
-            .map_or(ComputeUnitLog::new(), |logs| logs.clone())
+    /// Every program invocation in on-chain order with its depth, the
+    /// compute units reported for it and whether it succeeded
+    pub fn get_invocations(&self) -> Vec<invoke::Invocation> {
+        invoke::invocations(self.raw_logs.as_deref().unwrap_or(&[]))
     }
 
-    pub fn get_compute_units_consumed(&self) -> Option<u64> {
-        self.compute_units_consumed
+    /// Graphviz DOT of the transaction's program call graph
+    pub fn to_dot(&self) -> String {
+        invoke::to_dot(self.raw_logs.as_deref().unwrap_or(&[]))
+    }
+
+    /// Raw logs rendered like the Solana Explorer's instruction log view
+    pub fn format_explorer_style(&self) -> String {
+        invoke::format_explorer_style(self.raw_logs.as_deref().unwrap_or(&[]))
     }
 
     pub fn print_tx_logs(&self) {
@@ -158,15 +2099,18 @@ impl TxLogParser {
     pub fn print_cu_logs(&self) {
         if let Some(ref logs) = self.compute_unit_logs {
             println!("Compute Unit Logs:");
-            for (program_id, consumed) in logs.iter() {
-                println!("Program ID: {}, Consumed: {}", program_id, consumed);
+            for (program_id, log) in logs.iter() {
+                println!(
+                    "Program ID: {}, Consumed: {}, Invocations: {}",
+                    program_id, log.consumed, log.invocations
+                );
             }
         } else {
             println!("No compute unit logs found.");
         }
     }
 
-    pub fn print_logs_from_vec(logs: &Vec<String>) {
+    pub fn print_logs_from_vec(logs: &[String]) {
         println!("Transaction Logs:");
         for (idx, log) in logs.iter().enumerate() {
             println!("[{}] {}", idx + 1, log);
@@ -179,7 +2123,7 @@ impl TxLogParser {
         if let OptionSerializer::Some(ref logs) = tx.log_messages {
             for log in logs {
                 if log.contains(PROGRAM_LOG_PREFIX) {
-                    let mut log = log.replace(&PROGRAM_LOG_PREFIX, "");
+                    let mut log = log.replace(PROGRAM_LOG_PREFIX, "");
                     log = log.trim().to_string();
 
                     if log.is_empty() {
@@ -196,32 +2140,75 @@ impl TxLogParser {
 
         tx_logs
     }
+
+    /// Collect the programs from `Program <id> invoke [<depth>]` lines,
+    /// deduplicated and in first-seen order
+    pub fn programs_from_logs(logs: &[String]) -> Vec<Pubkey> {
+        let mut programs: Vec<Pubkey> = Vec::new();
+
+        for log in logs {
+            if !log.contains(PROGRAM_INVOKE_LOG_DISC) {
+                continue;
+            }
+
+            if let LogEvent::Invoke { program_id, .. } = LogEvent::parse(log)
+                && !programs.contains(&program_id)
+            {
+                programs.push(program_id);
+            }
+        }
+
+        programs
+    }
+
+    /// Number of `invoke` lines per program, inner invocations included
+    pub fn invocation_counts(logs: &[String]) -> HashMap<Pubkey, u32> {
+        let mut counts: HashMap<Pubkey, u32> = HashMap::new();
+
+        for log in logs {
+            if !log.contains(PROGRAM_INVOKE_LOG_DISC) {
+                continue;
+            }
+
+            if let LogEvent::Invoke { program_id, .. } = LogEvent::parse(log) {
+                *counts.entry(program_id).or_default() += 1;
+            }
+        }
+
+        counts
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::env;
+    use solana_rpc_client_api::request::RpcResponseErrorData;
 
-    #[tokio::test]
-    async fn test_tx_log_parser() {
-        let rpc_url = env::var("RPC_URL")
-            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
-        let tx_sig = "5mEjzNZjbrFmwyAWUMZemyASaheGj4MFWo2rG8DsD98m2ukKtx8JXkERhJ6GCFPc7s4D2zh36d8XrNBEsquagKkY";
-        let mut parser = TxLogParser::new(rpc_url, tx_sig.to_string(), None, false);
-        let logs = parser.parse().await;
+    // The saved meta of this mainnet transaction, so the tests run offline
+    const TX_SIG: &str = "5mEjzNZjbrFmwyAWUMZemyASaheGj4MFWo2rG8DsD98m2ukKtx8JXkERhJ6GCFPc7s4D2zh36d8XrNBEsquagKkY";
+    const TX_META: &str = include_str!("../fixtures/token_transfer_meta.json");
+
+    #[test]
+    fn test_tx_log_parser() {
+        let mut parser = TxLogParser::new(String::new(), TX_SIG.to_string(), None, false);
+        let logs = parser.parse_from_json(TX_META);
         assert!(logs.is_ok());
 
         parser.print_tx_logs();
+
+        assert_eq!(parser.get_raw_logs().len(), 29);
+        assert_eq!(parser.get_compute_units_consumed(), Some(29933));
+        assert!(parser.parse_from_json("not json").is_err());
     }
 
-    #[tokio::test]
-    async fn test_tx_log_parser_with_filter() {
-        let rpc_url = env::var("RPC_URL")
-            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
-        let tx_sig = "5mEjzNZjbrFmwyAWUMZemyASaheGj4MFWo2rG8DsD98m2ukKtx8JXkERhJ6GCFPc7s4D2zh36d8XrNBEsquagKkY";
-        let mut parser = TxLogParser::new(rpc_url, tx_sig.to_string(), Some("Instruction"), false);
-        let logs = parser.parse().await;
+    #[test]
+    fn test_tx_log_parser_with_filter() {
+        let mut parser =
+            TxLogParser::new(String::new(), TX_SIG.to_string(), Some("Instruction"), false);
+        let logs = parser.parse_from_file(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/token_transfer_meta.json"
+        ));
         assert!(logs.is_ok());
 
         parser.print_tx_logs();
@@ -233,15 +2220,918 @@ mod tests {
         assert!(compute_units_consumed.is_some());
 
         assert!(!logs.is_empty());
+        assert!(logs.iter().all(|log| log.contains("Instruction")));
     }
 
     #[tokio::test]
-    async fn test_tx_log_parser_with_cu_logs() {
-        let rpc_url = env::var("RPC_URL")
-            .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    async fn test_malformed_signature_is_rejected_before_rpc() {
+        // Nothing listens here, so an RPC attempt would fail with Rpc
+        let mut parser = TxLogParser::new(
+            "http://127.0.0.1:1".to_string(),
+            "abcde".to_string(),
+            None,
+            false,
+        );
+
+        let err = parser.parse().await.unwrap_err();
+        assert_eq!(
+            err,
+            ParseError::InvalidInput("Invalid transaction signature: abcde".to_string())
+        );
+        assert!(!err.is_upstream());
+    }
+
+    #[test]
+    fn test_parse_commitment() {
+        assert_eq!(parse_commitment(""), Ok(CommitmentConfig::confirmed()));
+        assert_eq!(
+            parse_commitment("Finalized"),
+            Ok(CommitmentConfig::finalized())
+        );
+        assert_eq!(
+            parse_commitment("processed"),
+            Ok(CommitmentConfig::processed())
+        );
+        assert!(parse_commitment("recent").is_err());
+    }
+
+    #[test]
+    fn test_programs_from_logs() {
+        let logs: Vec<String> = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program log: Create",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program 11111111111111111111111111111111 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let programs = TxLogParser::programs_from_logs(&logs);
+
+        assert_eq!(
+            programs,
+            vec![
+                Pubkey::from_str("ComputeBudget111111111111111111111111111111").unwrap(),
+                Pubkey::from_str("11111111111111111111111111111111").unwrap(),
+                Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_unit_logs_record() {
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let mut logs = ComputeUnitLogs::new();
+
+        logs.record(token, 4645, 180000);
+        logs.record(token, 2000, 170000);
+
+        let log = logs.get(&token).unwrap();
+        assert_eq!(log.invocations, 2);
+        assert_eq!(log.budget, Some(170000));
+        assert_eq!(log.consumed, 6645);
+        assert_eq!(logs.to_consumed_map()[&token], log.consumed);
+    }
+
+    #[test]
+    fn test_malformed_cu_lines_are_skipped() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 200000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program notapubkey consumed 100 of 200000 compute units",
+            "Program 11111111111111111111111111111111 consumed 99999999999999999999999 of 200000 compute units",
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 consumed 150 of 195355 compute units",
+            "Program 11111111111111111111111111111111 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, true);
+        parser.parse_logs(&logs).unwrap();
+
+        let cu_logs = parser.get_compute_unit_logs();
+        assert_eq!(cu_logs.len(), 2);
+        assert_eq!(cu_logs.total_consumed(), 4795);
+    }
+
+    #[test]
+    fn test_return_data() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program return: TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA AQIDBA==",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program return: 11111111111111111111111111111111 not*base64",
+            "Program 11111111111111111111111111111111 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false);
+        parser.parse_logs(&logs).unwrap();
+
+        let return_data = parser.get_return_data();
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        assert_eq!(return_data.len(), 1);
+        assert_eq!(return_data[&token], vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_compute_units_sum_across_invocations() {
+        let logs: Vec<String> = [
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 180000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4736 of 170000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 consumed 30000 of 200000 compute units",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, true);
+        parser.parse_logs(&logs).unwrap();
+
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let cu_logs = parser.get_compute_unit_logs();
+        let log = cu_logs.get(&token).unwrap();
+        assert_eq!(log.consumed, 4645 + 4736);
+        assert_eq!(log.invocations, 2);
+    }
+
+    #[test]
+    fn test_cu_totals_exclude_overhead() {
+        // Builtins log no `consumed` line; the meta's total still counts
+        // 150 units for each Compute Budget and System instruction
+        let logs: Vec<String> = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 199550 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, true);
+        parser.parse_logs(&logs).unwrap();
+        parser.compute_units_consumed = Some(5095);
+
+        assert_eq!(
+            parser.get_cu_totals(),
+            Some(CuTotals {
+                gross: 5095,
+                net: 4645
+            })
+        );
+
+        let parser = parser.with_cu_excluded_programs(&[]);
+        assert_eq!(parser.get_cu_totals().unwrap().net, 5095);
+    }
+
+    #[test]
+    fn test_cu_totals_exclude_votes() {
+        let logs = vec![
+            format!("Program {} invoke [1]", VOTE_PROGRAM_ID),
+            format!("Program {} success", VOTE_PROGRAM_ID),
+        ];
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, true);
+        parser.parse_logs(&logs).unwrap();
+        parser.compute_units_consumed = Some(2100);
+
+        assert_eq!(
+            parser.get_cu_totals(),
+            Some(CuTotals {
+                gross: 2100,
+                net: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_cu_ranking() {
+        let token = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        let memo = Pubkey::from_str("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr").unwrap();
+        let system = Pubkey::from_str("11111111111111111111111111111111").unwrap();
+        let mut logs = ComputeUnitLogs::new();
+
+        logs.record(memo, 250, 200000);
+        logs.record(token, 500, 200000);
+        logs.record(system, 250, 200000);
+
+        let ranking = logs.ranking();
+        assert_eq!(ranking[0].program_id, token);
+        assert_eq!(ranking[0].rank, 1);
+        assert_eq!(ranking[0].pct_of_total, 50.0);
+        // Equal usage falls back to program id order
+        assert_eq!(ranking[1].program_id, system);
+        assert_eq!(ranking[2].program_id, memo);
+        assert_eq!(ranking[2].rank, 3);
+    }
+
+    #[test]
+    fn test_max_logs_truncates_but_keeps_compute_units() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Instruction: Transfer",
+            "Program log: spam",
+            "Program log: spam",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 200000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, true).with_max_logs(2);
+        parser.parse_logs(&logs).unwrap();
+
+        assert!(parser.is_truncated());
+        assert_eq!(parser.get_raw_logs().len(), 2);
+        assert_eq!(parser.get_tx_logs(), vec!["Instruction: Transfer".to_string()]);
+        assert_eq!(parser.get_compute_unit_logs().total_consumed(), 4645);
+    }
+
+    #[test]
+    fn test_parse_anchor_events() {
+        let mut discriminator = crate::anchor::event_discriminator("Swap").to_vec();
+        discriminator.extend_from_slice(&7u64.to_le_bytes());
+        let logs: Vec<String> = vec![
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string(),
+            format!("Program data: {}", BASE64_STANDARD.encode(&discriminator)),
+            "Program data: not base64!".to_string(),
+            format!(
+                "Program data: {}",
+                BASE64_STANDARD.encode([1, 2, 3, 4, 5, 6, 7, 8, 0xab])
+            ),
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success".to_string(),
+        ];
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false);
+        parser.parse_logs(&logs).unwrap();
+
+        let events = parser.parse_anchor_events();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1].name, "0102030405060708");
+        assert_eq!(events[1].data, serde_json::json!("ab"));
+
+        let idl = Idl::from_json(
+            r#"{"events": [{"name": "Swap", "fields": [{"name": "amount", "type": "u64", "index": false}]}]}"#,
+        )
+        .unwrap();
+        let events = parser.with_idl(idl).parse_anchor_events();
+        assert_eq!(events[0].name, "Swap");
+        assert_eq!(events[0].data, serde_json::json!({"amount": 7}));
+        assert_eq!(events[1].name, "0102030405060708");
+    }
+
+    #[test]
+    fn test_work_budget_limits_parsing() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 4645 of 200000 compute units",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 consumed 150 of 195355 compute units",
+            "Program 11111111111111111111111111111111 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        // Within budget: 7 log lines and 2 CU lines
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, true).with_work_budget(9);
+        parser.parse_logs(&logs).unwrap();
+        assert!(!parser.is_limited());
+        assert_eq!(parser.get_compute_unit_logs().total_consumed(), 4795);
+
+        // The log lines leave room for one of the two CU lines
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, true).with_work_budget(8);
+        parser.parse_logs(&logs).unwrap();
+        assert!(parser.is_limited());
+        assert_eq!(parser.get_raw_logs().len(), 7);
+        assert_eq!(parser.get_compute_unit_logs().total_consumed(), 4645);
+
+        // Too small for all the log lines, let alone the CU lines
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, true).with_work_budget(3);
+        parser.parse_logs(&logs).unwrap();
+        assert!(parser.is_limited());
+        assert_eq!(parser.get_raw_logs().len(), 3);
+        assert_eq!(parser.get_compute_unit_logs().total_consumed(), 0);
+    }
+
+    #[test]
+    fn test_work_budget_limits_events() {
+        let logs: Vec<String> = vec![
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P invoke [1]".to_string(),
+            format!("Program data: {}", BASE64_STANDARD.encode([1, 2, 3, 4, 5, 6, 7, 8, 0xab])),
+            format!("Program data: {}", BASE64_STANDARD.encode([8, 7, 6, 5, 4, 3, 2, 1, 0xcd])),
+            "Program 6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P success".to_string(),
+        ];
+
+        // 4 log lines and both events
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, false).with_work_budget(6);
+        parser.parse_logs(&logs).unwrap();
+        assert!(!parser.is_limited());
+        assert_eq!(parser.parse_anchor_events().len(), 2);
+
+        // Room for the log lines and one event
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, false).with_work_budget(5);
+        parser.parse_logs(&logs).unwrap();
+        assert!(parser.is_limited());
+        assert_eq!(parser.get_raw_logs().len(), 4);
+        let events = parser.parse_anchor_events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "0102030405060708");
+    }
+
+    #[test]
+    fn test_exclude_patterns_drop_noise() {
+        let logs: Vec<String> = [
+            "Program log: Instruction: Transfer",
+            "Program log: Instruction: TransferChecked",
+            "Program log: swap 5 SOL for 870 USDC",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let patterns = compile_exclude_patterns(&["^Instruction: Transfer$".to_string()]).unwrap();
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false)
+            .with_exclude_patterns(&patterns);
+        parser.parse_logs(&logs).unwrap();
+
+        assert_eq!(
+            parser.get_tx_logs(),
+            vec![
+                "Instruction: TransferChecked".to_string(),
+                "swap 5 SOL for 870 USDC".to_string()
+            ]
+        );
+        assert_eq!(parser.get_raw_logs().len(), 3);
+        assert!(compile_exclude_patterns(&["(".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_regex_filter_mode() {
+        let logs: Vec<String> = [
+            "Program log: Instruction: Transfer",
+            "Program log: Instruction: TransferChecked",
+            "Program log: swap 5 SOL for 870 USDC",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(
+            String::new(),
+            String::new(),
+            Some(r"^Instruction: \w+$"),
+            false,
+        )
+        .with_filter_mode(FilterMode::Regex)
+        .unwrap();
+        parser.parse_logs(&logs).unwrap();
+        assert_eq!(
+            parser.get_tx_logs(),
+            vec![
+                "Instruction: Transfer".to_string(),
+                "Instruction: TransferChecked".to_string()
+            ]
+        );
+
+        // The default substring mode takes the pattern literally
+        let mut parser = TxLogParser::new(
+            String::new(),
+            String::new(),
+            Some(r"^Instruction: \w+$"),
+            false,
+        );
+        parser.parse_logs(&logs).unwrap();
+        assert!(parser.get_tx_logs().is_empty());
+
+        assert!(
+            TxLogParser::new(String::new(), String::new(), Some("("), false)
+                .with_filter_mode(FilterMode::Regex)
+                .is_err()
+        );
+        assert_eq!("".parse::<FilterMode>(), Ok(FilterMode::Substring));
+        assert!("glob".parse::<FilterMode>().is_err());
+    }
+
+    #[test]
+    fn test_raw_filter() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, false).with_raw_filter("LOG:");
+        parser.parse_logs(&logs).unwrap();
+
+        assert_eq!(
+            parser.get_raw_logs(),
+            vec!["Program log: Instruction: Transfer".to_string()]
+        );
+        // Derived data still sees every line
+        assert_eq!(parser.get_programs_invoked().len(), 1);
+
+        let mut parser =
+            TxLogParser::new(String::new(), String::new(), None, false).with_raw_filter("");
+        parser.parse_logs(&logs).unwrap();
+        assert_eq!(parser.get_raw_logs().len(), 3);
+    }
+
+    #[test]
+    fn test_logs_grouped_by_program() {
+        let logs: Vec<String> = [
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 invoke [1]",
+            "Program log: Instruction: Route",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program log: swap done",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program log: Instruction: Transfer",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false)
+            .with_log_order("grouped".parse().unwrap());
+        parser.parse_logs(&logs).unwrap();
+
+        assert_eq!(
+            parser.get_tx_logs(),
+            vec![
+                "Instruction: Route".to_string(),
+                "swap done".to_string(),
+                "Instruction: Transfer".to_string(),
+                "Instruction: Transfer".to_string()
+            ]
+        );
+        assert!("sideways".parse::<LogOrder>().is_err());
+    }
+
+    #[test]
+    fn test_status_from_meta() {
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false);
+        assert_eq!(parser.get_status(), None);
+
+        let succeeded: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": []
+        }))
+        .unwrap();
+        parser.parse_meta(&succeeded).unwrap();
+        assert_eq!(parser.get_status(), Some(Ok(())));
+
+        let failed: UiTransactionStatusMeta = serde_json::from_value(serde_json::json!({
+            "err": {"InstructionError": [0, {"Custom": 1}]},
+            "status": {"Err": {"InstructionError": [0, {"Custom": 1}]}},
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": []
+        }))
+        .unwrap();
+        parser.parse_meta(&failed).unwrap();
+        assert_eq!(parser.get_status(), Some(Err("Custom(1)".to_string())));
+    }
+
+    #[test]
+    fn test_from_json_rpc_response() {
+        let meta: serde_json::Value =
+            serde_json::from_str(include_str!("../fixtures/token_transfer_meta.json")).unwrap();
         let tx_sig = "5mEjzNZjbrFmwyAWUMZemyASaheGj4MFWo2rG8DsD98m2ukKtx8JXkERhJ6GCFPc7s4D2zh36d8XrNBEsquagKkY";
-        let mut parser = TxLogParser::new(rpc_url, tx_sig.to_string(), None, true);
-        let logs = parser.parse().await;
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "slot": 1,
+                "blockTime": null,
+                "transaction": {
+                    "signatures": [tx_sig],
+                    "message": {
+                        "header": {
+                            "numRequiredSignatures": 1,
+                            "numReadonlySignedAccounts": 0,
+                            "numReadonlyUnsignedAccounts": 0
+                        },
+                        "accountKeys": ["11111111111111111111111111111111"],
+                        "recentBlockhash": "11111111111111111111111111111111",
+                        "instructions": []
+                    }
+                },
+                "meta": meta
+            }
+        });
+
+        let parser = TxLogParser::from_json(response, Some("Transfer"), true).unwrap();
+
+        assert_eq!(parser.tx_sig, tx_sig);
+        assert_eq!(parser.get_tx_logs(), vec!["Instruction: Transfer".to_string()]);
+        assert_eq!(parser.get_compute_units_consumed(), Some(29933));
+        assert_eq!(parser.get_compute_unit_logs().len(), 2);
+    }
+
+    #[test]
+    fn test_instruction_counts() {
+        let mut meta: serde_json::Value =
+            serde_json::from_str(include_str!("../fixtures/token_transfer_meta.json")).unwrap();
+        let compiled = serde_json::json!({"programIdIndex": 0, "accounts": [], "data": ""});
+        meta["innerInstructions"] = serde_json::json!([
+            {"index": 0, "instructions": [compiled.clone(), compiled.clone()]},
+            {"index": 1, "instructions": [compiled.clone()]}
+        ]);
+        let tx = serde_json::json!({
+            "slot": 1,
+            "blockTime": 1700000000,
+            "transaction": {
+                "signatures": [],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 0
+                    },
+                    "accountKeys": ["11111111111111111111111111111111"],
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": [compiled.clone(), compiled]
+                }
+            },
+            "meta": meta
+        });
+
+        let parser = TxLogParser::from_json(tx, None, false).unwrap();
+
+        assert_eq!(parser.get_instruction_counts(), (2, 3));
+        assert_eq!(parser.get_version().as_deref(), Some("legacy"));
+        assert_eq!(parser.get_slot(), Some(1));
+        assert_eq!(parser.get_block_time(), Some(1700000000));
+    }
+
+    /// A `getTransaction` result carrying `TX_META` at `version`
+    fn versioned_tx_json(version: u8) -> serde_json::Value {
+        serde_json::json!({
+            "slot": 1,
+            "blockTime": null,
+            "version": version,
+            "transaction": {
+                "signatures": [TX_SIG],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 0
+                    },
+                    "accountKeys": ["11111111111111111111111111111111"],
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": [],
+                    "addressTableLookups": []
+                }
+            },
+            "meta": serde_json::from_str::<serde_json::Value>(TX_META).unwrap()
+        })
+    }
+
+    /// Parse a cached transaction of `version`, so no RPC is involved,
+    /// fetching up to `max_supported_version`
+    async fn parse_versioned(version: u8, max_supported_version: u8) -> Result<(), ParseError> {
+        let tx: EncodedConfirmedTransactionWithStatusMeta =
+            serde_json::from_value(versioned_tx_json(version)).unwrap();
+
+        let cache = Arc::new(TxCache::new(Default::default(), 1));
+        cache.insert("", TX_SIG, CommitmentConfig::finalized().commitment, Arc::new(tx));
+
+        let mut parser = TxLogParser::new(String::new(), TX_SIG.to_string(), None, false)
+            .with_cache(cache)
+            .with_max_supported_version(max_supported_version);
+        parser.parse().await
+    }
+
+    #[tokio::test]
+    async fn test_max_supported_version() {
+        assert!(parse_versioned(0, DEFAULT_MAX_SUPPORTED_VERSION).await.is_ok());
+
+        // A version past the limit is refused, not parsed or sent upstream
+        assert!(matches!(
+            parse_versioned(1, DEFAULT_MAX_SUPPORTED_VERSION).await,
+            Err(ParseError::UnsupportedVersion(_))
+        ));
+        assert!(parse_versioned(1, 1).await.is_ok());
+    }
+
+    /// A JSON-RPC endpoint answering every request with `result` after
+    /// `delay`, one connection at a time. Returns its URL.
+    async fn serve_rpc_result(result: serde_json::Value, delay: Duration) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let body = serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": result}).to_string();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                // Read the headers and as much body as they announce
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                loop {
+                    let n = stream.read(&mut buf).await.unwrap_or(0);
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text[..end]
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                }
+
+                tokio::time::sleep(delay).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+                let _ = stream.shutdown().await;
+            }
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn test_hedged_fetch_records_each_endpoint() {
+        // The working endpoint answers late, so the failure is in first
+        let working = serve_rpc_result(versioned_tx_json(0), Duration::from_millis(200)).await;
+        // Nothing listens on the port of a dropped listener
+        let failing = format!(
+            "http://{}",
+            std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap()
+        );
+
+        let mut parser = TxLogParser::new(failing.clone(), TX_SIG.to_string(), None, false)
+            .with_hedge_rpc_urls(std::slice::from_ref(&working));
+        parser.parse().await.unwrap();
+        assert!(!parser.get_tx_logs().is_empty());
+
+        let outcomes = parser.get_upstream_outcomes();
+        assert_eq!(outcomes.len(), 2);
+        assert!(matches!(&outcomes[0], (url, Err(_)) if *url == failing));
+        assert_eq!(outcomes[1], (working, Ok(())));
+    }
+
+    #[tokio::test]
+    async fn test_related_walk_is_capped() {
+        // Every page comes back full, as on an account too busy to walk back
+        // to the start
+        let entry = serde_json::json!({
+            "signature": TX_SIG,
+            "slot": 1,
+            "err": null,
+            "memo": null,
+            "blockTime": null,
+            "confirmationStatus": "finalized"
+        });
+        let rpc_url = serve_rpc_result(
+            serde_json::Value::Array(vec![entry; SIGNATURES_PAGE_SIZE]),
+            Duration::ZERO,
+        )
+        .await;
+
+        let parser = TxLogParser::new(rpc_url, TX_SIG.to_string(), None, false);
+        let related = parser
+            .fetch_related_signatures("11111111111111111111111111111111", None, Some(TX_SIG), 10)
+            .await;
+        assert!(
+            matches!(related, Err(ParseError::OutOfRange(ref msg)) if msg.ends_with(&format!("before = {}", TX_SIG)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unhedged_fetch_records_nothing() {
+        let working = serve_rpc_result(versioned_tx_json(0), Duration::ZERO).await;
+
+        let mut parser = TxLogParser::new(working, TX_SIG.to_string(), None, false);
+        parser.parse().await.unwrap();
+        assert!(parser.get_upstream_outcomes().is_empty());
+    }
+
+    #[test]
+    fn test_fetch_error_mapping() {
+        let parser = TxLogParser::new(String::new(), "sig".to_string(), None, false);
+        let rpc_error = |code: i64, message: &str| {
+            ClientError::from(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code,
+                message: message.to_string(),
+                data: RpcResponseErrorData::Empty,
+            }))
+        };
+
+        assert!(matches!(
+            parser.fetch_error(rpc_error(-32005, "Node is behind by 42 slots")),
+            ParseError::NodeBehind(_)
+        ));
+        assert!(matches!(
+            parser.fetch_error(rpc_error(
+                -32016,
+                "Minimum context slot has not been reached"
+            )),
+            ParseError::NodeBehind(_)
+        ));
+        assert!(matches!(
+            parser.fetch_error(rpc_error(
+                -32015,
+                "Transaction version (1) is not supported"
+            )),
+            ParseError::UnsupportedVersion(_)
+        ));
+        assert!(matches!(
+            parser.fetch_error(rpc_error(-32600, "Invalid request")),
+            ParseError::Rpc(_)
+        ));
+        assert!(ParseError::NodeBehind(String::new()).is_upstream());
+    }
+
+    #[test]
+    fn test_log_source() {
+        let logs = vec!["Program log: hello".to_string()];
+
+        assert_eq!(LogSource::of(None, 0, 0), LogSource::Unavailable);
+        assert_eq!(LogSource::of(Some(&[]), 0, 0), LogSource::Empty);
+        assert_eq!(LogSource::of(Some(&logs), 1, 0), LogSource::Filtered);
+        assert_eq!(LogSource::of(Some(&logs), 1, 1), LogSource::Logs);
+
+        // A transfer logs only invoke and success lines; that's not filtering
+        let transfer: Vec<String> = [
+            "Program 11111111111111111111111111111111 invoke [1]",
+            "Program 11111111111111111111111111111111 success",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(LogSource::of(Some(&transfer), 0, 0), LogSource::NoProgramLogs);
+
+        let mut parser = TxLogParser::new(String::new(), String::new(), None, false);
+        assert_eq!(parser.parse_logs(&transfer).unwrap(), 0);
+        assert_eq!(parser.parse_logs(&logs).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_find_first_log() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Instruction: Transfer",
+            "Program log: Transfer done",
+        ]
+        .iter()
+        .map(|log| log.to_string())
+        .collect();
+
+        assert_eq!(find_first_log(&logs, "transfer"), Some((1, &logs[1])));
+        assert_eq!(find_first_log(&logs, "burn"), None);
+    }
+
+    #[test]
+    fn test_slot_range() {
+        let mut parser = TxLogParser::new(String::new(), "sig".to_string(), None, false);
+        assert_eq!(parser.in_slot_range(Some(1), None), None);
+
+        parser.slot = Some(100);
+        assert_eq!(parser.in_slot_range(Some(100), Some(100)), Some(true));
+        assert_eq!(parser.in_slot_range(None, Some(99)), Some(false));
+        assert!(parser.check_slot_range(Some(50), None).is_ok());
+        assert_eq!(
+            parser.check_slot_range(Some(101), Some(200)),
+            Err(ParseError::OutOfRange(
+                "Transaction sig landed in slot 100, outside [101, 200]".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_page_cursor() {
+        let page = vec![("newer".to_string(), 2), ("older".to_string(), 1)];
+
+        // A full page continues from its oldest signature
+        assert_eq!(page_cursor(&page, 2), Some("older".to_string()));
+        // A short page is the end of the history
+        assert_eq!(page_cursor(&page, 3), None);
+        assert_eq!(page_cursor(&[], 1), None);
+    }
+
+    #[test]
+    fn test_inner_instruction_program_from_lookup_table() {
+        let payer = Pubkey::new_unique().to_string();
+        let program = Pubkey::new_unique().to_string();
+        let loaded_writable = Pubkey::new_unique().to_string();
+        let loaded_program = Pubkey::new_unique().to_string();
+
+        let mut meta: serde_json::Value =
+            serde_json::from_str(include_str!("../fixtures/token_transfer_meta.json")).unwrap();
+        meta["loadedAddresses"] = serde_json::json!({
+            "writable": [loaded_writable],
+            "readonly": [loaded_program]
+        });
+        // Index 3 is past the two static keys and the loaded writable one
+        meta["innerInstructions"] = serde_json::json!([{
+            "index": 0,
+            "instructions": [
+                {"programIdIndex": 3, "accounts": [0, 2], "data": "", "stackHeight": 2}
+            ]
+        }]);
+        let tx = serde_json::json!({
+            "slot": 1,
+            "blockTime": null,
+            "version": 0,
+            "transaction": {
+                "signatures": [],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1
+                    },
+                    "accountKeys": [payer, program],
+                    "recentBlockhash": "11111111111111111111111111111111",
+                    "instructions": [{"programIdIndex": 1, "accounts": [0], "data": ""}],
+                    "addressTableLookups": [{
+                        "accountKey": Pubkey::new_unique().to_string(),
+                        "writableIndexes": [0],
+                        "readonlyIndexes": [1]
+                    }]
+                }
+            },
+            "meta": meta
+        });
+
+        let parser = TxLogParser::from_json(tx, None, false).unwrap();
+
+        let inner = parser.get_inner_instructions();
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].instruction_index, 0);
+        assert_eq!(
+            inner[0].program_id.as_deref(),
+            Some(loaded_program.as_str())
+        );
+        assert_eq!(inner[0].accounts, vec![payer, loaded_writable]);
+        assert_eq!(inner[0].stack_height, Some(2));
+        assert_eq!(parser.get_instruction_counts(), (1, 1));
+    }
+
+    #[test]
+    fn test_tx_log_parser_with_cu_logs() {
+        let mut parser = TxLogParser::new(String::new(), TX_SIG.to_string(), None, true);
+        let logs = parser.parse_from_json(TX_META);
         assert!(logs.is_ok());
 
         parser.print_cu_logs();
@@ -249,5 +3139,7 @@ mod tests {
         let logs = parser.get_cu_logs();
 
         assert!(!logs.is_empty());
+        let token_program = Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap();
+        assert_eq!(logs.get(&token_program), Some(&11807));
     }
 }
@@ -0,0 +1,3 @@
+//! Types and client/server stubs generated from `proto/odin.proto`
+
+tonic::include_proto!("odin");
@@ -0,0 +1,89 @@
+use solana_transaction_status_client_types::{
+    UiTransactionStatusMeta, option_serializer::OptionSerializer,
+};
+
+/// A reward credited or debited by the transaction, from the meta's
+/// `rewards` (fee, rent, staking and voting rewards)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reward {
+    pub pubkey: String,
+    /// Negative when lamports were taken, e.g. rent
+    pub lamports: i64,
+    /// Account balance after the reward was applied
+    pub post_balance: u64,
+    /// `fee`, `rent`, `staking` or `voting`, when the RPC reports it
+    pub reward_type: Option<String>,
+    /// Vote account commission, for staking and voting rewards
+    pub commission: Option<u8>,
+}
+
+/// Rewards listed in a transaction's meta; empty when there are none
+pub fn rewards(meta: &UiTransactionStatusMeta) -> Vec<Reward> {
+    let OptionSerializer::Some(ref rewards) = meta.rewards else {
+        return Vec::new();
+    };
+
+    rewards
+        .iter()
+        .map(|reward| Reward {
+            pubkey: reward.pubkey.clone(),
+            lamports: reward.lamports,
+            post_balance: reward.post_balance,
+            reward_type: reward.reward_type.map(|ty| ty.to_string()),
+            commission: reward.commission,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_rewards() {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "rewards": [
+                {
+                    "pubkey": "validator",
+                    "lamports": 2500,
+                    "postBalance": 1002500,
+                    "rewardType": "Fee",
+                    "commission": null
+                },
+                {
+                    "pubkey": "stake",
+                    "lamports": -10,
+                    "postBalance": 90,
+                    "rewardType": "Rent",
+                    "commission": 5
+                }
+            ]
+        }))
+        .unwrap();
+
+        let credited = rewards(&meta);
+
+        assert_eq!(credited.len(), 2);
+        assert_eq!(credited[0].pubkey, "validator");
+        assert_eq!(credited[0].lamports, 2500);
+        assert_eq!(credited[0].reward_type.as_deref(), Some("fee"));
+        assert_eq!(credited[1].lamports, -10);
+        assert_eq!(credited[1].commission, Some(5));
+
+        let no_rewards: UiTransactionStatusMeta = serde_json::from_value(json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": []
+        }))
+        .unwrap();
+        assert!(rewards(&no_rewards).is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+
+use crate::error::ParseError;
+use crate::parser::new_rpc_client;
+
+/// RPC clients shared by URL, so parsers reuse one HTTP connection pool per
+/// upstream instead of opening a new one for every transaction. Hand them
+/// to `TxLogParser::with_client`.
+pub struct RpcClientPool {
+    user_agent: String,
+    capacity: usize,
+    clients: Mutex<HashMap<String, Arc<RpcClient>>>,
+}
+
+impl RpcClientPool {
+    /// Clients send `user_agent`. At most `capacity` URLs are kept; past
+    /// that, clients for new URLs are built for the caller alone.
+    pub fn new(user_agent: &str, capacity: usize) -> Self {
+        RpcClientPool {
+            user_agent: user_agent.to_string(),
+            capacity,
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The shared client for `rpc_url`, built on first use
+    pub fn get(&self, rpc_url: &str) -> Result<Arc<RpcClient>, ParseError> {
+        let mut clients = self.clients.lock().unwrap();
+        if let Some(client) = clients.get(rpc_url) {
+            return Ok(client.clone());
+        }
+
+        let client = Arc::new(new_rpc_client(rpc_url, &self.user_agent, &[])?);
+        if clients.len() < self.capacity {
+            clients.insert(rpc_url.to_string(), client.clone());
+        }
+        Ok(client)
+    }
+
+    /// Number of URLs with a shared client
+    pub fn len(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+// `RpcClient` isn't `Debug`, so show the URLs
+impl fmt::Debug for RpcClientPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let clients = self.clients.lock().unwrap();
+        f.debug_struct("RpcClientPool")
+            .field("user_agent", &self.user_agent)
+            .field("capacity", &self.capacity)
+            .field("urls", &clients.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// A client injected into a `TxLogParser`, `Debug` by its URL
+#[derive(Clone)]
+pub struct SharedRpcClient(pub Arc<RpcClient>);
+
+impl fmt::Debug for SharedRpcClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SharedRpcClient")
+            .field(&self.0.url())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clients_are_shared_by_url() {
+        let pool = RpcClientPool::new("odin-test", 1);
+
+        let first = pool.get("http://localhost:8899").unwrap();
+        let second = pool.get("http://localhost:8899").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(first.url(), "http://localhost:8899");
+
+        // Over capacity: still served, but not kept
+        let other = pool.get("http://localhost:8900").unwrap();
+        assert!(!Arc::ptr_eq(
+            &other,
+            &pool.get("http://localhost:8900").unwrap()
+        ));
+        assert_eq!(pool.len(), 1);
+    }
+}
@@ -1,17 +1,49 @@
-use tonic::{Request, Response, Status, transport::Server};
+use clap::Parser;
+use regex::Regex;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use tonic::{Request, Response, Status, server::NamedService, transport::Server};
+use tonic_health::{ServingStatus, server::HealthReporter};
+use tokio::sync::Semaphore;
 use tokio_stream::wrappers::ReceiverStream;
-
-// Include the generated protobuf code from proto/odin.proto
-pub mod proto {
-    tonic::include_proto!("odin");
-}
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
 
 // Import the generated types and server trait
+use odin::proto;
 use proto::solana_tx_log_server::{SolanaTxLog, SolanaTxLogServer};
-use proto::{GetTxRequest, GetTxResponse, StreamProgramRequest, ComputeUnitLog};
+use proto::{GetTxRequest, GetTxResponse, StreamProgramRequest, ComputeUnitLog, CuRank, LogsBySeverity, StatusUpdate};
+use proto::{GetRelatedRequest, GetRelatedResponse, RelatedTransaction, SimulateRequest};
+use proto::{ContainsLogRequest, ContainsLogResponse};
+use proto::{BatchProgress, BatchStreamMessage, BatchTxResult, GetTxBatchRequest, GetTxBatchResponse, batch_stream_message};
 
 // Import the parser module from the odin crate
-use odin::parser::TxLogParser;
+use odin::accounts::AccountAccessSummary;
+use odin::aimd::AdaptiveConcurrency;
+use odin::audit::{AuditField, AuditLog, AuditRequest, AuditResponse};
+use odin::backoff::Backoff;
+use odin::breaker::CircuitBreakers;
+use odin::cache::{CommitmentTtl, TxCache};
+use odin::compute_budget::CostBreakdown;
+use odin::decode::DecodedInstruction;
+use odin::error::ParseError;
+use odin::enriched::{EnrichedProvider, EnrichedTransaction, ProviderKind};
+use odin::frame::{Frame, FrameComputeUnits};
+use odin::grpc_client::{anchor_event_to_proto, return_data_to_proto};
+use odin::labels::ProgramLabels;
+use odin::parser::{ComputeUnitLogs, FilterMode, LogOrder, LogSource, TxLogParser, compile_exclude_patterns, encoding_label, parse_commitment};
+use odin::severity::Severity;
+use odin::status::parse_error_status;
+use odin::subscribe::{SubscribeOptions, subscribe_logs, ws_url_from_rpc};
+use odin::rewards::Reward;
+use odin::rpc_pool::RpcClientPool;
+use odin::token_balance::TokenBalanceChange;
+use odin::tx_error::TxError;
 
 // Default RPC URL for Solana Mainnet Beta
 const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
@@ -19,32 +51,524 @@ const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
 // Default server address
 const DEFAULT_SERVER_ADDR: &str = "[::1]:50051";
 
+// Transactions returned by GetRelatedTransactions when no limit is given, and the cap
+const DEFAULT_RELATED_LIMIT: usize = 25;
+const MAX_RELATED_LIMIT: usize = 100;
+
+// Signatures accepted by one GetTxLogsBatch request
+const MAX_BATCH_SIZE: usize = 100;
+
+// Least time between progress messages of a StreamTxLogsBatch call
+const BATCH_PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+// How long to wait before re-checking a streamed signature's status.
+// Finalization trails confirmation by roughly 32 slots (~13s).
+const FINALIZATION_CHECK_DELAY: Duration = Duration::from_secs(30);
+
+// Caps on the per-stream fetch retry overrides, so one subscription can't
+// hold an RPC permit looping on a signature that will never show up
+const MAX_STREAM_FETCH_RETRIES: u32 = 20;
+const MAX_STREAM_FETCH_RETRY_MS: u64 = 5_000;
+
+// Upstream URLs whose RPC clients are kept for reuse; requests naming
+// further URLs get a client of their own
+const RPC_CLIENT_POOL_CAPACITY: usize = 64;
+
+/// Odin gRPC Server - Serve Solana transaction logs
+#[derive(Parser, Debug)]
+#[command(name = "odin-server")]
+#[command(about = "gRPC server for fetching and streaming Solana transaction logs", long_about = None)]
+pub struct ServerConfig {
+    /// RPC used by requests that don't set their own `rpc_url`, and checked
+    /// by the startup preflight and health probes
+    #[arg(long, env = "ODIN_RPC_URL", default_value = DEFAULT_RPC_URL)]
+    rpc_url: String,
+
+    /// Skip the startup RPC preflight against the default RPC URL
+    #[arg(long = "no-preflight", env = "ODIN_NO_PREFLIGHT", default_value = "false")]
+    no_preflight: bool,
+
+    /// Don't warn when a log filter matches none or all of a transaction's logs
+    #[arg(long, env = "ODIN_QUIET_FILTER_WARNINGS", default_value = "false")]
+    quiet_filter_warnings: bool,
+
+    /// User-Agent sent with RPC requests
+    #[arg(long, env = "ODIN_USER_AGENT", default_value = odin::parser::DEFAULT_USER_AGENT)]
+    user_agent: String,
+
+    /// Maximum log lines kept per transaction; the rest are dropped and the
+    /// response is marked truncated
+    #[arg(long, env = "ODIN_MAX_LOGS", default_value = "10000")]
+    max_logs: usize,
+
+    /// Work units (log lines, CU lines, events, decoded instructions) spent per
+    /// transaction before the response is cut short and marked limited;
+    /// 0 disables the budget
+    #[arg(long, env = "ODIN_WORK_BUDGET", default_value = "0")]
+    work_budget: usize,
+
+    /// JSON file of extra program labels (`{"<program id>": "<label>"}`)
+    /// merged over the built-in well-known programs
+    #[arg(long, env = "ODIN_LABELS_FILE")]
+    labels_file: Option<String>,
+
+    /// Also listen on this Unix domain socket path, alongside TCP
+    #[arg(long, env = "ODIN_UDS")]
+    uds: Option<String>,
+
+    /// Serve compact binary stream frames on this TCP address (e.g. `[::1]:50052`)
+    #[arg(long, env = "ODIN_FRAME_ADDR")]
+    frame_addr: Option<String>,
+
+    /// Log every request and a summary of its response for audit trails
+    #[arg(long, env = "ODIN_AUDIT_LOG", default_value = "false")]
+    audit_log: bool,
+
+    /// Comma-separated fields replaced with `[redacted]` in audit lines
+    /// (signature, program, client, rpc_url)
+    #[arg(long, env = "ODIN_AUDIT_REDACT", value_delimiter = ',')]
+    audit_redact: Vec<AuditField>,
+
+    /// RPC provider type. `helius` also queries the enhanced transactions
+    /// API and returns its parsed instructions, transfers and events.
+    #[arg(long, env = "ODIN_PROVIDER", default_value = "standard")]
+    provider: ProviderKind,
+
+    /// API key for the enhanced-API provider
+    #[arg(long, env = "ODIN_ENHANCED_API_KEY")]
+    enhanced_api_key: Option<String>,
+
+    /// Enhanced transactions endpoint
+    #[arg(long, env = "ODIN_ENHANCED_API_URL", default_value = odin::enriched::HELIUS_API_URL)]
+    enhanced_api_url: String,
+
+    /// Consecutive upstream failures after which an RPC URL's circuit opens
+    /// and requests to it are rejected with UNAVAILABLE (0 disables)
+    #[arg(long, env = "ODIN_BREAKER_THRESHOLD", default_value = "5")]
+    breaker_threshold: u32,
+
+    /// Seconds an open circuit rejects requests before probing the RPC again
+    #[arg(long, env = "ODIN_BREAKER_COOLDOWN", default_value = "30")]
+    breaker_cooldown: u64,
+
+    /// Lowest number of signatures GetTxLogsBatch keeps in flight per RPC
+    #[arg(long, env = "ODIN_BATCH_MIN_CONCURRENCY", default_value = "2")]
+    batch_min_concurrency: usize,
+
+    /// Highest number of signatures GetTxLogsBatch keeps in flight per RPC
+    #[arg(long, env = "ODIN_BATCH_MAX_CONCURRENCY", default_value = "32")]
+    batch_max_concurrency: usize,
+
+    /// Batch fetches slower than this many milliseconds stop the concurrency
+    /// from growing
+    #[arg(long, env = "ODIN_BATCH_LATENCY_TARGET_MS", default_value = "1000")]
+    batch_latency_target_ms: u64,
+
+    /// Serve Prometheus metrics over HTTP on this address (e.g. `127.0.0.1:9090`)
+    #[arg(long, env = "ODIN_METRICS_ADDR")]
+    metrics_addr: Option<String>,
+
+    /// Delay in milliseconds before the first WebSocket reconnect
+    #[arg(long, env = "ODIN_RECONNECT_BASE_MS", default_value = "500")]
+    reconnect_base_ms: u64,
+
+    /// Factor the reconnect delay grows by after each failed attempt
+    #[arg(long, env = "ODIN_RECONNECT_MULTIPLIER", default_value = "2.0")]
+    reconnect_multiplier: f64,
+
+    /// Cap in milliseconds on the reconnect delay, jitter included
+    #[arg(long, env = "ODIN_RECONNECT_MAX_MS", default_value = "30000")]
+    reconnect_max_ms: u64,
+
+    /// Fraction (0.0-1.0) of each reconnect delay randomly added or removed,
+    /// so streams dropped together don't reconnect together
+    #[arg(long, env = "ODIN_RECONNECT_JITTER", default_value = "0.2")]
+    reconnect_jitter: f64,
+
+    /// Reconnect attempts without a confirmed subscription before the stream
+    /// is ended with UNAVAILABLE
+    #[arg(long, env = "ODIN_RECONNECT_MAX_ATTEMPTS", default_value = "10")]
+    reconnect_max_attempts: u32,
+
+    /// Seconds a stream may go without a single notification before it is
+    /// resubscribed, for nodes that stop delivering without closing the
+    /// WebSocket (0 disables). Keep it well above the quietest program's gaps.
+    #[arg(long, env = "ODIN_STREAM_SILENCE_TIMEOUT", default_value = "0")]
+    stream_silence_timeout: u64,
+
+    /// Milliseconds to wait after a log notification before fetching its
+    /// transaction, so the RPC has it indexed (useful on `processed`)
+    #[arg(long, env = "ODIN_STREAM_FETCH_DELAY_MS", default_value = "0")]
+    stream_fetch_delay_ms: u64,
+
+    /// Times a streamed transaction the RPC doesn't have yet is fetched again
+    /// before it is given up on
+    #[arg(long, env = "ODIN_STREAM_FETCH_RETRIES", default_value = "3")]
+    stream_fetch_retries: u32,
+
+    /// Milliseconds between those retries
+    #[arg(long, env = "ODIN_STREAM_FETCH_RETRY_MS", default_value = "400")]
+    stream_fetch_retry_ms: u64,
+
+    /// Seconds between `getHealth` probes of the RPC backing readiness (0 disables)
+    #[arg(long, env = "ODIN_HEALTH_INTERVAL", default_value = "10")]
+    health_interval: u64,
+
+    /// Seconds the RPC may keep failing health probes before the gRPC health
+    /// service reports NOT_SERVING
+    #[arg(long, env = "ODIN_HEALTH_FAILURE_THRESHOLD", default_value = "30")]
+    health_failure_threshold: u64,
+
+    /// Highest transaction version to fetch; newer transactions fail with
+    /// FAILED_PRECONDITION
+    #[arg(long, env = "ODIN_MAX_SUPPORTED_VERSION", default_value = "0")]
+    max_supported_version: u8,
+
+    /// Regular expression for program logs to drop from every response, e.g.
+    /// `^Instruction: Transfer$`; repeat for more (newline-separated in the
+    /// environment variable)
+    #[arg(long = "exclude-pattern", env = "ODIN_EXCLUDE_PATTERNS", value_delimiter = '\n')]
+    exclude_patterns: Vec<String>,
+
+    /// Extra RPC endpoints (comma-separated) that GetTxLogs races against the
+    /// default RPC, keeping the first successful response. Only used for
+    /// requests that don't set their own `rpc_url`.
+    #[arg(long, env = "ODIN_HEDGE_RPC_URLS", value_delimiter = ',')]
+    hedge_rpc_urls: Vec<String>,
+
+    /// Fetched transactions to keep cached (0 disables). Finalized ones are
+    /// kept until evicted, others only for their commitment's TTL.
+    #[arg(long, env = "ODIN_TX_CACHE_SIZE", default_value = "1024")]
+    tx_cache_size: usize,
+
+    /// Milliseconds a transaction fetched at `processed` stays cached
+    #[arg(long, env = "ODIN_CACHE_PROCESSED_TTL_MS", default_value = "500")]
+    cache_processed_ttl_ms: u64,
+
+    /// Milliseconds a transaction fetched at `confirmed` stays cached
+    #[arg(long, env = "ODIN_CACHE_CONFIRMED_TTL_MS", default_value = "10000")]
+    cache_confirmed_ttl_ms: u64,
+
+    /// Upstream RPC calls allowed in flight across all requests and streams
+    /// (0 for no limit). Calls beyond it wait for a free slot.
+    #[arg(long, env = "ODIN_RPC_CONCURRENCY", default_value = "256")]
+    rpc_concurrency: usize,
+}
+
+impl ServerConfig {
+    fn reconnect_backoff(&self) -> Backoff {
+        Backoff {
+            base: Duration::from_millis(self.reconnect_base_ms),
+            multiplier: self.reconnect_multiplier,
+            max: Duration::from_millis(self.reconnect_max_ms),
+            jitter: self.reconnect_jitter,
+        }
+    }
+}
+
+/// What every parser the server builds shares, set up from `ServerConfig`
+/// at startup
+#[derive(Debug)]
+struct ParserResources {
+    /// `exclude_patterns`, compiled once
+    exclude_regexes: Vec<Regex>,
+    /// Fetched transactions, unless `tx_cache_size` is 0
+    tx_cache: Option<Arc<TxCache>>,
+    /// Permits for `rpc_concurrency`, unless it's 0
+    rpc_permits: Option<Arc<Semaphore>>,
+    /// RPC clients, one connection pool per upstream URL
+    rpc_clients: RpcClientPool,
+}
+
 /// OdinService implements the SolanaTxLog gRPC service
-#[derive(Debug, Default)]
-pub struct OdinService;
+#[derive(Debug)]
+pub struct OdinService {
+    config: Arc<ServerConfig>,
+    /// Cache, RPC permits and clients shared by every parser
+    resources: Arc<ParserResources>,
+    /// Program labels returned when a request sets `include_labels`
+    labels: Arc<ProgramLabels>,
+    /// Per-request audit trail, when `--audit-log` is set
+    audit: Option<AuditLog>,
+    /// Circuit breaker per upstream RPC URL
+    breakers: Arc<CircuitBreakers>,
+    /// GetTxLogsBatch concurrency per upstream RPC URL
+    concurrency: Arc<AdaptiveConcurrency>,
+    /// Cancelled on shutdown so stream tasks unsubscribe and close their WebSockets
+    shutdown: CancellationToken,
+    /// Tracks the WebSocket tasks spawned by `stream_program_logs`
+    streams: TaskTracker,
+    /// Program log streams currently open, reported on shutdown
+    active_streams: Arc<AtomicUsize>,
+}
+
+impl OdinService {
+    pub fn new(config: ServerConfig) -> Result<Self, String> {
+        let mut labels = ProgramLabels::new();
+        if let Some(ref path) = config.labels_file {
+            labels.extend_from_file(path)?;
+        }
+
+        if config.provider != ProviderKind::Standard && config.enhanced_api_key.is_none() {
+            return Err("--enhanced-api-key is required for enhanced providers".to_string());
+        }
+
+        if !(config.reconnect_multiplier >= 1.0 && config.reconnect_multiplier.is_finite()) {
+            return Err("--reconnect-multiplier must be a finite number of at least 1.0".to_string());
+        }
+        if !(0.0..=1.0).contains(&config.reconnect_jitter) {
+            return Err("--reconnect-jitter must be between 0.0 and 1.0".to_string());
+        }
+
+        let resources = ParserResources {
+            exclude_regexes: compile_exclude_patterns(&config.exclude_patterns).map_err(|e| e.to_string())?,
+            tx_cache: (config.tx_cache_size > 0).then(|| {
+                let ttl = CommitmentTtl {
+                    processed: Duration::from_millis(config.cache_processed_ttl_ms),
+                    confirmed: Duration::from_millis(config.cache_confirmed_ttl_ms),
+                };
+                Arc::new(TxCache::new(ttl, config.tx_cache_size))
+            }),
+            rpc_permits: (config.rpc_concurrency > 0).then(|| Arc::new(Semaphore::new(config.rpc_concurrency))),
+            rpc_clients: RpcClientPool::new(&config.user_agent, RPC_CLIENT_POOL_CAPACITY),
+        };
+
+        let audit = config
+            .audit_log
+            .then(|| AuditLog::new(config.audit_redact.iter().copied()));
+
+        let breakers = CircuitBreakers::new(
+            config.breaker_threshold,
+            Duration::from_secs(config.breaker_cooldown),
+        );
+
+        let concurrency = AdaptiveConcurrency::new(
+            config.batch_min_concurrency,
+            config.batch_max_concurrency,
+            Duration::from_millis(config.batch_latency_target_ms),
+        );
+
+        Ok(OdinService {
+            config: Arc::new(config),
+            resources: Arc::new(resources),
+            labels: Arc::new(labels),
+            audit,
+            breakers: Arc::new(breakers),
+            concurrency: Arc::new(concurrency),
+            shutdown: CancellationToken::new(),
+            streams: TaskTracker::new(),
+            active_streams: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    fn audit_request(&self, request: AuditRequest) {
+        if let Some(ref audit) = self.audit {
+            audit.log_request(&request);
+        }
+    }
+
+    fn audit_response(&self, method: &'static str, responses: &[&GetTxResponse]) {
+        if let Some(ref audit) = self.audit {
+            let compute_units = responses
+                .iter()
+                .filter_map(|response| response.compute_units_consumed)
+                .reduce(|total, consumed| total + consumed);
+
+            audit.log_response(&AuditResponse {
+                method,
+                logs: responses.iter().map(|response| response.raw_logs.len()).sum(),
+                compute_units,
+                success: responses.iter().all(|response| response.error.is_none()),
+                error: None,
+            });
+        }
+    }
+
+    /// Validate a batch request and capture what parsing it needs, so it
+    /// can run outside the handler
+    fn batch_fetcher(
+        &self,
+        method: &'static str,
+        client: Option<String>,
+        req: &GetTxBatchRequest,
+    ) -> Result<BatchFetcher, Status> {
+        // Use provided RPC URL or the server's default
+        let rpc_url = if req.rpc_url.is_empty() {
+            self.config.rpc_url.clone()
+        } else {
+            req.rpc_url.clone()
+        };
+
+        self.audit_request(AuditRequest {
+            method,
+            client,
+            signature: None,
+            program: None,
+            rpc_url: Some(rpc_url.clone()),
+        });
+
+        if req.tx_sigs.is_empty() {
+            return Err(self.audit_failure(
+                method,
+                Status::invalid_argument("At least one transaction signature is required"),
+            ));
+        }
+        if req.tx_sigs.len() > MAX_BATCH_SIZE {
+            return Err(self.audit_failure(
+                method,
+                Status::invalid_argument(format!("At most {} signatures per batch", MAX_BATCH_SIZE)),
+            ));
+        }
+
+        Ok(BatchFetcher {
+            config: self.config.clone(),
+            resources: self.resources.clone(),
+            labels: self.labels.clone(),
+            breakers: self.breakers.clone(),
+            concurrency: self.concurrency.clone(),
+            rpc_url,
+            // Prepare filter (None if empty)
+            filter: (!req.filter.is_empty()).then(|| req.filter.clone()),
+            include_cu_logs: req.include_cu_logs,
+            include_labels: req.include_labels,
+        })
+    }
+
+    /// Record a failed request and hand the status back for returning
+    fn audit_failure(&self, method: &'static str, status: Status) -> Status {
+        if let Some(ref audit) = self.audit {
+            audit.log_response(&AuditResponse {
+                method,
+                error: Some(status.message().to_string()),
+                ..Default::default()
+            });
+        }
+        status
+    }
+}
+
+/// Parses the signatures of a batch request, keeping as many in flight as
+/// the RPC's adaptive concurrency limit allows
+#[derive(Clone)]
+struct BatchFetcher {
+    config: Arc<ServerConfig>,
+    resources: Arc<ParserResources>,
+    labels: Arc<ProgramLabels>,
+    breakers: Arc<CircuitBreakers>,
+    concurrency: Arc<AdaptiveConcurrency>,
+    rpc_url: String,
+    filter: Option<String>,
+    include_cu_logs: bool,
+    include_labels: bool,
+}
+
+impl BatchFetcher {
+    /// Parse one signature, feeding the outcome into the breaker and the
+    /// concurrency limit
+    async fn fetch(self, signature: String) -> Result<GetTxResponse, Status> {
+        let rpc_url = self.rpc_url.as_str();
+        check_breaker(&self.breakers, rpc_url)?;
+
+        let mut parser = new_parser(
+            &self.config,
+            &self.resources,
+            self.rpc_url.clone(),
+            signature,
+            self.filter.as_deref(),
+            self.include_cu_logs,
+        );
+        let started = Instant::now();
+        let parsed = parser.parse().await;
+        record_rpc_outcome(&self.breakers, rpc_url, &parsed);
+
+        match &parsed {
+            Ok(()) => self.concurrency.record_success(rpc_url, started.elapsed()),
+            Err(ParseError::RateLimited(_)) => self.concurrency.record_rate_limited(rpc_url, started),
+            Err(_) => {}
+        }
+        parsed.map_err(|e| parse_error_status("Failed to parse transaction logs", &e))?;
+
+        Ok(build_tx_response(&parser, &self.labels, self.include_cu_logs, self.include_labels))
+    }
+
+    /// Parse every signature, yielding `(index, outcome)` in completion
+    /// order. The in-flight set is topped up to the current limit whenever
+    /// a fetch completes, so the limit takes effect as soon as it moves.
+    fn run(
+        self,
+        tx_sigs: Vec<String>,
+    ) -> impl futures_util::Stream<Item = (usize, Result<GetTxResponse, Status>)> {
+        use futures_util::{
+            FutureExt, StreamExt,
+            future::BoxFuture,
+            stream::{FuturesUnordered, unfold},
+        };
+
+        let in_flight: FuturesUnordered<BoxFuture<'static, (usize, Result<GetTxResponse, Status>)>> =
+            FuturesUnordered::new();
+
+        unfold(
+            (self, tx_sigs.into_iter().enumerate(), in_flight),
+            |(fetcher, mut pending, mut in_flight)| async move {
+                while in_flight.len() < fetcher.concurrency.limit(&fetcher.rpc_url) {
+                    let Some((idx, signature)) = pending.next() else {
+                        break;
+                    };
+                    in_flight.push(fetcher.clone().fetch(signature).map(move |outcome| (idx, outcome)).boxed());
+                }
+
+                let outcome = in_flight.next().await?;
+                Some((outcome, (fetcher, pending, in_flight)))
+            },
+        )
+    }
+}
+
+impl Drop for OdinService {
+    fn drop(&mut self) {
+        self.streams.close();
+        self.shutdown.cancel();
+    }
+}
 
 #[tonic::async_trait]
 impl SolanaTxLog for OdinService {
     type StreamProgramLogsStream = ReceiverStream<Result<proto::StreamTransactionResponse, Status>>;
+    type StreamTxLogsBatchStream = ReceiverStream<Result<BatchStreamMessage, Status>>;
 
     /// Fetch transaction logs for a given transaction signature
     async fn get_tx_logs(
         &self,
         request: Request<GetTxRequest>,
     ) -> Result<Response<GetTxResponse>, Status> {
+        let client = request.remote_addr().map(|addr| addr.to_string());
         let req = request.into_inner();
 
-        // Use provided RPC URL or default to Mainnet Beta
+        // Hedge endpoints serve the default cluster, so only race them for
+        // requests using the default RPC
+        let hedge_rpc_urls: &[String] = if req.rpc_url.is_empty() {
+            &self.config.hedge_rpc_urls
+        } else {
+            &[]
+        };
+
+        // Use provided RPC URL or the server's default
         let rpc_url = if req.rpc_url.is_empty() {
-            DEFAULT_RPC_URL.to_string()
+            self.config.rpc_url.clone()
         } else {
             req.rpc_url
         };
 
+        self.audit_request(AuditRequest {
+            method: "GetTxLogs",
+            client,
+            signature: Some(req.tx_sig.clone()),
+            program: None,
+            rpc_url: Some(rpc_url.clone()),
+        });
+
         // Validate transaction signature
-        if req.tx_sig.is_empty() {
-            return Err(Status::invalid_argument("Transaction signature is required"));
-        }
+        check_signature(&req.tx_sig).map_err(|status| self.audit_failure("GetTxLogs", status))?;
 
         // Prepare filter (None if empty)
         let filter = if req.filter.is_empty() {
@@ -53,41 +577,431 @@ impl SolanaTxLog for OdinService {
             Some(req.filter.as_str())
         };
 
+        // Noise to drop on top of the server-wide patterns
+        let exclude_patterns = compile_exclude_patterns(&req.exclude_pattern)
+            .map_err(|e| self.audit_failure("GetTxLogs", Status::invalid_argument(e.to_string())))?;
+        let log_order: LogOrder = req
+            .log_order
+            .parse()
+            .map_err(|e: String| self.audit_failure("GetTxLogs", Status::invalid_argument(e)))?;
+        let filter_mode: FilterMode = req
+            .filter_mode
+            .parse()
+            .map_err(|e: String| self.audit_failure("GetTxLogs", Status::invalid_argument(e)))?;
+        let commitment =
+            parse_commitment(&req.commitment).map_err(|e| self.audit_failure("GetTxLogs", Status::invalid_argument(e)))?;
+
+        // Fail fast while the RPC's circuit is open
+        check_breaker(&self.breakers, &rpc_url).map_err(|status| self.audit_failure("GetTxLogs", status))?;
+
         // Create parser instance
-        let mut parser = TxLogParser::new(
-            rpc_url,
-            req.tx_sig.clone(),
-            filter,
-            req.include_cu_logs,
-        );
+        let mut parser = new_parser(&self.config, &self.resources, rpc_url.clone(), req.tx_sig.clone(), filter, req.include_cu_logs)
+            .with_exclude_patterns(&exclude_patterns)
+            .with_log_order(log_order)
+            .with_hedge_rpc_urls(hedge_rpc_urls)
+            .with_raw_filter(&req.raw_filter)
+            .with_commitment(commitment)
+            .with_filter_mode(filter_mode)
+            .map_err(|e| self.audit_failure("GetTxLogs", Status::invalid_argument(e.to_string())))?;
+
+        // CU-only requests skip building the log vectors
+        if req.cu_only {
+            let fetched = parser.fetch_compute_units_only().await;
+            record_parse_outcome(&self.breakers, &rpc_url, &parser, &fetched);
+            let (consumed, cu_logs) = fetched.map_err(|e| {
+                self.audit_failure(
+                    "GetTxLogs",
+                    parse_error_status("Failed to fetch compute units", &e),
+                )
+            })?;
+
+            let response = GetTxResponse {
+                compute_units: compute_units_to_proto(&cu_logs, &self.labels, req.include_labels),
+                cu_ranking: cu_ranking_to_proto(&cu_logs, &self.labels, req.include_labels),
+                compute_units_consumed: Some(consumed),
+                ..Default::default()
+            };
+            self.audit_response("GetTxLogs", &[&response]);
+
+            return Ok(Response::new(response));
+        }
 
         // Parse the transaction logs
-        parser.parse().await.map_err(|e| {
-            Status::internal(format!("Failed to parse transaction logs: {}", e))
+        let parsed = parser.parse().await;
+        record_parse_outcome(&self.breakers, &rpc_url, &parser, &parsed);
+        parsed.map_err(|e| {
+            self.audit_failure(
+                "GetTxLogs",
+                parse_error_status("Failed to parse transaction logs", &e),
+            )
         })?;
 
-        // Get the parsed logs
-        let logs = parser.get_tx_logs();
-        let raw_logs = parser.get_raw_logs();
-
-        // Build compute unit logs if requested
-        let mut compute_units = Vec::new();
-        if req.include_cu_logs {
-            let cu_logs = parser.get_cu_logs();
-            for (program_id, consumed) in cu_logs.iter() {
-                compute_units.push(ComputeUnitLog {
-                    program_id: program_id.to_string(),
-                    consumed: *consumed,
+        // Backfills over a slot window skip transactions that drifted out of it
+        parser
+            .check_slot_range(req.min_slot, req.max_slot)
+            .map_err(|e| self.audit_failure("GetTxLogs", parse_error_status("Transaction out of range", &e)))?;
+
+        // Build the response
+        let response = build_tx_response(&parser, &self.labels, req.include_cu_logs, req.include_labels);
+        self.audit_response("GetTxLogs", &[&response]);
+
+        Ok(Response::new(response))
+    }
+
+    /// Fetch transaction logs for many signatures, keeping as many in flight
+    /// as the RPC's adaptive concurrency limit allows
+    async fn get_tx_logs_batch(
+        &self,
+        request: Request<GetTxBatchRequest>,
+    ) -> Result<Response<GetTxBatchResponse>, Status> {
+        use futures_util::StreamExt;
+
+        let client = request.remote_addr().map(|addr| addr.to_string());
+        let req = request.into_inner();
+        let fetcher = self.batch_fetcher("GetTxLogsBatch", client, &req)?;
+
+        let mut results: Vec<BatchTxResult> = req
+            .tx_sigs
+            .iter()
+            .map(|tx_sig| BatchTxResult {
+                tx_sig: tx_sig.clone(),
+                ..Default::default()
+            })
+            .collect();
+
+        let outcomes = fetcher.run(req.tx_sigs);
+        tokio::pin!(outcomes);
+        while let Some((idx, outcome)) = outcomes.next().await {
+            match outcome {
+                Ok(response) => results[idx].response = Some(response),
+                Err(status) => {
+                    results[idx].error = status.message().to_string();
+                    results[idx].code = status.code() as i32;
+                }
+            }
+        }
+
+        self.audit_response(
+            "GetTxLogsBatch",
+            &results
+                .iter()
+                .filter_map(|result| result.response.as_ref())
+                .collect::<Vec<_>>(),
+        );
+
+        Ok(Response::new(GetTxBatchResponse { results }))
+    }
+
+    /// Same as GetTxLogsBatch, but each result is streamed as soon as it is
+    /// parsed, interleaved with progress messages
+    async fn stream_tx_logs_batch(
+        &self,
+        request: Request<GetTxBatchRequest>,
+    ) -> Result<Response<Self::StreamTxLogsBatchStream>, Status> {
+        use futures_util::StreamExt;
+
+        let client = request.remote_addr().map(|addr| addr.to_string());
+        let req = request.into_inner();
+        let fetcher = self.batch_fetcher("StreamTxLogsBatch", client, &req)?;
+
+        let (tx, rx) = tokio::sync::mpsc::channel(128);
+        let audit = self.audit.clone();
+        let total = req.tx_sigs.len() as u32;
+        let tx_sigs = req.tx_sigs;
+
+        self.streams.spawn(async move {
+            let progress = |processed: u32| BatchStreamMessage {
+                message: Some(batch_stream_message::Message::Progress(BatchProgress { processed, total })),
+                ..Default::default()
+            };
+
+            let outcomes = fetcher.run(tx_sigs.clone());
+            tokio::pin!(outcomes);
+            let mut processed = 0;
+            let mut logs = 0;
+            let mut success = true;
+            let mut last_progress = Instant::now();
+            while let Some((idx, outcome)) = outcomes.next().await {
+                processed += 1;
+
+                let mut result = BatchTxResult {
+                    tx_sig: tx_sigs[idx].clone(),
+                    ..Default::default()
+                };
+                match outcome {
+                    Ok(response) => {
+                        logs += response.raw_logs.len();
+                        success &= response.error.is_none();
+                        result.response = Some(response);
+                    }
+                    Err(status) => {
+                        result.error = status.message().to_string();
+                        result.code = status.code() as i32;
+                    }
+                }
+
+                let message = BatchStreamMessage {
+                    index: idx as u32,
+                    message: Some(batch_stream_message::Message::Result(Box::new(result))),
+                };
+                if tx.send(Ok(message)).await.is_err() {
+                    return;
+                }
+
+                // The final count is always sent, below
+                if processed < total && last_progress.elapsed() >= BATCH_PROGRESS_INTERVAL {
+                    last_progress = Instant::now();
+                    if tx.send(Ok(progress(processed))).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            let _ = tx.send(Ok(progress(processed))).await;
+
+            if let Some(audit) = audit {
+                audit.log_response(&AuditResponse {
+                    method: "StreamTxLogsBatch",
+                    logs,
+                    success,
+                    ..Default::default()
                 });
             }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    /// Parse a transaction and the ones that followed it on an account,
+    /// ordered by slot
+    async fn get_related_transactions(
+        &self,
+        request: Request<GetRelatedRequest>,
+    ) -> Result<Response<GetRelatedResponse>, Status> {
+        let client = request.remote_addr().map(|addr| addr.to_string());
+        let req = request.into_inner();
+
+        // Use provided RPC URL or the server's default
+        let rpc_url = if req.rpc_url.is_empty() {
+            self.config.rpc_url.clone()
+        } else {
+            req.rpc_url
+        };
+
+        self.audit_request(AuditRequest {
+            method: "GetRelatedTransactions",
+            client,
+            signature: Some(req.tx_sig.clone()),
+            program: Some(req.account.clone()),
+            rpc_url: Some(rpc_url.clone()),
+        });
+
+        check_signature(&req.tx_sig).map_err(|status| self.audit_failure("GetRelatedTransactions", status))?;
+        if req.account.is_empty() {
+            return Err(self.audit_failure(
+                "GetRelatedTransactions",
+                Status::invalid_argument("Account to follow is required"),
+            ));
+        }
+
+        let limit = if req.limit == 0 {
+            DEFAULT_RELATED_LIMIT
+        } else {
+            (req.limit as usize).min(MAX_RELATED_LIMIT)
+        };
+
+        // Prepare filter (None if empty)
+        let filter = if req.filter.is_empty() {
+            None
+        } else {
+            Some(req.filter.as_str())
+        };
+
+        // Gather the related signatures
+        check_breaker(&self.breakers, &rpc_url)
+            .map_err(|status| self.audit_failure("GetRelatedTransactions", status))?;
+        let before = (!req.before.is_empty()).then_some(req.before.as_str());
+        let until = (!req.until.is_empty()).then_some(req.until.as_str());
+        let related = new_parser(&self.config, &self.resources, rpc_url.clone(), req.tx_sig.clone(), None, false)
+            .fetch_related_signatures(&req.account, before, until, limit)
+            .await;
+        record_rpc_outcome(&self.breakers, &rpc_url, &related);
+        let related = related.map_err(|e| {
+            self.audit_failure(
+                "GetRelatedTransactions",
+                parse_error_status("Failed to fetch related transactions", &e),
+            )
+        })?;
+
+        // Parse each of them; one failure doesn't sink the others
+        let mut transactions = Vec::new();
+        for (signature, slot) in related.signatures {
+            let mut related_tx = RelatedTransaction {
+                signature: signature.clone(),
+                slot,
+                ..Default::default()
+            };
+
+            let mut parser = new_parser(
+                &self.config,
+                &self.resources,
+                rpc_url.clone(),
+                signature.clone(),
+                filter,
+                req.include_cu_logs,
+            );
+
+            let parsed = match check_breaker(&self.breakers, &rpc_url) {
+                Ok(()) => {
+                    let parsed = parser.parse().await;
+                    record_rpc_outcome(&self.breakers, &rpc_url, &parsed);
+                    parsed.map_err(|e| {
+                        parse_error_status(&format!("Failed to parse transaction {}", signature), &e)
+                    })
+                }
+                Err(status) => Err(status),
+            };
+            match parsed {
+                Ok(_) => {
+                    related_tx.response = Some(build_tx_response(
+                        &parser,
+                        &self.labels,
+                        req.include_cu_logs,
+                        req.include_labels,
+                    ))
+                }
+                Err(status) => related_tx.error = status.message().to_string(),
+            }
+
+            transactions.push(related_tx);
         }
 
-        // Build the response
-        let response = GetTxResponse {
-            logs,
-            compute_units,
-            anchor_events: Vec::new(), // TODO: Implement anchor event parsing later
-            raw_logs,
+        self.audit_response(
+            "GetRelatedTransactions",
+            &transactions
+                .iter()
+                .filter_map(|transaction| transaction.response.as_ref())
+                .collect::<Vec<_>>(),
+        );
+
+        Ok(Response::new(GetRelatedResponse {
+            transactions,
+            next_cursor: related.next_cursor.unwrap_or_default(),
+        }))
+    }
+
+    /// Simulate a serialized transaction and return the logs it would produce
+    async fn simulate_transaction(
+        &self,
+        request: Request<SimulateRequest>,
+    ) -> Result<Response<GetTxResponse>, Status> {
+        let client = request.remote_addr().map(|addr| addr.to_string());
+        let req = request.into_inner();
+
+        // Use provided RPC URL or the server's default
+        let rpc_url = if req.rpc_url.is_empty() {
+            self.config.rpc_url.clone()
+        } else {
+            req.rpc_url
+        };
+
+        self.audit_request(AuditRequest {
+            method: "SimulateTransaction",
+            client,
+            signature: None,
+            program: None,
+            rpc_url: Some(rpc_url.clone()),
+        });
+
+        if req.encoded_tx.is_empty() {
+            return Err(self.audit_failure(
+                "SimulateTransaction",
+                Status::invalid_argument("Encoded transaction is required"),
+            ));
+        }
+
+        // Prepare filter (None if empty)
+        let filter = if req.filter.is_empty() {
+            None
+        } else {
+            Some(req.filter.as_str())
+        };
+
+        check_breaker(&self.breakers, &rpc_url)
+            .map_err(|status| self.audit_failure("SimulateTransaction", status))?;
+
+        let mut parser = new_parser(&self.config, &self.resources, rpc_url.clone(), String::new(), filter, req.include_cu_logs);
+
+        let simulated = parser.simulate(&req.encoded_tx).await;
+        record_rpc_outcome(&self.breakers, &rpc_url, &simulated);
+        simulated.map_err(|e| {
+            self.audit_failure(
+                "SimulateTransaction",
+                parse_error_status("Failed to simulate transaction", &e),
+            )
+        })?;
+
+        let response = build_tx_response(&parser, &self.labels, req.include_cu_logs, req.include_labels);
+        self.audit_response("SimulateTransaction", &[&response]);
+
+        Ok(Response::new(response))
+    }
+
+    /// Check whether a transaction logged a line, stopping at the first match
+    async fn contains_log(
+        &self,
+        request: Request<ContainsLogRequest>,
+    ) -> Result<Response<ContainsLogResponse>, Status> {
+        let client = request.remote_addr().map(|addr| addr.to_string());
+        let req = request.into_inner();
+
+        // Use provided RPC URL or the server's default
+        let rpc_url = if req.rpc_url.is_empty() {
+            self.config.rpc_url.clone()
+        } else {
+            req.rpc_url
+        };
+
+        self.audit_request(AuditRequest {
+            method: "ContainsLog",
+            client,
+            signature: Some(req.tx_sig.clone()),
+            program: None,
+            rpc_url: Some(rpc_url.clone()),
+        });
+
+        check_signature(&req.tx_sig).map_err(|status| self.audit_failure("ContainsLog", status))?;
+        if req.needle.is_empty() {
+            return Err(self.audit_failure(
+                "ContainsLog",
+                Status::invalid_argument("Needle is required"),
+            ));
+        }
+
+        check_breaker(&self.breakers, &rpc_url).map_err(|status| self.audit_failure("ContainsLog", status))?;
+
+        let parser = new_parser(&self.config, &self.resources, rpc_url.clone(), req.tx_sig.clone(), None, false);
+        let found = parser.find_first(&req.needle).await;
+        record_rpc_outcome(&self.breakers, &rpc_url, &found);
+        let found = found.map_err(|e| {
+            self.audit_failure("ContainsLog", parse_error_status("Failed to fetch transaction", &e))
+        })?;
+
+        if let Some(ref audit) = self.audit {
+            audit.log_response(&AuditResponse {
+                method: "ContainsLog",
+                logs: usize::from(found.is_some()),
+                success: true,
+                ..Default::default()
+            });
+        }
+
+        let response = match found {
+            Some((index, line)) => ContainsLogResponse {
+                found: true,
+                index: Some(index as u32),
+                line,
+            },
+            None => ContainsLogResponse::default(),
         };
 
         Ok(Response::new(response))
@@ -98,30 +1012,39 @@ impl SolanaTxLog for OdinService {
         &self,
         request: Request<StreamProgramRequest>,
     ) -> Result<Response<Self::StreamProgramLogsStream>, Status> {
-        use futures_util::{SinkExt, StreamExt};
-        use tokio_tungstenite::{connect_async, tungstenite::Message};
-        use serde_json::json;
+        use futures_util::StreamExt;
 
+        let client = request.remote_addr().map(|addr| addr.to_string());
         let req = request.into_inner();
 
+        self.audit_request(AuditRequest {
+            method: "StreamProgramLogs",
+            client,
+            signature: None,
+            program: Some(req.program_address.clone()),
+            rpc_url: (!req.rpc_url.is_empty()).then(|| req.rpc_url.clone()),
+        });
+
         // Validate program address
         if req.program_address.is_empty() {
-            return Err(Status::invalid_argument("Program address is required"));
+            return Err(self.audit_failure(
+                "StreamProgramLogs",
+                Status::invalid_argument("Program address is required"),
+            ));
         }
 
-        // Determine WebSocket URL from RPC URL
-        let ws_url = if req.rpc_url.is_empty() {
+        // Determine WebSocket URL from RPC URL, unless given
+        let ws_url = if !req.ws_url.is_empty() {
+            req.ws_url.clone()
+        } else if req.rpc_url.is_empty() {
             "wss://api.mainnet-beta.solana.com".to_string()
         } else {
-            // Convert HTTP(S) URL to WS(S)
-            req.rpc_url
-                .replace("https://", "wss://")
-                .replace("http://", "ws://")
+            ws_url_from_rpc(&req.rpc_url)
         };
 
         // Use HTTP RPC URL for fetching transaction details
         let rpc_url = if req.rpc_url.is_empty() {
-            DEFAULT_RPC_URL.to_string()
+            self.config.rpc_url.clone()
         } else {
             req.rpc_url.clone()
         };
@@ -136,127 +1059,216 @@ impl SolanaTxLog for OdinService {
             Some(req.filter.clone())
         };
 
+        // Noise to drop on top of the server-wide patterns
+        let exclude_patterns = compile_exclude_patterns(&req.exclude_pattern)
+            .map_err(|e| self.audit_failure("StreamProgramLogs", Status::invalid_argument(e.to_string())))?;
+        let log_order: LogOrder = req
+            .log_order
+            .parse()
+            .map_err(|e: String| self.audit_failure("StreamProgramLogs", Status::invalid_argument(e)))?;
+        let commitment = parse_commitment(&req.commitment)
+            .map_err(|e| self.audit_failure("StreamProgramLogs", Status::invalid_argument(e)))?;
+
         // Create channel for streaming
         let (tx, rx) = tokio::sync::mpsc::channel(128);
 
-        // Spawn WebSocket task
-        tokio::spawn(async move {
-            // Connect to WebSocket
-            let ws_stream = match connect_async(&ws_url).await {
-                Ok((stream, _)) => stream,
-                Err(e) => {
-                    eprintln!("❌ WebSocket connection failed: {}", e);
-                    let _ = tx.send(Err(Status::internal(format!("WebSocket connection failed: {}", e)))).await;
-                    return;
+        // Refetch policy for notifications that race the transaction's
+        // availability at the stream's commitment
+        let fetch_retries = req
+            .fetch_retries
+            .unwrap_or(self.config.stream_fetch_retries)
+            .min(MAX_STREAM_FETCH_RETRIES);
+        let fetch_retry_delay = Duration::from_millis(
+            req.fetch_retry_ms
+                .unwrap_or(self.config.stream_fetch_retry_ms)
+                .min(MAX_STREAM_FETCH_RETRY_MS),
+        );
+
+        let shutdown = self.shutdown.clone();
+        let config = self.config.clone();
+        let resources = self.resources.clone();
+        let followups = self.streams.clone();
+        let labels = self.labels.clone();
+        let breakers = self.breakers.clone();
+
+        // The library subscription reconnects on its own and unsubscribes
+        // when the server shuts down
+        let options = SubscribeOptions {
+            commitment: commitment.commitment.to_string(),
+            backoff: self.config.reconnect_backoff(),
+            max_reconnect_attempts: self.config.reconnect_max_attempts,
+            silence_timeout: (self.config.stream_silence_timeout > 0)
+                .then(|| Duration::from_secs(self.config.stream_silence_timeout)),
+            cancel: shutdown.clone(),
+            tracker: Some(self.streams.clone()),
+        };
+
+        // Spawn the stream task, tracked so shutdown waits for it
+        let active = ActiveStream::open(&self.active_streams);
+        self.streams.spawn(async move {
+            let _active = active;
+            let notifications = subscribe_logs(ws_url, req.program_address.clone(), options);
+            tokio::pin!(notifications);
+
+            loop {
+                let notification = tokio::select! {
+                    _ = tx.closed() => {
+                        println!("🔌 Client disconnected");
+                        break;
+                    }
+                    // Tell the client why the stream ends; dropping `tx`
+                    // then lets it drain what's already queued
+                    _ = shutdown.cancelled() => {
+                        let _ = tx.send(Err(Status::unavailable("Server is shutting down"))).await;
+                        break;
+                    }
+                    notification = notifications.next() => notification,
+                };
+
+                let notification = match notification {
+                    Some(Ok(notification)) => notification,
+                    Some(Err(e)) => {
+                        let _ = tx.send(Err(Status::unavailable(e.to_string()))).await;
+                        break;
+                    }
+                    None => break,
+                };
+                let signature = notification.signature.as_str();
+                let slot = notification.slot;
+
+                // Skip notifications older than the resume point
+                if slot < req.since_slot {
+                    continue;
                 }
-            };
 
-            let (mut write, mut read) = ws_stream.split();
-
-            // Subscribe to logs for the program
-            let subscribe_msg = json!({
-                "jsonrpc": "2.0",
-                "id": 1,
-                "method": "logsSubscribe",
-                "params": [
-                    {
-                        "mentions": [req.program_address.clone()]
-                    },
-                    {
-                        "commitment": "confirmed"
+                // In errors-only mode, skip healthy transactions before fetching them
+                if req.errors_only {
+                    let matches_filter = filter.as_ref().is_some_and(|f| notification.logs_match(f));
+                    if !notification.failed && !matches_filter {
+                        continue;
                     }
-                ]
-            });
+                }
 
-            if let Err(e) = write.send(Message::Text(subscribe_msg.to_string())).await {
-                eprintln!("❌ Failed to send subscription: {}", e);
-                let _ = tx.send(Err(Status::internal("Failed to subscribe"))).await;
-                return;
-            }
+                // Don't pile requests onto an RPC whose circuit is open
+                if breakers.try_acquire(&rpc_url).is_err() {
+                    eprintln!("⏭️  Skipping {}: circuit open for RPC {}", signature, rpc_url);
+                    continue;
+                }
+
+                println!("📨 Processing transaction: {}", signature);
+
+                // Parse the full transaction using TxLogParser
+                let mut parser = new_parser(
+                    &config,
+                    &resources,
+                    rpc_url.clone(),
+                    signature.to_string(),
+                    filter.as_deref(),
+                    req.include_cu_logs,
+                )
+                .with_exclude_patterns(&exclude_patterns)
+                .with_log_order(log_order)
+                .with_raw_filter(&req.raw_filter)
+                .with_commitment(commitment);
+
+                // The notification can arrive before the transaction is
+                // queryable, so wait a little and retry while it isn't found
+                if config.stream_fetch_delay_ms > 0 {
+                    tokio::time::sleep(Duration::from_millis(config.stream_fetch_delay_ms)).await;
+                }
+                let mut retries = 0;
+                let parsed = loop {
+                    let parsed = if req.errors_only {
+                        parser.parse_errors_only().await
+                    } else {
+                        parser.parse().await.map(|_| true)
+                    };
+
+                    match parsed {
+                        Err(ParseError::NotFound(_) | ParseError::NodeBehind(_)) if retries < fetch_retries => {
+                            retries += 1;
+                            tokio::time::sleep(fetch_retry_delay).await;
+                        }
+                        parsed => break parsed,
+                    }
+                };
+                record_rpc_outcome(&breakers, &rpc_url, &parsed);
+
+                match parsed {
+                    Ok(false) => {
+                        // Healthy transaction skipped in errors-only mode
+                    }
+                    Ok(true) => {
+                        let tx_response = build_tx_response(
+                            &parser,
+                            &labels,
+                            req.include_cu_logs,
+                            req.include_labels,
+                        );
+
+                        // Build the response, or one message per log line
+                        let responses = if req.flatten_logs {
+                            flatten_response(signature, slot, tx_response)
+                        } else {
+                            vec![stream_response(signature, slot, tx_response)]
+                        };
 
-            println!("✅ Subscribed successfully!");
-
-            // Process incoming messages
-            while let Some(msg) = read.next().await {
-                match msg {
-                    Ok(Message::Text(text)) => {
-                        // Parse WebSocket message
-                        if let Ok(value) = serde_json::from_str::<serde_json::Value>(&text) {
-                            // Check if it's a log notification
-                            if value.get("method").and_then(|m| m.as_str()) == Some("logsNotification") {
-                                // Extract signature
-                                let signature = value
-                                    .pointer("/params/result/value/signature")
-                                    .and_then(|s| s.as_str())
-                                    .unwrap_or("unknown");
-
-                                if signature == "unknown" {
-                                    continue;
+                        for response in responses {
+                            if tx.send(Ok(response)).await.is_err() {
+                                // Client disconnected
+                                println!("🔌 Client disconnected");
+                                return;
+                            }
+                        }
+
+                        println!("✅ Streamed parsed transaction: {}", signature);
+
+                        // Schedule a follow-up status check for the signature
+                        if req.track_finalization {
+                            let tx = tx.clone();
+                            let shutdown = shutdown.clone();
+                            let rpc_url = rpc_url.clone();
+                            let signature = signature.to_string();
+                            let resources = resources.clone();
+
+                            followups.spawn(async move {
+                                tokio::select! {
+                                    _ = shutdown.cancelled() => return,
+                                    _ = tokio::time::sleep(FINALIZATION_CHECK_DELAY) => {}
                                 }
 
-                                println!("📨 Processing transaction: {}", signature);
-
-                                // Parse the full transaction using TxLogParser
-                                let mut parser = TxLogParser::new(
-                                    rpc_url.clone(),
-                                    signature.to_string(),
-                                    filter.as_deref(),
-                                    req.include_cu_logs,
-                                );
-
-                                match parser.parse().await {
-                                    Ok(_) => {
-                                        // Get the parsed logs
-                                        let logs = parser.get_tx_logs();
-                                        let raw_logs = parser.get_raw_logs();
-
-                                        // Build compute unit logs if requested
-                                        let mut compute_units = Vec::new();
-                                        if req.include_cu_logs {
-                                            let cu_logs = parser.get_cu_logs();
-                                            for (program_id, consumed) in cu_logs.iter() {
-                                                compute_units.push(ComputeUnitLog {
-                                                    program_id: program_id.to_string(),
-                                                    consumed: *consumed,
-                                                });
-                                            }
-                                        }
-
-                                        // Build the response
-                                        let response = proto::StreamTransactionResponse {
-                                            signature: signature.to_string(),
-                                            logs,
-                                            compute_units,
-                                            raw_logs,
-                                            timestamp: chrono::Utc::now().to_rfc3339(),
-                                        };
-
-                                        if tx.send(Ok(response)).await.is_err() {
-                                            // Client disconnected
-                                            println!("🔌 Client disconnected");
-                                            return;
-                                        }
-
-                                        println!("✅ Streamed parsed transaction: {}", signature);
-                                    }
+                                let _permit = match resources.rpc_permits {
+                                    Some(ref permits) => permits.clone().acquire_owned().await.ok(),
+                                    None => None,
+                                };
+                                let status = match finalization_status(&resources, &rpc_url, &signature).await {
+                                    Ok(status) => status,
                                     Err(e) => {
-                                        eprintln!("❌ Failed to parse transaction {}: {}", signature, e);
-                                        // Continue streaming even if one transaction fails
+                                        eprintln!("❌ Failed to check status of {}: {}", signature, e);
+                                        return;
                                     }
-                                }
-                            }
+                                };
+
+                                let response = proto::StreamTransactionResponse {
+                                    signature,
+                                    timestamp: chrono::Utc::now().to_rfc3339(),
+                                    slot,
+                                    status_update: Some(StatusUpdate {
+                                        status: status.to_string(),
+                                    }),
+                                    ..Default::default()
+                                };
+                                let _ = tx.send(Ok(response)).await;
+                            });
                         }
                     }
-                    Ok(Message::Close(_)) => {
-                        println!("🔌 WebSocket closed");
-                        break;
+                    Err(ParseError::NotFound(_) | ParseError::NodeBehind(_)) => {
+                        eprintln!("⏭️  Skipping {}: not available after {} refetches", signature, retries);
                     }
                     Err(e) => {
-                        eprintln!("❌ WebSocket error: {}", e);
-                        let _ = tx.send(Err(Status::internal(format!("WebSocket error: {}", e)))).await;
-                        break;
+                        eprintln!("❌ Failed to parse transaction {}: {}", signature, e);
+                        // Continue streaming even if one transaction fails
                     }
-                    _ => {}
                 }
             }
 
@@ -268,18 +1280,884 @@ impl SolanaTxLog for OdinService {
     }
 }
 
+/// Counts an open program stream in `active_streams` for as long as it lives
+struct ActiveStream(Arc<AtomicUsize>);
+
+impl ActiveStream {
+    fn open(active_streams: &Arc<AtomicUsize>) -> Self {
+        active_streams.fetch_add(1, Ordering::Relaxed);
+        ActiveStream(active_streams.clone())
+    }
+}
+
+impl Drop for ActiveStream {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Issue a cheap `getHealth` against the RPC to warm the connection and
+/// validate the endpoint before accepting requests
+async fn preflight(rpc: &RpcClient) -> Result<(), String> {
+    let started = std::time::Instant::now();
+
+    rpc.get_health()
+        .await
+        .map_err(|e| format!("RPC preflight against {} failed: {}", rpc.url(), e))?;
+
+    println!("🩺 RPC preflight OK: {} ({} ms)", rpc.url(), started.elapsed().as_millis());
+    Ok(())
+}
+
+/// Probe the RPC with `getHealth` every `interval` and report NOT_SERVING,
+/// for both the overall server and the SolanaTxLog service, once it has been
+/// failing for longer than `threshold`
+async fn watch_rpc_health(
+    reporter: HealthReporter,
+    rpc: Arc<RpcClient>,
+    interval: Duration,
+    threshold: Duration,
+    shutdown: CancellationToken,
+) {
+    let rpc_url = rpc.url();
+    let mut failing_since: Option<std::time::Instant> = None;
+    let mut serving = true;
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => return,
+            _ = tokio::time::sleep(interval) => {}
+        }
+
+        match rpc.get_health().await {
+            Ok(()) => failing_since = None,
+            Err(e) => {
+                if failing_since.is_none() {
+                    eprintln!("⚠️  RPC health probe against {} failed: {}", rpc_url, e);
+                }
+                failing_since.get_or_insert_with(std::time::Instant::now);
+            }
+        }
+
+        let healthy = failing_since.is_none_or(|since| since.elapsed() <= threshold);
+        if healthy == serving {
+            continue;
+        }
+        serving = healthy;
+
+        let status = if healthy {
+            println!("🩺 RPC {} healthy again; readiness is SERVING", rpc_url);
+            ServingStatus::Serving
+        } else {
+            eprintln!(
+                "🚫 RPC {} unhealthy for over {}s; readiness is NOT_SERVING",
+                rpc_url,
+                threshold.as_secs()
+            );
+            ServingStatus::NotServing
+        };
+
+        reporter.set_service_status("", status).await;
+        reporter
+            .set_service_status(<SolanaTxLogServer<OdinService> as NamedService>::NAME, status)
+            .await;
+    }
+}
+
+/// Reject a missing or malformed transaction signature with INVALID_ARGUMENT
+/// before any RPC work
+fn check_signature(tx_sig: &str) -> Result<(), Status> {
+    if tx_sig.is_empty() {
+        return Err(Status::invalid_argument("Transaction signature is required"));
+    }
+    Signature::from_str(tx_sig)
+        .map(|_| ())
+        .map_err(|_| Status::invalid_argument(format!("Invalid transaction signature: {}", tx_sig)))
+}
+
+/// Reject a request with UNAVAILABLE while the RPC's circuit is open
+fn check_breaker(breakers: &CircuitBreakers, rpc_url: &str) -> Result<(), Status> {
+    breakers.try_acquire(rpc_url).map_err(|retry_in| {
+        Status::unavailable(format!(
+            "RPC {} is failing; circuit open, retry in {}s",
+            rpc_url,
+            retry_in.as_secs().max(1)
+        ))
+    })
+}
+
+/// Feed the outcome of an RPC-backed call into the RPC's circuit breaker.
+/// Only upstream errors count as failures; a not-found answer still shows
+/// the RPC is healthy.
+fn record_rpc_outcome<T>(breakers: &CircuitBreakers, rpc_url: &str, result: &Result<T, ParseError>) {
+    match result {
+        Err(e) if e.is_upstream() => breakers.record_failure(rpc_url),
+        Err(ParseError::InvalidInput(_) | ParseError::Config(_)) => breakers.release(rpc_url),
+        _ => breakers.record_success(rpc_url),
+    }
+}
+
+/// `record_rpc_outcome` for a parse that may have been hedged: every
+/// endpoint the hedged fetch heard from gets its own outcome, and the probe
+/// slot of `rpc_url` is released if it was cancelled. Unhedged parses are
+/// put down to `rpc_url` as before.
+fn record_parse_outcome<T>(
+    breakers: &CircuitBreakers,
+    rpc_url: &str,
+    parser: &TxLogParser,
+    result: &Result<T, ParseError>,
+) {
+    let outcomes = parser.get_upstream_outcomes();
+    if outcomes.is_empty() {
+        record_rpc_outcome(breakers, rpc_url, result);
+        return;
+    }
+
+    for (url, outcome) in outcomes {
+        record_rpc_outcome(breakers, url, outcome);
+    }
+    if !outcomes.iter().any(|(url, _)| url == rpc_url) {
+        breakers.release(rpc_url);
+    }
+}
+
+/// The shared client for `rpc_url`, sending the configured User-Agent
+fn rpc_client(resources: &ParserResources, rpc_url: &str) -> Result<Arc<RpcClient>, ParseError> {
+    resources.rpc_clients.get(rpc_url)
+}
+
+/// Create a parser with the server-wide options applied
+fn new_parser(
+    config: &ServerConfig,
+    resources: &ParserResources,
+    rpc_url: String,
+    tx_sig: String,
+    filter: Option<&str>,
+    include_cu_logs: bool,
+) -> TxLogParser {
+    // Fall back to a client of the parser's own if the shared one can't be built
+    let shared = resources.rpc_clients.get(&rpc_url).ok();
+    let parser = match shared {
+        Some(client) => TxLogParser::with_client(client, tx_sig, filter, include_cu_logs),
+        None => TxLogParser::new(rpc_url, tx_sig, filter, include_cu_logs),
+    };
+    let parser = parser
+        .with_filter_warnings(!config.quiet_filter_warnings)
+        .with_user_agent(config.user_agent.clone())
+        .with_max_logs(config.max_logs)
+        .with_max_supported_version(config.max_supported_version)
+        .with_exclude_patterns(&resources.exclude_regexes);
+    let parser = match config.work_budget {
+        0 => parser,
+        units => parser.with_work_budget(units),
+    };
+    let parser = match resources.tx_cache {
+        Some(ref cache) => parser.with_cache(cache.clone()),
+        None => parser,
+    };
+    let parser = match resources.rpc_permits {
+        Some(ref permits) => parser.with_rpc_permits(permits.clone()),
+        None => parser,
+    };
+
+    match config.provider {
+        ProviderKind::Standard => parser,
+        kind => parser.with_enriched_provider(EnrichedProvider {
+            kind,
+            api_url: config.enhanced_api_url.clone(),
+            api_key: config.enhanced_api_key.clone().unwrap_or_default(),
+        }),
+    }
+}
+
+/// Build the response for a parsed transaction
+fn build_tx_response(
+    parser: &TxLogParser,
+    labels: &ProgramLabels,
+    include_cu_logs: bool,
+    include_labels: bool,
+) -> GetTxResponse {
+    // Get the parsed logs
+    let logs = parser.get_tx_logs();
+    let raw_logs = parser.get_raw_logs();
+    let programs_invoked = parser.get_programs_invoked();
+    let programs = programs_invoked
+        .iter()
+        .map(|program_id| program_id.to_string())
+        .collect();
+
+    // Build compute unit logs if requested
+    let (compute_units, cu_ranking) = if include_cu_logs {
+        let cu_logs = parser.get_compute_unit_logs();
+        (
+            compute_units_to_proto(&cu_logs, labels, include_labels),
+            cu_ranking_to_proto(&cu_logs, labels, include_labels),
+        )
+    } else {
+        (Vec::new(), Vec::new())
+    };
+
+    // Label the programs invoked if requested
+    let program_labels = if include_labels {
+        labels_for(labels, &programs_invoked)
+    } else {
+        HashMap::new()
+    };
+
+    let (top_level_instructions, inner_instructions) = parser.get_instruction_counts();
+
+    // Bucket raw logs by severity
+    let mut severity_buckets = parser.get_logs_by_severity();
+    let logs_by_severity = LogsBySeverity {
+        errors: severity_buckets.remove(&Severity::Error).unwrap_or_default(),
+        warnings: severity_buckets.remove(&Severity::Warning).unwrap_or_default(),
+        info: severity_buckets.remove(&Severity::Info).unwrap_or_default(),
+    };
+
+    GetTxResponse {
+        logs,
+        compute_units,
+        anchor_events: parser.parse_anchor_events().into_iter().map(anchor_event_to_proto).collect(),
+        raw_logs,
+        programs,
+        logs_by_severity: Some(logs_by_severity),
+        error: parser.get_tx_error().map(tx_error_to_proto),
+        truncated: parser.is_truncated(),
+        program_labels,
+        compute_units_consumed: parser.get_compute_units_consumed(),
+        compute_budget_explicit: parser.is_compute_budget_explicit(),
+        compute_unit_limit: parser.get_compute_budget().and_then(|budget| budget.unit_limit),
+        compute_unit_price: parser.get_compute_budget().and_then(|budget| budget.unit_price),
+        enriched: parser.get_enriched().map(enriched_to_proto),
+        top_level_instructions: top_level_instructions as u32,
+        inner_instructions: inner_instructions as u32,
+        cu_ranking,
+        decoded_instructions: parser
+            .get_decoded_instructions()
+            .into_iter()
+            .map(decoded_instruction_to_proto)
+            .collect(),
+        version: parser.get_version().unwrap_or_default(),
+        cost_breakdown: parser.get_cost_breakdown().map(cost_breakdown_to_proto),
+        account_access: parser
+            .get_account_access_summary()
+            .map(account_access_to_proto),
+        encoding: parser
+            .get_encoding()
+            .map(|encoding| encoding_label(encoding).to_string())
+            .unwrap_or_default(),
+        token_balances: parser
+            .get_token_balance_changes()
+            .into_iter()
+            .map(token_balance_to_proto)
+            .collect(),
+        slot: parser.get_slot().unwrap_or_default(),
+        has_logs: matches!(parser.get_log_source(), Some(LogSource::Logs | LogSource::Filtered | LogSource::NoProgramLogs)),
+        log_source: parser
+            .get_log_source()
+            .map(|source| source.as_str().to_string())
+            .unwrap_or_default(),
+        rewards: parser.get_rewards().into_iter().map(reward_to_proto).collect(),
+        limited: parser.is_limited(),
+        return_data: return_data_to_proto(parser),
+        success: matches!(parser.get_status(), Some(Ok(()))),
+        block_time: parser.get_block_time(),
+    }
+}
+
+fn compute_units_to_proto(
+    cu_logs: &ComputeUnitLogs,
+    labels: &ProgramLabels,
+    include_labels: bool,
+) -> Vec<ComputeUnitLog> {
+    cu_logs
+        .sorted()
+        .into_iter()
+        .map(|(program_id, cu_log)| ComputeUnitLog {
+            program_id: program_id.to_string(),
+            consumed: cu_log.consumed,
+            budget: cu_log.budget,
+            invocations: cu_log.invocations,
+            label: program_label(labels, program_id, include_labels),
+        })
+        .collect()
+}
+
+fn cu_ranking_to_proto(
+    cu_logs: &ComputeUnitLogs,
+    labels: &ProgramLabels,
+    include_labels: bool,
+) -> Vec<CuRank> {
+    cu_logs
+        .ranking()
+        .into_iter()
+        .map(|rank| CuRank {
+            program_id: rank.program_id.to_string(),
+            consumed: rank.consumed,
+            pct_of_total: rank.pct_of_total,
+            rank: rank.rank,
+            label: program_label(labels, &rank.program_id, include_labels),
+        })
+        .collect()
+}
+
+fn decoded_instruction_to_proto(decoded: DecodedInstruction) -> proto::DecodedInstruction {
+    proto::DecodedInstruction {
+        program: decoded.program,
+        name: decoded.name,
+        fields: decoded
+            .fields
+            .into_iter()
+            .map(|(name, value)| proto::DecodedField { name, value })
+            .collect(),
+    }
+}
+
+fn cost_breakdown_to_proto(cost: CostBreakdown) -> proto::CostBreakdown {
+    proto::CostBreakdown {
+        base_fee: cost.base_fee,
+        priority_fee: cost.priority_fee,
+        total_fee: cost.total_fee,
+        compute_units: cost.compute_units,
+        lamports_per_cu: cost.lamports_per_cu,
+    }
+}
+
+fn account_access_to_proto(access: AccountAccessSummary) -> proto::AccountAccess {
+    proto::AccountAccess {
+        writable: access.writable.iter().map(|key| key.to_string()).collect(),
+        readonly: access.readonly.iter().map(|key| key.to_string()).collect(),
+    }
+}
+
+fn token_balance_to_proto(change: TokenBalanceChange) -> proto::TokenBalanceChange {
+    proto::TokenBalanceChange {
+        account_index: change.account_index as u32,
+        account: change.account.unwrap_or_default(),
+        mint: change.mint,
+        owner: change.owner.unwrap_or_default(),
+        program_id: change.program_id.unwrap_or_default(),
+        decimals: change.decimals as u32,
+        pre_amount: change.pre_amount,
+        post_amount: change.post_amount,
+    }
+}
+
+fn reward_to_proto(reward: Reward) -> proto::Reward {
+    proto::Reward {
+        pubkey: reward.pubkey,
+        lamports: reward.lamports,
+        post_balance: reward.post_balance,
+        reward_type: reward.reward_type.unwrap_or_default(),
+        commission: reward.commission.map(u32::from),
+    }
+}
+
+/// Wrap a parsed transaction for the stream
+fn stream_response(
+    signature: &str,
+    slot: u64,
+    tx_response: GetTxResponse,
+) -> proto::StreamTransactionResponse {
+    proto::StreamTransactionResponse {
+        signature: signature.to_string(),
+        logs: tx_response.logs,
+        compute_units: tx_response.compute_units,
+        raw_logs: tx_response.raw_logs,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        programs: tx_response.programs,
+        slot,
+        status_update: None,
+        error: tx_response.error,
+        truncated: tx_response.truncated,
+        program_labels: tx_response.program_labels,
+        compute_units_consumed: tx_response.compute_units_consumed,
+        compute_budget_explicit: tx_response.compute_budget_explicit,
+        compute_unit_limit: tx_response.compute_unit_limit,
+        compute_unit_price: tx_response.compute_unit_price,
+        enriched: tx_response.enriched,
+        top_level_instructions: tx_response.top_level_instructions,
+        inner_instructions: tx_response.inner_instructions,
+        log_index: None,
+        log_line: String::new(),
+        cu_ranking: tx_response.cu_ranking,
+        decoded_instructions: tx_response.decoded_instructions,
+        version: tx_response.version,
+        cost_breakdown: tx_response.cost_breakdown,
+        account_access: tx_response.account_access,
+        encoding: tx_response.encoding,
+        token_balances: tx_response.token_balances,
+        has_logs: tx_response.has_logs,
+        log_source: tx_response.log_source,
+        rewards: tx_response.rewards,
+        limited: tx_response.limited,
+        anchor_events: tx_response.anchor_events,
+        return_data: tx_response.return_data,
+        success: tx_response.success,
+        block_time: tx_response.block_time,
+        logs_by_severity: tx_response.logs_by_severity,
+    }
+}
+
+/// One message per (filtered) program log line, for `flatten_logs`
+fn flatten_response(
+    signature: &str,
+    slot: u64,
+    tx_response: GetTxResponse,
+) -> Vec<proto::StreamTransactionResponse> {
+    let timestamp = chrono::Utc::now().to_rfc3339();
+
+    tx_response
+        .logs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, log_line)| proto::StreamTransactionResponse {
+            signature: signature.to_string(),
+            timestamp: timestamp.clone(),
+            slot,
+            log_index: Some(idx as u32),
+            log_line,
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn program_label(labels: &ProgramLabels, program_id: &Pubkey, include_labels: bool) -> String {
+    if include_labels {
+        labels.get(program_id).unwrap_or_default().to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Program id -> label for the labeled programs among `programs`
+fn labels_for(labels: &ProgramLabels, programs: &[Pubkey]) -> HashMap<String, String> {
+    programs
+        .iter()
+        .filter_map(|program_id| {
+            labels
+                .get(program_id)
+                .map(|label| (program_id.to_string(), label.to_string()))
+        })
+        .collect()
+}
+
+fn enriched_to_proto(enriched: EnrichedTransaction) -> proto::EnrichedTransaction {
+    proto::EnrichedTransaction {
+        transaction_type: enriched.transaction_type,
+        source: enriched.source,
+        description: enriched.description,
+        instructions: enriched
+            .instructions
+            .into_iter()
+            .map(|instruction| proto::EnrichedInstruction {
+                program_id: instruction.program_id,
+                inner_instructions: instruction.inner_instructions as u32,
+            })
+            .collect(),
+        transfers: enriched
+            .transfers
+            .into_iter()
+            .map(|transfer| proto::EnrichedTransfer {
+                from: transfer.from,
+                to: transfer.to,
+                amount: transfer.amount,
+                mint: transfer.mint.unwrap_or_default(),
+            })
+            .collect(),
+        events: enriched
+            .events
+            .into_iter()
+            .map(|event| proto::EnrichedEvent {
+                kind: event.kind,
+                json: event.json,
+            })
+            .collect(),
+    }
+}
+
+fn tx_error_to_proto(err: TxError) -> proto::TxError {
+    proto::TxError {
+        error: err.error,
+        instruction_index: err.instruction_index.map(u32::from),
+        program_id: err.program_id.map_or(String::new(), |program_id| program_id.to_string()),
+    }
+}
+
+/// Re-check a streamed signature: `finalized`, still only `confirmed`, or
+/// `dropped` if the cluster no longer knows about it
+async fn finalization_status(
+    resources: &ParserResources,
+    rpc_url: &str,
+    signature: &str,
+) -> Result<&'static str, String> {
+    let signature = Signature::from_str(signature).map_err(|e| e.to_string())?;
+    let rpc = rpc_client(resources, rpc_url).map_err(|e| e.to_string())?;
+
+    for (commitment, status) in [
+        (CommitmentConfig::finalized(), "finalized"),
+        (CommitmentConfig::confirmed(), "confirmed"),
+    ] {
+        let found = rpc
+            .get_signature_status_with_commitment(&signature, commitment)
+            .await
+            .map_err(|e| e.to_string())?;
+        if found.is_some() {
+            return Ok(status);
+        }
+    }
+
+    Ok("dropped")
+}
+
+/// Accept binary frame connections. Each client sends a program address
+/// followed by a newline and then receives one frame per transaction, see
+/// `odin::frame` for the layout.
+async fn serve_frames(listener: tokio::net::TcpListener, service: Arc<OdinService>) {
+    let shutdown = service.shutdown.clone();
+
+    loop {
+        let (socket, peer) = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    eprintln!("❌ Frame listener error: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        // Tracked like gRPC streams so shutdown waits for the client
+        let service = service.clone();
+        let streams = service.streams.clone();
+        streams.spawn(async move {
+            if let Err(e) = stream_frames(socket, service).await {
+                eprintln!("❌ Frame client {} closed: {}", peer, e);
+            }
+        });
+    }
+}
+
+/// Answer every connection with the current metrics in the Prometheus text
+/// format, whatever the request path
+async fn serve_metrics(listener: tokio::net::TcpListener, service: Arc<OdinService>) {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let shutdown = service.shutdown.clone();
+
+    loop {
+        let (mut socket, _) = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    eprintln!("❌ Metrics listener error: {}", e);
+                    continue;
+                }
+            },
+        };
+
+        let body = render_metrics(&service);
+        tokio::spawn(async move {
+            // The request itself doesn't matter, but read it so the client
+            // doesn't see a reset
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            let _ = socket.write_all(response.as_bytes()).await;
+            let _ = socket.shutdown().await;
+        });
+    }
+}
+
+fn render_metrics(service: &OdinService) -> String {
+    let states = service.breakers.states();
+    let mut out = String::new();
+
+    out.push_str("# HELP odin_rpc_circuit_state Circuit breaker state per RPC URL (0 closed, 1 half-open, 2 open)\n");
+    out.push_str("# TYPE odin_rpc_circuit_state gauge\n");
+    for (rpc_url, state, _) in states.iter() {
+        out.push_str(&format!("odin_rpc_circuit_state{{rpc_url={:?}}} {}\n", rpc_url, state.as_gauge()));
+    }
+
+    out.push_str("# HELP odin_rpc_consecutive_failures Consecutive upstream failures per RPC URL\n");
+    out.push_str("# TYPE odin_rpc_consecutive_failures gauge\n");
+    for (rpc_url, _, failures) in states.iter() {
+        out.push_str(&format!("odin_rpc_consecutive_failures{{rpc_url={:?}}} {}\n", rpc_url, failures));
+    }
+
+    out.push_str("# HELP odin_batch_concurrency GetTxLogsBatch signatures allowed in flight per RPC URL\n");
+    out.push_str("# TYPE odin_batch_concurrency gauge\n");
+    for (rpc_url, limit) in service.concurrency.limits() {
+        out.push_str(&format!("odin_batch_concurrency{{rpc_url={:?}}} {}\n", rpc_url, limit));
+    }
+
+    if let Some(ref permits) = service.resources.rpc_permits {
+        let limit = service.config.rpc_concurrency;
+        out.push_str("# HELP odin_rpc_calls_in_flight Upstream RPC calls holding a global concurrency permit\n");
+        out.push_str("# TYPE odin_rpc_calls_in_flight gauge\n");
+        out.push_str(&format!("odin_rpc_calls_in_flight {}\n", limit - permits.available_permits()));
+        out.push_str("# HELP odin_rpc_concurrency_limit Upstream RPC calls allowed in flight across the server\n");
+        out.push_str("# TYPE odin_rpc_concurrency_limit gauge\n");
+        out.push_str(&format!("odin_rpc_concurrency_limit {}\n", limit));
+    }
+
+    out
+}
+
+/// Stream frames for the program requested on one connection, reusing the
+/// gRPC stream pipeline
+async fn stream_frames(
+    socket: tokio::net::TcpStream,
+    service: Arc<OdinService>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    use futures_util::StreamExt;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+    let (read, mut write) = socket.into_split();
+    let mut program_address = String::new();
+    BufReader::new(read).read_line(&mut program_address).await?;
+
+    let request = Request::new(StreamProgramRequest {
+        program_address: program_address.trim().to_string(),
+        include_cu_logs: true,
+        ..Default::default()
+    });
+    let mut stream = service.stream_program_logs(request).await?.into_inner();
+
+    while let Some(response) = stream.next().await {
+        let response = response?;
+
+        // Follow-up status updates have no frame representation
+        if response.status_update.is_some() {
+            continue;
+        }
+
+        write.write_all(&frame_from_response(&response)?.encode()).await?;
+    }
+
+    Ok(())
+}
+
+fn frame_from_response(response: &proto::StreamTransactionResponse) -> Result<Frame, String> {
+    let signature = Signature::from_str(&response.signature)
+        .map_err(|_| format!("Invalid signature: {}", response.signature))?;
+
+    let compute_units = response
+        .compute_units
+        .iter()
+        .filter_map(|cu_log| {
+            Pubkey::from_str(&cu_log.program_id)
+                .ok()
+                .map(|program_id| FrameComputeUnits {
+                    program_id,
+                    consumed: cu_log.consumed,
+                    invocations: cu_log.invocations,
+                })
+        })
+        .collect();
+
+    Ok(Frame {
+        signature,
+        slot: response.slot,
+        failed: response.error.is_some(),
+        truncated: response.truncated,
+        compute_units_consumed: response.compute_units_consumed.unwrap_or_default(),
+        compute_units,
+    })
+}
+
+/// Bind a Unix domain socket, replacing a stale socket file left by a
+/// previous run
+#[cfg(unix)]
+fn uds_incoming(path: &str) -> std::io::Result<tokio_stream::wrappers::UnixListenerStream> {
+    if std::path::Path::new(path).exists() {
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = tokio::net::UnixListener::bind(path)?;
+    Ok(tokio_stream::wrappers::UnixListenerStream::new(listener))
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let config = ServerConfig::parse();
     let addr = DEFAULT_SERVER_ADDR.parse()?;
-    let service = OdinService::default();
+    let no_preflight = config.no_preflight;
+    let uds_path = config.uds.clone();
+    let frame_addr = config.frame_addr.clone();
+    let metrics_addr = config.metrics_addr.clone();
+    let health_interval = Duration::from_secs(config.health_interval);
+    let health_failure_threshold = Duration::from_secs(config.health_failure_threshold);
+    let service = Arc::new(OdinService::new(config)?);
+    let shutdown = service.shutdown.clone();
+    let streams = service.streams.clone();
+
+    // Preflight and health probes share the requests' client for the default RPC
+    let rpc = rpc_client(&service.resources, &service.config.rpc_url).map_err(|e| e.to_string())?;
+
+    if no_preflight {
+        println!("⏭️  Skipping RPC preflight");
+    } else if let Err(e) = preflight(&rpc).await {
+        eprintln!("❌ {}", e);
+        eprintln!("   Check connectivity to the RPC or start with --no-preflight");
+        return Err(e.into());
+    }
 
     println!("🚀 Odin gRPC Server starting on {}", addr);
-    println!("📡 Ready to serve transaction logs...");
 
-    Server::builder()
-        .add_service(SolanaTxLogServer::new(service))
-        .serve(addr)
-        .await?;
+    if let Some(ref frame_addr) = frame_addr {
+        let listener = tokio::net::TcpListener::bind(frame_addr).await?;
+        println!("🧱 Serving binary frames on {}", frame_addr);
+        tokio::spawn(serve_frames(listener, service.clone()));
+    }
+
+    if let Some(ref metrics_addr) = metrics_addr {
+        let listener = tokio::net::TcpListener::bind(metrics_addr).await?;
+        println!("📈 Serving metrics on http://{}/metrics", metrics_addr);
+        tokio::spawn(serve_metrics(listener, service.clone()));
+    }
+
+    // Standard gRPC health service, gated on the RPC's health
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    health_reporter
+        .set_serving::<SolanaTxLogServer<OdinService>>()
+        .await;
+
+    if health_interval.is_zero() {
+        println!("⏭️  RPC health probes disabled; readiness always SERVING");
+    } else {
+        tokio::spawn(watch_rpc_health(
+            health_reporter,
+            rpc,
+            health_interval,
+            health_failure_threshold,
+            shutdown.clone(),
+        ));
+    }
+
+    // Ctrl-C cancels `shutdown`: stream tasks unsubscribe and end, and the
+    // servers stop accepting and finish the requests in flight
+    tokio::spawn({
+        let shutdown = shutdown.clone();
+        let active_streams = service.active_streams.clone();
+        async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                println!(
+                    "🛑 Shutting down, closing {} active stream(s)...",
+                    active_streams.load(Ordering::Relaxed)
+                );
+                shutdown.cancel();
+            }
+        }
+    });
+
+    let tcp = Server::builder()
+        .add_service(health_service.clone())
+        .add_service(SolanaTxLogServer::from_arc(service.clone()))
+        .serve_with_shutdown(addr, shutdown.clone().cancelled_owned());
+
+    let result = match uds_path {
+        #[cfg(unix)]
+        Some(ref path) => {
+            let incoming = uds_incoming(path)?;
+            println!("🧦 Also listening on Unix socket {}", path);
+            println!("📡 Ready to serve transaction logs...");
+
+            let uds = Server::builder()
+                .add_service(health_service)
+                .add_service(SolanaTxLogServer::from_arc(service.clone()))
+                .serve_with_incoming_shutdown(incoming, shutdown.clone().cancelled_owned());
+
+            tokio::try_join!(tcp, uds).map(|_| ())
+        }
+        #[cfg(not(unix))]
+        Some(_) => return Err("Unix domain sockets are not supported on this platform".into()),
+        None => {
+            println!("📡 Ready to serve transaction logs...");
+            tcp.await
+        }
+    };
+
+    // Cancel any stream tasks still running and wait for them to unsubscribe
+    shutdown.cancel();
+    streams.close();
+    streams.wait().await;
+    println!("👋 Server stopped, all streams closed");
+
+    if let Some(ref path) = uds_path {
+        let _ = std::fs::remove_file(path);
+    }
+
+    result?;
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    fn test_service() -> OdinService {
+        OdinService::new(ServerConfig::parse_from(["odin-server"])).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_stream_tx_logs_batch_ends_with_full_progress() {
+        let service = test_service();
+        // Malformed signatures fail before any RPC call
+        let tx_sigs: Vec<String> = ["bad1", "bad2", "bad3"].iter().map(|sig| sig.to_string()).collect();
+        let request = Request::new(GetTxBatchRequest {
+            tx_sigs: tx_sigs.clone(),
+            ..Default::default()
+        });
+
+        let messages: Vec<BatchStreamMessage> = service
+            .stream_tx_logs_batch(request)
+            .await
+            .unwrap()
+            .into_inner()
+            .map(|message| message.unwrap())
+            .collect()
+            .await;
+
+        let mut results = Vec::new();
+        let mut last_processed = 0;
+        for (position, message) in messages.iter().enumerate() {
+            match message.message {
+                Some(batch_stream_message::Message::Result(ref result)) => {
+                    assert_eq!(result.tx_sig, tx_sigs[message.index as usize]);
+                    assert!(!result.error.is_empty());
+                    results.push(message.index);
+                }
+                Some(batch_stream_message::Message::Progress(ref progress)) => {
+                    assert_eq!(progress.total, 3);
+                    assert!(progress.processed >= last_processed);
+                    // Progress never runs ahead of the results sent so far
+                    assert_eq!(progress.processed as usize, results.len());
+                    last_processed = progress.processed;
+                    if position + 1 < messages.len() {
+                        assert!(progress.processed < progress.total);
+                    }
+                }
+                None => panic!("empty batch stream message"),
+            }
+        }
+
+        results.sort();
+        assert_eq!(results, vec![0, 1, 2]);
+        match messages.last().and_then(|message| message.message.as_ref()) {
+            Some(batch_stream_message::Message::Progress(progress)) => {
+                assert_eq!((progress.processed, progress.total), (3, 3));
+            }
+            other => panic!("expected a final progress message, got {:?}", other),
+        }
+    }
+}
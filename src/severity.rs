@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+/// Severity bucket a log line is classified into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// Keyword sets used to classify log lines by severity.
+///
+/// Matching is case-insensitive and by substring. A line matching both an
+/// error and a warning keyword is classified as an error; anything that
+/// matches neither set is info.
+#[derive(Debug, Clone)]
+pub struct SeverityKeywords {
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+}
+
+impl Default for SeverityKeywords {
+    fn default() -> Self {
+        SeverityKeywords {
+            errors: ["error", "failed", "panicked", "insufficient", "exceeded"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            warnings: ["warning", "warn:", "deprecated", "slippage"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl SeverityKeywords {
+    pub fn new(errors: Vec<String>, warnings: Vec<String>) -> Self {
+        SeverityKeywords { errors, warnings }
+    }
+
+    pub fn classify(&self, log: &str) -> Severity {
+        let log = log.to_lowercase();
+
+        if self.errors.iter().any(|k| log.contains(&k.to_lowercase())) {
+            Severity::Error
+        } else if self.warnings.iter().any(|k| log.contains(&k.to_lowercase())) {
+            Severity::Warning
+        } else {
+            Severity::Info
+        }
+    }
+
+    pub fn categorize(&self, logs: &[String]) -> HashMap<Severity, Vec<String>> {
+        let mut buckets: HashMap<Severity, Vec<String>> = HashMap::new();
+
+        for log in logs {
+            buckets
+                .entry(self.classify(log))
+                .or_default()
+                .push(log.clone());
+        }
+
+        buckets
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_categorize_default_keywords() {
+        let logs: Vec<String> = [
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Error: insufficient funds",
+            "Program log: Warning: price moved, slippage applied",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let buckets = SeverityKeywords::default().categorize(&logs);
+
+        assert_eq!(buckets[&Severity::Error].len(), 2);
+        assert_eq!(buckets[&Severity::Warning].len(), 1);
+        assert_eq!(buckets[&Severity::Info].len(), 1);
+    }
+
+    #[test]
+    fn test_custom_keywords_override_defaults() {
+        let keywords = SeverityKeywords::new(vec!["REVERT".to_string()], Vec::new());
+
+        assert_eq!(keywords.classify("Program log: revert: bad state"), Severity::Error);
+        assert_eq!(keywords.classify("Program log: Error: still info"), Severity::Info);
+    }
+}
@@ -0,0 +1,67 @@
+use tonic::{Code, Status};
+
+use crate::error::ParseError;
+
+/// Status for a failed parse; bad input such as a malformed signature is the
+/// caller's invalid argument, unsupported transaction versions are the
+/// caller's precondition, a rate-limited RPC is exhausted, a lagging node is
+/// unavailable (retryable), everything else (an RPC outage, undecodable
+/// data, a broken server setup) is internal
+pub fn parse_error_status(context: &str, e: &ParseError) -> Status {
+    match e {
+        ParseError::InvalidInput(msg) => Status::invalid_argument(msg.clone()),
+        ParseError::UnsupportedVersion(msg) => Status::failed_precondition(msg.clone()),
+        ParseError::RateLimited(_) => Status::resource_exhausted(format!("{}: {}", context, e)),
+        ParseError::NotFound(msg) => Status::not_found(msg.clone()),
+        ParseError::OutOfRange(msg) => Status::out_of_range(msg.clone()),
+        ParseError::NodeBehind(_) => Status::unavailable(format!("{}: {}", context, e)),
+        e => Status::internal(format!("{}: {}", context, e)),
+    }
+}
+
+/// Map a server status back to the parser error it was built from
+pub fn status_error(status: Status) -> ParseError {
+    let message = status.message().to_string();
+    match status.code() {
+        Code::InvalidArgument => ParseError::InvalidInput(message),
+        Code::NotFound => ParseError::NotFound(message),
+        Code::ResourceExhausted => ParseError::RateLimited(message),
+        Code::FailedPrecondition => ParseError::UnsupportedVersion(message),
+        Code::OutOfRange => ParseError::OutOfRange(message),
+        Code::Unavailable => ParseError::NodeBehind(message),
+        _ => ParseError::Rpc(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_round_trip() {
+        let errors = [
+            ParseError::InvalidInput("bad signature".to_string()),
+            ParseError::NotFound("gone".to_string()),
+            ParseError::RateLimited("slow down".to_string()),
+            ParseError::UnsupportedVersion("v1".to_string()),
+            ParseError::OutOfRange("slot 5".to_string()),
+            ParseError::NodeBehind("behind".to_string()),
+        ];
+        for e in errors {
+            let back = status_error(parse_error_status("ctx", &e));
+            assert_eq!(std::mem::discriminant(&back), std::mem::discriminant(&e));
+        }
+
+        let internal = parse_error_status("Failed", &ParseError::Decode("bad".to_string()));
+        assert_eq!(internal.code(), Code::Internal);
+        assert_eq!(internal.message(), "Failed: bad");
+        assert!(matches!(status_error(internal), ParseError::Rpc(_)));
+
+        let outage = parse_error_status("Failed", &ParseError::Rpc("timed out".to_string()));
+        assert_eq!(outage.code(), Code::Internal);
+
+        // A bad configured header is the server's problem, not the caller's
+        let config = parse_error_status("Failed", &ParseError::Config("bad header".to_string()));
+        assert_eq!(config.code(), Code::Internal);
+    }
+}
@@ -0,0 +1,390 @@
+use std::time::Duration;
+
+use futures_util::{Sink, SinkExt, Stream, StreamExt};
+use serde_json::{Value, json};
+use tokio::{sync::mpsc, time::Instant};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+use tokio_util::{sync::CancellationToken, task::TaskTracker};
+
+use crate::anchor::{AnchorEvent, Idl};
+use crate::backoff::Backoff;
+use crate::error::ParseError;
+use crate::output::ParsedTransaction;
+use crate::parser::TxLogParser;
+
+/// A `logsNotification` received for a `logsSubscribe` subscription
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogNotification {
+    pub signature: String,
+    pub slot: u64,
+    /// The notification reported a transaction error
+    pub failed: bool,
+    pub logs: Vec<String>,
+}
+
+impl LogNotification {
+    /// Read a `logsNotification` message; `None` for anything else
+    pub fn from_json(value: &Value) -> Option<Self> {
+        if value.get("method")?.as_str()? != "logsNotification" {
+            return None;
+        }
+
+        let result = value.pointer("/params/result")?;
+        let signature = result.pointer("/value/signature")?.as_str()?.to_string();
+
+        Some(LogNotification {
+            signature,
+            slot: result
+                .pointer("/context/slot")
+                .and_then(|slot| slot.as_u64())
+                .unwrap_or(0),
+            failed: result
+                .pointer("/value/err")
+                .is_some_and(|err| !err.is_null()),
+            logs: result
+                .pointer("/value/logs")
+                .and_then(|logs| logs.as_array())
+                .map(|logs| {
+                    logs.iter()
+                        .filter_map(|log| log.as_str())
+                        .map(|log| log.to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Whether any log line contains `filter` (case-insensitive)
+    pub fn logs_match(&self, filter: &str) -> bool {
+        let filter = filter.to_lowercase();
+        self.logs
+            .iter()
+            .any(|log| log.to_lowercase().contains(&filter))
+    }
+}
+
+/// How a subscription connects and recovers
+#[derive(Debug, Clone)]
+pub struct SubscribeOptions {
+    pub commitment: String,
+    /// Delays between reconnects after the WebSocket drops
+    pub backoff: Backoff,
+    /// Reconnects without a confirmed subscription before the stream ends
+    /// with an error
+    pub max_reconnect_attempts: u32,
+    /// Resubscribe after this long without a notification, in case the node
+    /// stopped delivering them without closing the socket. `None` waits
+    /// forever.
+    pub silence_timeout: Option<Duration>,
+    /// Cancel to unsubscribe and end the stream
+    pub cancel: CancellationToken,
+    /// Run the subscription task on this tracker, so whoever cancels can
+    /// also wait for the unsubscribe. `None` spawns it untracked.
+    pub tracker: Option<TaskTracker>,
+}
+
+impl Default for SubscribeOptions {
+    fn default() -> Self {
+        SubscribeOptions {
+            commitment: "confirmed".to_string(),
+            backoff: Backoff::default(),
+            max_reconnect_attempts: 10,
+            silence_timeout: None,
+            cancel: CancellationToken::new(),
+            tracker: None,
+        }
+    }
+}
+
+/// WebSocket URL of an HTTP(S) RPC URL
+pub fn ws_url_from_rpc(rpc_url: &str) -> String {
+    rpc_url
+        .replace("https://", "wss://")
+        .replace("http://", "ws://")
+}
+
+/// Subscribe to the logs of every transaction mentioning `program`. The
+/// WebSocket is reconnected and resubscribed when it drops; the stream ends
+/// when it is dropped, when `options.cancel` is cancelled, or with an error
+/// once reconnecting gives up. Must be called within a Tokio runtime.
+pub fn subscribe_logs(
+    ws_url: String,
+    program: String,
+    options: SubscribeOptions,
+) -> impl Stream<Item = Result<LogNotification, ParseError>> {
+    let (tx, rx) = mpsc::channel(128);
+    match options.tracker.clone() {
+        Some(tracker) => tracker.spawn(run_subscription(ws_url, program, options, tx)),
+        None => tokio::spawn(run_subscription(ws_url, program, options, tx)),
+    };
+    ReceiverStream::new(rx)
+}
+
+/// Subscribe to `program` and parse each transaction mentioning it with
+/// compute unit logs, keeping the program logs that match `filter`. Parsing
+/// happens one transaction at a time; a failed parse is yielded as an error
+/// and the stream goes on. Pin the stream (e.g. with `Box::pin`) to poll it.
+pub fn subscribe_program_logs(
+    ws_url: &str,
+    rpc_url: &str,
+    program: &str,
+    filter: Option<&str>,
+) -> impl Stream<Item = Result<ParsedTransaction, ParseError>> {
+    let rpc_url = rpc_url.to_string();
+    let filter = filter.map(|filter| filter.to_string());
+
+    subscribe_logs(
+        ws_url.to_string(),
+        program.to_string(),
+        SubscribeOptions::default(),
+    )
+    .then(move |notification| {
+        let rpc_url = rpc_url.clone();
+        let filter = filter.clone();
+
+        async move {
+            let notification = notification?;
+            let mut parser =
+                TxLogParser::new(rpc_url, notification.signature, filter.as_deref(), true);
+            parser.parse().await?;
+            Ok(ParsedTransaction::from_parser(&parser))
+        }
+    })
+}
+
+/// Subscribe to `program` and yield only the Anchor events it emits,
+/// decoded with `idl`. Events are read straight from the log notifications,
+/// so no transaction is fetched; failed transactions are skipped since
+/// their events never took effect. An error is yielded only when
+/// reconnecting gives up.
+pub fn subscribe_program_events(
+    ws_url: &str,
+    program: &str,
+    idl: Idl,
+) -> impl Stream<Item = Result<AnchorEvent, ParseError>> {
+    let program = program.to_string();
+
+    subscribe_logs(
+        ws_url.to_string(),
+        program.clone(),
+        SubscribeOptions::default(),
+    )
+    .flat_map(move |notification| {
+        let events = match notification {
+            Ok(notification) if notification.failed => Vec::new(),
+            Ok(notification) => idl
+                .events_from_logs(&program, &notification.logs)
+                .into_iter()
+                .map(Ok)
+                .collect(),
+            Err(e) => vec![Err(e)],
+        };
+        futures_util::stream::iter(events)
+    })
+}
+
+async fn run_subscription(
+    ws_url: String,
+    program: String,
+    options: SubscribeOptions,
+    tx: mpsc::Sender<Result<LogNotification, ParseError>>,
+) {
+    let SubscribeOptions {
+        commitment,
+        backoff,
+        max_reconnect_attempts,
+        silence_timeout,
+        cancel,
+        ..
+    } = options;
+
+    // `attempt` counts reconnects since the last confirmed subscription
+    let mut retrying = false;
+    let mut attempt: u32 = 0;
+
+    loop {
+        if retrying {
+            if attempt >= max_reconnect_attempts {
+                eprintln!(
+                    "❌ Giving up on WebSocket after {} reconnect attempts",
+                    attempt
+                );
+                let _ = tx
+                    .send(Err(ParseError::Rpc(format!(
+                        "WebSocket {} unavailable after {} reconnect attempts",
+                        ws_url, attempt
+                    ))))
+                    .await;
+                return;
+            }
+
+            let delay = backoff.delay(attempt);
+            attempt += 1;
+            println!(
+                "🔁 Reconnecting to WebSocket in {} ms (attempt {})",
+                delay.as_millis(),
+                attempt
+            );
+
+            tokio::select! {
+                _ = cancel.cancelled() => return,
+                _ = tx.closed() => return,
+                _ = tokio::time::sleep(delay) => {}
+            }
+        }
+        retrying = true;
+
+        let ws_stream = match connect_async(ws_url.as_str()).await {
+            Ok((stream, _)) => stream,
+            Err(e) => {
+                eprintln!("❌ WebSocket connection failed: {}", e);
+                continue;
+            }
+        };
+
+        let (mut write, mut read) = ws_stream.split();
+
+        let subscribe_msg = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "logsSubscribe",
+            "params": [
+                {
+                    "mentions": [program]
+                },
+                {
+                    "commitment": commitment
+                }
+            ]
+        });
+
+        if let Err(e) = write.send(Message::Text(subscribe_msg.to_string())).await {
+            eprintln!("❌ Failed to send subscription: {}", e);
+            continue;
+        }
+
+        println!("✅ Subscribed successfully!");
+
+        // Subscription id returned by the node, needed for logsUnsubscribe
+        let mut subscription_id: Option<u64> = None;
+        // Pings and other traffic don't count: only notifications prove the
+        // subscription still works
+        let mut last_notification = Instant::now();
+
+        loop {
+            let silence = async {
+                match silence_timeout {
+                    Some(timeout) => tokio::time::sleep_until(last_notification + timeout).await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            let msg = tokio::select! {
+                _ = cancel.cancelled() => {
+                    unsubscribe(&mut write, subscription_id).await;
+                    println!("🛑 Subscription cancelled");
+                    return;
+                }
+                _ = tx.closed() => {
+                    unsubscribe(&mut write, subscription_id).await;
+                    return;
+                }
+                _ = silence => {
+                    eprintln!(
+                        "🔇 No notifications for {} s, resubscribing",
+                        silence_timeout.unwrap_or_default().as_secs()
+                    );
+                    unsubscribe(&mut write, subscription_id).await;
+                    break;
+                }
+                msg = read.next() => msg,
+            };
+
+            let Some(msg) = msg else {
+                break;
+            };
+
+            match msg {
+                Ok(Message::Text(text)) => {
+                    let Ok(value) = serde_json::from_str::<Value>(&text) else {
+                        continue;
+                    };
+
+                    // Remember the subscription id; a confirmed subscription
+                    // resets the backoff
+                    if value.get("id").and_then(|id| id.as_u64()) == Some(1) {
+                        subscription_id = value.get("result").and_then(|r| r.as_u64());
+                        attempt = 0;
+                    }
+
+                    if let Some(notification) = LogNotification::from_json(&value) {
+                        last_notification = Instant::now();
+                        if tx.send(Ok(notification)).await.is_err() {
+                            unsubscribe(&mut write, subscription_id).await;
+                            return;
+                        }
+                    }
+                }
+                Ok(Message::Close(_)) => {
+                    println!("🔌 WebSocket closed");
+                    break;
+                }
+                Err(e) => {
+                    eprintln!("❌ WebSocket error: {}", e);
+                    break;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Send `logsUnsubscribe` for a confirmed subscription and close the socket
+async fn unsubscribe<S>(write: &mut S, subscription_id: Option<u64>)
+where
+    S: Sink<Message> + Unpin,
+{
+    if let Some(id) = subscription_id {
+        let unsubscribe_msg = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "logsUnsubscribe",
+            "params": [id]
+        });
+        let _ = write.send(Message::Text(unsubscribe_msg.to_string())).await;
+    }
+    let _ = write.close().await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_notification_from_json() {
+        let value = json!({
+            "jsonrpc": "2.0",
+            "method": "logsNotification",
+            "params": {
+                "result": {
+                    "context": {"slot": 5208469},
+                    "value": {
+                        "signature": "5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv",
+                        "err": {"InstructionError": [0, {"Custom": 1}]},
+                        "logs": ["Program log: Instruction: Transfer"]
+                    }
+                },
+                "subscription": 24040
+            }
+        });
+
+        let notification = LogNotification::from_json(&value).unwrap();
+
+        assert_eq!(notification.slot, 5208469);
+        assert!(notification.failed);
+        assert!(notification.logs_match("transfer"));
+        assert!(!notification.logs_match("swap"));
+        assert!(LogNotification::from_json(&json!({"id": 1, "result": 24040})).is_none());
+    }
+}
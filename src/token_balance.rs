@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+
+use solana_transaction_status_client_types::{
+    UiTransactionStatusMeta, UiTransactionTokenBalance, option_serializer::OptionSerializer,
+};
+
+/// Token program of classic SPL tokens
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// Token program of Token-2022 (token extensions) mints
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// A token account's balance before and after the transaction, from the
+/// meta's `preTokenBalances` and `postTokenBalances`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+    /// Index of the token account in the transaction's account keys
+    pub account_index: u8,
+    /// Token account address, when the account keys are known
+    pub account: Option<String>,
+    pub mint: String,
+    /// Wallet owning the token account, when the RPC reports it
+    pub owner: Option<String>,
+    /// Token program owning the mint (classic SPL or Token-2022), when the
+    /// RPC reports it
+    pub program_id: Option<String>,
+    pub decimals: u8,
+    /// Raw amounts in base units; 0 for accounts created or closed by the
+    /// transaction
+    pub pre_amount: u64,
+    pub post_amount: u64,
+}
+
+impl TokenBalanceChange {
+    /// `post_amount - pre_amount` in base units
+    pub fn change(&self) -> i128 {
+        self.post_amount as i128 - self.pre_amount as i128
+    }
+
+    pub fn is_token_2022(&self) -> bool {
+        self.program_id.as_deref() == Some(TOKEN_2022_PROGRAM_ID)
+    }
+}
+
+/// Pair the pre and post token balances of a transaction by account,
+/// ordered by account index. `account_keys` are the transaction's keys,
+/// addresses loaded from lookup tables included, used to name the accounts.
+pub fn token_balance_changes(
+    meta: &UiTransactionStatusMeta,
+    account_keys: &[String],
+) -> Vec<TokenBalanceChange> {
+    let balances = |balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>| match balances {
+        OptionSerializer::Some(balances) => balances.clone(),
+        _ => Vec::new(),
+    };
+
+    let mut changes: BTreeMap<u8, TokenBalanceChange> = BTreeMap::new();
+    for (balance, is_post) in balances(&meta.pre_token_balances)
+        .into_iter()
+        .map(|balance| (balance, false))
+        .chain(
+            balances(&meta.post_token_balances)
+                .into_iter()
+                .map(|balance| (balance, true)),
+        )
+    {
+        let change = changes
+            .entry(balance.account_index)
+            .or_insert_with(|| TokenBalanceChange {
+                account_index: balance.account_index,
+                account: account_keys.get(balance.account_index as usize).cloned(),
+                ..Default::default()
+            });
+
+        // Post balances win for the descriptive fields; an account closed
+        // by the transaction only has a pre balance
+        change.mint = balance.mint;
+        change.decimals = balance.ui_token_amount.decimals;
+        if let OptionSerializer::Some(owner) = balance.owner {
+            change.owner = Some(owner);
+        }
+        if let OptionSerializer::Some(program_id) = balance.program_id {
+            change.program_id = Some(program_id);
+        }
+
+        let amount = balance.ui_token_amount.amount.parse().unwrap_or_default();
+        if is_post {
+            change.post_amount = amount;
+        } else {
+            change.pre_amount = amount;
+        }
+    }
+
+    changes.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_token_balance_changes() {
+        let meta: UiTransactionStatusMeta = serde_json::from_value(json!({
+            "err": null,
+            "status": {"Ok": null},
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "preTokenBalances": [
+                {
+                    "accountIndex": 1,
+                    "mint": "mintA",
+                    "owner": "alice",
+                    "programId": TOKEN_2022_PROGRAM_ID,
+                    "uiTokenAmount": {"uiAmount": 1.5, "decimals": 6, "amount": "1500000", "uiAmountString": "1.5"}
+                },
+                {
+                    "accountIndex": 3,
+                    "mint": "mintB",
+                    "owner": "bob",
+                    "programId": TOKEN_PROGRAM_ID,
+                    "uiTokenAmount": {"uiAmount": 2.0, "decimals": 0, "amount": "2", "uiAmountString": "2"}
+                }
+            ],
+            "postTokenBalances": [
+                {
+                    "accountIndex": 1,
+                    "mint": "mintA",
+                    "owner": "alice",
+                    "programId": TOKEN_2022_PROGRAM_ID,
+                    "uiTokenAmount": {"uiAmount": 0.5, "decimals": 6, "amount": "500000", "uiAmountString": "0.5"}
+                },
+                {
+                    "accountIndex": 2,
+                    "mint": "mintA",
+                    "owner": "carol",
+                    "programId": TOKEN_2022_PROGRAM_ID,
+                    "uiTokenAmount": {"uiAmount": 1.0, "decimals": 6, "amount": "1000000", "uiAmountString": "1"}
+                }
+            ]
+        }))
+        .unwrap();
+        let keys: Vec<String> = ["payer", "aliceAta", "carolAta"]
+            .iter()
+            .map(|key| key.to_string())
+            .collect();
+
+        let changes = token_balance_changes(&meta, &keys);
+
+        assert_eq!(changes.len(), 3);
+        assert_eq!(changes[0].account.as_deref(), Some("aliceAta"));
+        assert_eq!(changes[0].change(), -1_000_000);
+        assert!(changes[0].is_token_2022());
+        assert_eq!(changes[1].owner.as_deref(), Some("carol"));
+        assert_eq!(
+            (changes[1].pre_amount, changes[1].post_amount),
+            (0, 1_000_000)
+        );
+        // Closed account, and no key at index 3
+        assert_eq!(changes[2].account, None);
+        assert_eq!(changes[2].change(), -2);
+        assert!(!changes[2].is_token_2022());
+    }
+}
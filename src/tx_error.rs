@@ -0,0 +1,108 @@
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::invoke::LogEvent;
+
+/// Where and why a transaction failed, decoded from `meta.err`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxError {
+    /// Decoded error, e.g. `Custom(6001)` or `InsufficientFundsForFee`
+    pub error: String,
+    /// Index of the top-level instruction that failed, for `InstructionError`s
+    pub instruction_index: Option<u8>,
+    /// Program invoked by the failing instruction
+    pub program_id: Option<Pubkey>,
+}
+
+impl TxError {
+    /// Decode the JSON form of a transaction error. An `InstructionError`
+    /// looks like `{"InstructionError":[1,{"Custom":6001}]}`; its index is
+    /// correlated with the program of the matching `invoke [1]` log line.
+    pub fn from_json(err: &Value, logs: &[String]) -> Self {
+        let instruction_error = err
+            .get("InstructionError")
+            .and_then(|e| e.as_array())
+            .filter(|e| e.len() == 2);
+
+        match instruction_error {
+            Some(parts) => {
+                let instruction_index = parts[0].as_u64().and_then(|idx| u8::try_from(idx).ok());
+                TxError {
+                    error: describe(&parts[1]),
+                    instruction_index,
+                    program_id: instruction_index.and_then(|idx| top_level_program(logs, idx)),
+                }
+            }
+            None => TxError {
+                error: describe(err),
+                instruction_index: None,
+                program_id: None,
+            },
+        }
+    }
+}
+
+/// Render an error variant as `Name` or `Name(detail)`
+fn describe(err: &Value) -> String {
+    match err {
+        Value::String(name) => name.clone(),
+        Value::Object(map) if map.len() == 1 => {
+            let (name, detail) = map.iter().next().unwrap();
+            match detail {
+                Value::Null => name.clone(),
+                Value::String(detail) => format!("{}({})", name, detail),
+                detail => format!("{}({})", name, detail),
+            }
+        }
+        other => other.to_string(),
+    }
+}
+
+/// Program invoked by the `idx`-th top-level instruction
+fn top_level_program(logs: &[String], idx: u8) -> Option<Pubkey> {
+    logs.iter()
+        .filter_map(|log| match LogEvent::parse(log) {
+            LogEvent::Invoke { program_id, depth: 1 } => Some(program_id),
+            _ => None,
+        })
+        .nth(idx as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_instruction_error_correlates_program() {
+        let logs: Vec<String> = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]",
+            "Program ComputeBudget111111111111111111111111111111 success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [1]",
+            "Program log: Error: insufficient funds",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA failed: custom program error: 0x1",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let err = TxError::from_json(&json!({"InstructionError": [1, {"Custom": 1}]}), &logs);
+
+        assert_eq!(err.error, "Custom(1)");
+        assert_eq!(err.instruction_index, Some(1));
+        assert_eq!(
+            err.program_id,
+            Some(Pubkey::from_str("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_transaction_level_error() {
+        let err = TxError::from_json(&json!("AccountNotFound"), &[]);
+
+        assert_eq!(err.error, "AccountNotFound");
+        assert_eq!(err.instruction_index, None);
+        assert_eq!(err.program_id, None);
+    }
+}
@@ -0,0 +1,190 @@
+//! End-to-end test of the gRPC server against a local `solana-test-validator`.
+//!
+//! Skipped unless `ODIN_E2E=1`. Needs `solana-test-validator` on the PATH and
+//! the ports it and the server use by default (8899, 8900 and 50051) free.
+//! The validator ships with the SPL Memo program, which stands in for a tiny
+//! logging program: every memo is echoed as a `Program log:` line.
+
+use std::process::{Child, Command, Stdio};
+use std::time::{Duration, Instant};
+
+use solana_commitment_config::CommitmentConfig;
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    transaction::Transaction,
+};
+
+pub mod proto {
+    tonic::include_proto!("odin");
+}
+
+use proto::solana_tx_log_client::SolanaTxLogClient;
+use proto::{GetTxRequest, StreamProgramRequest};
+
+const RPC_URL: &str = "http://127.0.0.1:8899";
+const WS_URL: &str = "ws://127.0.0.1:8900";
+const SERVER_URL: &str = "http://[::1]:50051";
+const MEMO_PROGRAM_ID: &str = "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr";
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Child processes killed when the test ends, pass or fail
+struct Processes(Vec<Child>);
+
+impl Drop for Processes {
+    fn drop(&mut self) {
+        for child in self.0.iter_mut() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_get_tx_logs_and_stream_against_test_validator() {
+    if std::env::var("ODIN_E2E").as_deref() != Ok("1") {
+        eprintln!("skipping: set ODIN_E2E=1 to run against solana-test-validator");
+        return;
+    }
+
+    let ledger = std::env::temp_dir().join(format!("odin-e2e-{}", std::process::id()));
+    let mut processes = Processes(Vec::new());
+
+    processes.0.push(
+        Command::new("solana-test-validator")
+            .arg("--reset")
+            .arg("--quiet")
+            .arg("--ledger")
+            .arg(&ledger)
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("solana-test-validator must be on the PATH"),
+    );
+
+    let rpc = RpcClient::new_with_commitment(RPC_URL.to_string(), CommitmentConfig::confirmed());
+    let started = Instant::now();
+    while rpc.get_health().await.is_err() {
+        assert!(
+            started.elapsed() < STARTUP_TIMEOUT,
+            "validator didn't become healthy"
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    processes.0.push(
+        Command::new(env!("CARGO_BIN_EXE_odin-server"))
+            .args(["--no-preflight", "--health-interval", "0"])
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("failed to start odin-server"),
+    );
+
+    let started = Instant::now();
+    let mut client = loop {
+        match SolanaTxLogClient::connect(SERVER_URL).await {
+            Ok(client) => break client,
+            Err(e) => {
+                assert!(
+                    started.elapsed() < STARTUP_TIMEOUT,
+                    "server didn't start: {}",
+                    e
+                );
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        }
+    };
+
+    let payer = Keypair::new();
+    let airdrop = rpc
+        .request_airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)
+        .await
+        .unwrap();
+    let started = Instant::now();
+    while !rpc.confirm_transaction(&airdrop).await.unwrap() {
+        assert!(
+            started.elapsed() < STARTUP_TIMEOUT,
+            "airdrop wasn't confirmed"
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    // Subscribe before submitting, and give the server a moment to confirm
+    // the subscription with the validator
+    let mut stream = client
+        .stream_program_logs(StreamProgramRequest {
+            rpc_url: RPC_URL.to_string(),
+            ws_url: WS_URL.to_string(),
+            program_address: MEMO_PROGRAM_ID.to_string(),
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    let signature = send_memo(&rpc, &payer, "odin e2e").await;
+
+    // Unary: the memo comes back as a program log
+    let response = client
+        .get_tx_logs(GetTxRequest {
+            rpc_url: RPC_URL.to_string(),
+            tx_sig: signature.to_string(),
+            include_cu_logs: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap()
+        .into_inner();
+
+    assert!(
+        response.logs.iter().any(|log| log.contains("odin e2e")),
+        "memo missing from logs: {:?}",
+        response.logs
+    );
+    assert!(response.programs.contains(&MEMO_PROGRAM_ID.to_string()));
+    assert!(response.error.is_none());
+    assert!(
+        response
+            .compute_units_consumed
+            .is_some_and(|consumed| consumed > 0)
+    );
+
+    // Streaming: the same transaction arrives on the subscription
+    let streamed = tokio::time::timeout(Duration::from_secs(30), stream.message())
+        .await
+        .expect("no streamed transaction within 30s")
+        .unwrap()
+        .expect("stream ended");
+
+    assert_eq!(streamed.signature, signature.to_string());
+    assert!(
+        streamed.logs.iter().any(|log| log.contains("odin e2e")),
+        "memo missing from streamed logs: {:?}",
+        streamed.logs
+    );
+
+    drop(processes);
+    let _ = std::fs::remove_dir_all(&ledger);
+}
+
+async fn send_memo(rpc: &RpcClient, payer: &Keypair, memo: &str) -> Signature {
+    let instruction = Instruction::new_with_bytes(
+        MEMO_PROGRAM_ID.parse::<Pubkey>().unwrap(),
+        memo.as_bytes(),
+        vec![AccountMeta::new_readonly(payer.pubkey(), true)],
+    );
+    let blockhash = rpc.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&payer.pubkey()),
+        &[payer],
+        blockhash,
+    );
+
+    rpc.send_and_confirm_transaction(&transaction)
+        .await
+        .unwrap()
+}